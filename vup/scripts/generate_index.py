@@ -2,11 +2,16 @@
 """
 Generate the package index for VUP.
 Creates public/index.json with package metadata and URLs for both GitHub releases and R2.
+
+Each package's "version" is the full xbps "<version>_<revision>" (as in a
+pkgver), so vuru can compare it directly with the installed version and a
+revision-only bump (1.2.3_1 -> 1.2.3_2) is seen as an upgrade.
 """
 
 import json
 import os
 import re
+import subprocess
 
 # Import shared config
 try:
@@ -55,6 +60,48 @@ def parse_template(template_path):
     return version, revision
 
 
+def parse_suggests(template_path):
+    """
+    Returns the optional companion packages listed in a template's
+    'suggests="pkg1 pkg2"' line (vuru offers them after install).
+    """
+    with open(template_path, "r") as f:
+        match = re.search(r"^suggests=([^#\n]+)", f.read(), re.MULTILINE)
+
+    if not match:
+        return []
+    return match.group(1).strip().strip("\"'").split()
+
+
+def parse_license(template_path):
+    """
+    Returns a template's license="..." value, or None if it has none.
+    """
+    with open(template_path, "r") as f:
+        match = re.search(r"^license=([^#\n]+)", f.read(), re.MULTILINE)
+
+    if not match:
+        return None
+    return match.group(1).strip().strip("\"'") or None
+
+
+def template_updated_at(template_path):
+    """
+    Returns the date (YYYY-MM-DD) of the last commit touching a template,
+    or None outside a git checkout (vuru --new-since treats it as unknown).
+    """
+    try:
+        result = subprocess.run(
+            ["git", "log", "-1", "--format=%cs", "--", template_path],
+            capture_output=True,
+            text=True,
+            check=True,
+        )
+    except (OSError, subprocess.CalledProcessError):
+        return None
+    return result.stdout.strip() or None
+
+
 def generate_index():
     index = {
         "_meta": {
@@ -111,12 +158,34 @@ def generate_index():
                     tag = f"{category}-{arch}-current"
                     repo_urls[arch] = f"{BASE_URL}/{tag}"
 
-                index["packages"][pkg] = {
+                entry = {
                     "category": category,
                     "version": full_version,
                     "archs": archs,
                     "repo_urls": repo_urls,
                 }
+
+                updated_at = template_updated_at(template_path)
+                if updated_at:
+                    entry["updated_at"] = updated_at
+
+                pkg_license = parse_license(template_path)
+                if pkg_license:
+                    entry["license"] = pkg_license
+
+                suggests = parse_suggests(template_path)
+                if suggests:
+                    entry["suggests"] = suggests
+
+                # Subpackages are symlinks to their source package directory;
+                # vuru needs the real directory to fetch the template.
+                pkg_dir = os.path.join(cat_dir, pkg)
+                if os.path.islink(pkg_dir):
+                    source_pkg = os.path.basename(os.path.realpath(pkg_dir))
+                    if source_pkg != pkg:
+                        entry["source_pkg"] = source_pkg
+
+                index["packages"][pkg] = entry
                 print(
                     f"Indexed: {pkg} -> {category} ({full_version}) [{', '.join(archs)}]"
                 )
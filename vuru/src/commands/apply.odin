@@ -0,0 +1,220 @@
+package commands
+
+import "core:fmt"
+import "core:slice"
+import "core:strings"
+
+import errors "../core/errors"
+import index "../core/index"
+import pins "../core/pins"
+import resolve "../core/resolve"
+import transaction "../core/transaction"
+import xbps "../core/xbps"
+import utils "../utils"
+
+// Apply command - bring the system in line with a manifest (see manifest.odin):
+// show how it differs from what's installed, then install what's missing.
+// Installed packages at another version than the manifest pins are only
+// reported; apply never upgrades, downgrades or removes anything.
+apply_run :: proc(args: []string, config: ^Config) -> int {
+	if len(args) != 1 {
+		fmt.println("Usage: vuru apply <manifest.toml>")
+		return 1
+	}
+
+	manifest, ok := manifest_read(args[0])
+	if !ok {
+		return 1
+	}
+	if len(manifest.packages) == 0 {
+		errors.log_info("%s lists no packages", args[0])
+		return 0
+	}
+
+	installed_list, installed_ok := xbps.list_installed(utils.run_command_output, context.temp_allocator)
+	if !installed_ok {
+		errors.log_error("Failed to run xbps-query")
+		return errors.EXIT_XBPS_FAILED
+	}
+	installed := make(map[string]string, context.temp_allocator)
+	for p in installed_list {
+		installed[p[0]] = p[1]
+	}
+
+	missing := make([dynamic]Manifest_Package, context.temp_allocator)
+	differs := 0
+	for pkg in manifest.packages {
+		version, is_installed := installed[pkg.name]
+		status := "installed"
+		switch {
+		case !is_installed:
+			status = "install"
+			append(&missing, pkg)
+		case len(pkg.version) > 0 && version != pkg.version:
+			status = "differs"
+			differs += 1
+		}
+
+		if config.porcelain {
+			fmt.printf("%s\t%s\n", pkg.name, status)
+			continue
+		}
+
+		switch status {
+		case "install":
+			fmt.printf("  %s+%s %s\n", errors.COLOR_GREEN, errors.COLOR_RESET, manifest_package_label(pkg))
+		case "differs":
+			fmt.printf(
+				"  %s~%s %s (installed %s, manifest %s)\n",
+				errors.COLOR_YELLOW,
+				errors.COLOR_RESET,
+				pkg.name,
+				version,
+				pkg.version,
+			)
+		case:
+			if config.verbose {
+				fmt.printf("  = %s %s\n", pkg.name, version)
+			}
+		}
+	}
+
+	if !config.porcelain {
+		fmt.println()
+		fmt.printf(
+			"%d to install, %d already installed, %d at another version\n",
+			len(missing),
+			len(manifest.packages) - len(missing) - differs,
+			differs,
+		)
+	}
+	if differs > 0 {
+		errors.log_warning("apply doesn't change installed versions; use 'vuru update' or reinstall those packages")
+	}
+
+	if config.dry_run {
+		return 0
+	}
+
+	if len(missing) > 0 {
+		tx, tx_ret := apply_transaction(missing[:], config)
+		if tx_ret != 0 {
+			return tx_ret
+		}
+
+		transaction.transaction_print(&tx)
+		ret, installed := install_transaction(&tx, config)
+		if ret != 0 || !installed {
+			return ret
+		}
+	} else {
+		errors.log_info("Nothing to install")
+	}
+
+	if !apply_pins(manifest) {
+		return 1
+	}
+	return 0
+}
+
+// Plan the install of the manifest packages that are missing. Packages with a
+// repo go straight to that repo (xbps pulls in their dependencies); the rest
+// are resolved like `vuru install` does. Versions become exact pkgvers.
+@(private)
+apply_transaction :: proc(missing: []Manifest_Package, config: ^Config) -> (transaction.Transaction, int) {
+	tx: transaction.Transaction
+
+	names := make([dynamic]string, context.temp_allocator)
+	for pkg in missing {
+		if len(pkg.repo) == 0 {
+			append(&names, pkg.name)
+		}
+	}
+
+	if len(names) > 0 {
+		idx, idx_ok := index.index_load_or_fetch(config.index_url, false)
+		if !idx_ok {
+			errors.log_error("Failed to load package index")
+			return tx, errors.EXIT_INDEX_FAILED
+		}
+
+		res, res_ok := resolve.resolve_deps(names[:], &idx, config.force_build, template_source(config))
+		if !res_ok || len(res.missing) > 0 {
+			for err in res.errors {
+				errors.print_error(err)
+			}
+			if len(res.errors) > 0 {
+				return tx, errors.exit_code(res.errors[0].kind)
+			}
+			errors.log_error("Failed to resolve dependencies")
+			return tx, 1
+		}
+		tx = transaction.transaction_from_resolution(&res)
+	} else {
+		tx = transaction.transaction_make()
+	}
+
+	for pkg in missing {
+		if len(pkg.repo) > 0 {
+			append(&tx.items, transaction.Transaction_Item {
+				op          = .Install_VUP,
+				name        = strings.clone(pkg.name, tx.allocator),
+				new_version = strings.clone(pkg.version, tx.allocator),
+				repo_url    = strings.clone(pkg.repo, tx.allocator),
+				reason      = strings.clone("explicit", tx.allocator),
+				exact       = len(pkg.version) > 0,
+			})
+			continue
+		}
+		if len(pkg.version) == 0 {
+			continue
+		}
+
+		for &item in tx.items {
+			if item.name != pkg.name {
+				continue
+			}
+			if item.op == .Build_Install && item.new_version != pkg.version {
+				errors.log_error(
+					"%s can only be built at %s (the template version), not %s",
+					pkg.name,
+					item.new_version,
+					pkg.version,
+				)
+				return tx, 1
+			}
+			item.new_version = strings.clone(pkg.version, tx.allocator)
+			item.exact = true
+		}
+	}
+
+	return tx, 0
+}
+
+// Add the manifest packages marked pin = true to the pin list
+@(private)
+apply_pins :: proc(manifest: Manifest) -> bool {
+	pinned, ok := pins.pins_load(context.temp_allocator)
+	if !ok {
+		return false
+	}
+
+	updated := make([dynamic]string, context.temp_allocator)
+	append(&updated, ..pinned)
+	for pkg in manifest.packages {
+		if pkg.pin && !slice.contains(updated[:], pkg.name) {
+			append(&updated, pkg.name)
+			errors.log_info("Pinned %s", pkg.name)
+		}
+	}
+	if len(updated) == len(pinned) {
+		return true
+	}
+
+	slice.sort(updated[:])
+	if !pins.pins_save(updated[:]) {
+		errors.log_error("Failed to save pin list")
+		return false
+	}
+	return true
+}
@@ -13,11 +13,18 @@ build_run :: proc(args: []string, config: ^Config) -> int {
 		return 1
 	}
 
+	args := args
+	names, names_ok := normalize_package_names(args)
+	if !names_ok {
+		return 1
+	}
+	args = names
+
 	// Load index
 	idx, ok := index.index_load_or_fetch(config.index_url, false)
 	if !ok {
 		errors.log_error("Failed to load package index")
-		return 1
+		return errors.EXIT_INDEX_FAILED
 	}
 
 	cfg, cfg_ok := builder.default_build_config()
@@ -32,12 +39,12 @@ build_run :: proc(args: []string, config: ^Config) -> int {
 		pkg, ok := index.index_get_package(&idx, pkg_name)
 		if !ok {
 			errors.log_error("Package '%s' not found in VUP index", pkg_name)
-			exit_code = 1
+			exit_code = errors.EXIT_NOT_FOUND
 			continue
 		}
 
 		if !builder.build_package(&cfg, pkg_name, pkg.category) {
-			exit_code = 1
+			exit_code = errors.EXIT_XBPS_FAILED
 		} else {
 			errors.log_info("Successfully built %s", pkg_name)
 
@@ -0,0 +1,113 @@
+package commands
+
+import "core:fmt"
+import "core:slice"
+
+import errors "../core/errors"
+import index "../core/index"
+import template "../core/template"
+import xbps "../core/xbps"
+import utils "../utils"
+
+// Check command - report installed VUP packages whose upstream template
+// changed since they were installed (i.e. what the next update will ask to
+// review). Nothing is installed; -v also prints the diffs.
+check_run :: proc(args: []string, config: ^Config) -> int {
+	idx, ok := index.index_load_or_fetch(config.index_url, false)
+	if !ok {
+		errors.log_error("Failed to load package index")
+		return errors.EXIT_INDEX_FAILED
+	}
+
+	installed, installed_ok := xbps.list_installed(utils.run_command_output, context.temp_allocator)
+	if !installed_ok {
+		errors.log_error("Failed to run xbps-query")
+		return errors.EXIT_XBPS_FAILED
+	}
+
+	names := make([dynamic]string, context.temp_allocator)
+	for p in installed {
+		if p[0] in idx.packages && (len(args) == 0 || slice.contains(args, p[0])) {
+			append(&names, p[0])
+		}
+	}
+	slice.sort(names[:])
+
+	if len(names) == 0 {
+		errors.log_info("No installed VUP packages to check")
+		return 0
+	}
+
+	source := template_source(config)
+	changed, failed := 0, 0
+
+	reqs := make([]template.Template_Request, len(names), context.temp_allocator)
+	for name, i in names {
+		pkg := idx.packages[name]
+		reqs[i] = {pkg.category, index.package_template_name(pkg, name)}
+	}
+	fetched, fetched_ok := template.fetch_templates(
+		reqs,
+		source,
+		max_concurrent(config),
+		context.temp_allocator,
+	)
+
+	for name, i in names {
+		status := "unchanged"
+		upstream, fetch_ok := fetched[i], fetched_ok[i]
+		if !fetch_ok {
+			upstream, fetch_ok = template.fetch_template(
+				reqs[i].category,
+				reqs[i].pkg_name,
+				source,
+				context.temp_allocator,
+			)
+		}
+		cached, cached_ok := template.cache_get_template(name, context.temp_allocator)
+		switch {
+		case !fetch_ok:
+			status = "failed"
+			failed += 1
+		case !cached_ok:
+			status = "no-cache"
+		case cached != upstream:
+			status = "changed"
+			changed += 1
+		}
+
+		if config.porcelain {
+			fmt.printf("%s\t%s\n", name, status)
+			continue
+		}
+
+		switch status {
+		case "changed":
+			fmt.printf("  %s%-10s%s %s\n", errors.COLOR_WARNING, "changed", errors.COLOR_RESET, name)
+			if config.verbose {
+				if diff, diff_ok := utils.diff_generate(cached, upstream, context.temp_allocator); diff_ok {
+					fmt.println(diff)
+				}
+			}
+		case "no-cache":
+			fmt.printf("  %-10s %s (no cached template to compare)\n", "unknown", name)
+		case "failed":
+			fmt.printf(
+				"  %s%-10s%s %s (could not fetch template)\n",
+				errors.COLOR_ERROR,
+				"failed",
+				errors.COLOR_RESET,
+				name,
+			)
+		case:
+			fmt.printf("  %-10s %s\n", status, name)
+		}
+	}
+
+	if !config.porcelain {
+		fmt.println()
+		fmt.printf("%d of %d installed VUP package(s) have upstream template changes\n", changed, len(names))
+	}
+
+	return errors.EXIT_INDEX_FAILED if failed > 0 else 0
+}
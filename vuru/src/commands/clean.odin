@@ -0,0 +1,191 @@
+package commands
+
+import "core:fmt"
+
+import errors "../core/errors"
+import index "../core/index"
+import template "../core/template"
+import xbps "../core/xbps"
+import utils "../utils"
+
+// Clean command implementation
+clean_run :: proc(args: []string, config: ^Config) -> int {
+	if config.clean_all {
+		return clean_everything(config)
+	}
+
+	if !config.prune_cache && !config.clean_binpkgs {
+		clean_usage()
+		return 1
+	}
+
+	if config.prune_cache {
+		if ret := clean_templates(config, true); ret != 0 {
+			return ret
+		}
+	}
+
+	if config.clean_binpkgs {
+		return clean_binpkgs(config)
+	}
+	return 0
+}
+
+// Clean --all: every cached template, the cached indexes and the xbps
+// binpkg cache, unless skipped with --no-templates/--no-index/--no-binpkgs.
+// Keeps going when one part fails and reports the first failure.
+clean_everything :: proc(config: ^Config) -> int {
+	ret := 0
+
+	if !config.no_templates {
+		if r := clean_templates(config, false); r != 0 && ret == 0 {
+			ret = r
+		}
+	}
+
+	if !config.no_index {
+		size := index.index_cache_usage()
+		if config.dry_run {
+			errors.log_info(
+				"Would remove the cached package indexes, freeing %s",
+				utils.format_size(size, context.temp_allocator),
+			)
+		} else {
+			index.index_cache_remove_all()
+			errors.log_info(
+				"Removed the cached package indexes, freed %s (next command fetches them again)",
+				utils.format_size(size, context.temp_allocator),
+			)
+		}
+	}
+
+	if !config.no_binpkgs {
+		if r := clean_binpkgs(config); r != 0 && ret == 0 {
+			ret = r
+		}
+	}
+
+	return ret
+}
+
+// Clear the xbps binary package cache (xbps-remove -O, needs root)
+clean_binpkgs :: proc(config: ^Config) -> int {
+	cache_dir := xbps.cache_dir_path(config.rootdir, context.temp_allocator)
+
+	_, before, before_ok := xbps.cache_usage(cache_dir)
+	if ret := remove_cache(config); ret != 0 {
+		errors.log_error("Failed to clean %s", cache_dir)
+		return ret
+	}
+
+	if config.dry_run || !before_ok {
+		return 0
+	}
+
+	_, after, _ := xbps.cache_usage(cache_dir)
+	errors.log_info(
+		"Freed %s in %s",
+		utils.format_size(max(before - after, 0), context.temp_allocator),
+		cache_dir,
+	)
+	return 0
+}
+
+// Tell where xbps keeps downloaded binpkgs and how much space they take
+print_binpkg_cache_usage :: proc(rootdir: string) {
+	cache_dir := xbps.cache_dir_path(rootdir, context.temp_allocator)
+	files, size, ok := xbps.cache_usage(cache_dir)
+	if !ok || files == 0 {
+		return
+	}
+
+	errors.log_info(
+		"Downloaded packages are cached in %s (%d file(s), %s); 'vuru clean --binpkgs' clears them",
+		cache_dir,
+		files,
+		utils.format_size(size, context.temp_allocator),
+	)
+}
+
+// Remove cached templates, with only_uninstalled just those of packages that
+// are no longer installed. Without a cached template the next update of a
+// package shows the whole template instead of a diff.
+clean_templates :: proc(config: ^Config, only_uninstalled: bool) -> int {
+	all_entries, ok := template.cache_entries(context.temp_allocator)
+	if !ok {
+		return 1
+	}
+
+	// Only templates vuru wrote are removed; anything else is left alone
+	entries := make([dynamic]template.Cache_Entry, context.temp_allocator)
+	for entry in all_entries {
+		if entry.kind == .Template {
+			append(&entries, entry)
+		} else {
+			errors.log_warning(
+				"Skipping %s in template cache: %s",
+				entry.path,
+				template.cache_entry_kind_string(entry.kind),
+			)
+		}
+	}
+
+	// One xbps-query -l for the whole cache instead of a query per template.
+	// Without the list nothing can be told apart, so nothing is removed.
+	installed := make(map[string]bool, context.temp_allocator)
+	if only_uninstalled {
+		pkgs, list_ok := xbps.list_installed(utils.run_command_output, context.temp_allocator)
+		if !list_ok {
+			errors.log_error("Failed to list installed packages")
+			return 1
+		}
+		for p in pkgs {
+			installed[p[0]] = true
+		}
+	}
+
+	removed := 0
+	freed: i64 = 0
+
+	for entry in entries {
+		if only_uninstalled && entry.name in installed {
+			continue
+		}
+
+		if config.dry_run {
+			fmt.printf("Would remove cached template: %s\n", entry.name)
+		} else if !template.cache_remove_template(entry.name) {
+			errors.log_warning("Failed to remove cached template for %s", entry.name)
+			continue
+		} else if config.verbose {
+			errors.log_info("Removed cached template for %s", entry.name)
+		}
+
+		removed += 1
+		freed += entry.size
+	}
+
+	if removed == 0 {
+		errors.log_info("Template cache is clean (%d template(s) kept)", len(entries))
+		return 0
+	}
+
+	size := utils.format_size(freed, context.temp_allocator)
+	if config.dry_run {
+		errors.log_info("Would remove %d template(s), freeing %s", removed, size)
+	} else {
+		errors.log_info("Removed %d template(s), freed %s", removed, size)
+	}
+	return 0
+}
+
+clean_usage :: proc() {
+	fmt.println("Usage: vuru clean [options]")
+	fmt.println()
+	fmt.println("Options:")
+	fmt.println("  --prune-cache    Remove cached templates of uninstalled packages")
+	fmt.println("  --binpkgs        Clear the xbps binary package cache (xbps-remove -O)")
+	fmt.println("  --all            All cached templates, cached indexes and binpkgs; skip parts")
+	fmt.println("                   with --no-templates, --no-index or --no-binpkgs")
+	fmt.println("  -n, --dry-run    Show what would be removed")
+}
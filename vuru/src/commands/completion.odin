@@ -0,0 +1,501 @@
+package commands
+
+import "core:fmt"
+import "core:os"
+import "core:strings"
+
+import cfg "../core/config"
+import errors "../core/errors"
+import index "../core/index"
+import utils "../utils"
+
+// Shells `vuru completion` has scripts for
+COMPLETION_SHELLS :: []string{"bash", "zsh", "fish"}
+
+// Completion command - prints a shell completion script, or writes it to --output
+completion_run :: proc(args: []string, config: ^Config) -> int {
+	if config.list_shells {
+		return completion_list_shells(config)
+	}
+
+	if len(args) == 0 {
+		fmt.println("Usage: vuru completion <bash|zsh|fish> [--output <file>]")
+		fmt.println("       vuru completion --list-shells")
+		return 1
+	}
+
+	names_path, _ := index.completion_names_path(context.temp_allocator)
+	script, ok := completion_script(args[0], names_path)
+	if !ok {
+		errors.log_error(
+			"Unsupported shell: %s (supported: %s)",
+			args[0],
+			strings.join(COMPLETION_SHELLS, ", ", context.temp_allocator),
+		)
+		return 1
+	}
+
+	if len(config.output_path) == 0 {
+		fmt.print(script)
+		return 0
+	}
+
+	// Packaging scripts point this straight into a staging tree, so create parents
+	if slash := strings.last_index_byte(config.output_path, '/'); slash > 0 {
+		parent := config.output_path[:slash]
+		if !utils.mkdir_p(parent) {
+			errors.log_error("Failed to create directory: %s", parent)
+			return 1
+		}
+	}
+
+	if !utils.write_file(config.output_path, script) {
+		errors.log_error("Failed to write %s", config.output_path)
+		return 1
+	}
+
+	errors.log_info("Wrote %s completion to %s", args[0], config.output_path)
+	return 0
+}
+
+// --list-shells: the supported shells and where their script is usually
+// installed; --porcelain prints only the names
+completion_list_shells :: proc(config: ^Config) -> int {
+	for shell in COMPLETION_SHELLS {
+		if config.porcelain {
+			fmt.println(shell)
+		} else {
+			fmt.printf("%-6s %s\n", shell, completion_install_hint(shell))
+		}
+	}
+	if config.porcelain {
+		return 0
+	}
+
+	// Every script completes package names from the names file, and simply
+	// skips them while it doesn't exist
+	names_path, names_ok := index.completion_names_path(context.temp_allocator)
+	fmt.println()
+	if !names_ok {
+		fmt.println("Package names: unavailable (no cache directory)")
+	} else if os.exists(names_path) {
+		fmt.printf("Package names: %s\n", names_path)
+	} else {
+		fmt.printf("Package names: %s (run 'vuru gen-completion-cache' to create it)\n", names_path)
+	}
+	return 0
+}
+
+// Where a shell's completion script usually goes
+@(private)
+completion_install_hint :: proc(shell: string) -> string {
+	switch shell {
+	case "bash":
+		return "~/.local/share/bash-completion/completions/vuru"
+	case "zsh":
+		return "_vuru in a directory of $fpath"
+	case "fish":
+		return "~/.config/fish/completions/vuru.fish"
+	}
+	return ""
+}
+
+// Completion script for a shell. Package names for install/build/query are
+// read from names_file (see gen-completion-cache) when it exists.
+completion_script :: proc(shell: string, names_file: string) -> (string, bool) {
+	script: string
+	switch shell {
+	case "bash":
+		script = BASH_COMPLETION
+	case "zsh":
+		script = ZSH_COMPLETION
+	case "fish":
+		script = FISH_COMPLETION
+	case:
+		return "", false
+	}
+	out, _ := strings.replace_all(script, COMPLETION_NAMES_PLACEHOLDER, names_file, context.temp_allocator)
+	return out, true
+}
+
+// Gen-completion-cache command - writes the package names list and completion
+// scripts reading it, so tab-completion never runs vuru or needs the index
+gen_completion_cache_run :: proc(args: []string, config: ^Config) -> int {
+	idx, ok := index.index_load_or_fetch(config.index_url, false)
+	if !ok {
+		errors.log_error("Failed to load package index")
+		return errors.EXIT_INDEX_FAILED
+	}
+
+	names_path, names_ok := index.completion_names_path(context.temp_allocator)
+	cache_dir, cache_ok := cfg.get_cache_dir(context.temp_allocator)
+	if !names_ok || !cache_ok {
+		errors.log_error("Failed to determine cache directory")
+		return 1
+	}
+
+	if !index.write_completion_names(&idx) {
+		errors.log_error("Failed to write %s", names_path)
+		return 1
+	}
+
+	out_dir := utils.path_join(cache_dir, "completions", allocator = context.temp_allocator)
+	if !utils.mkdir_p(out_dir) {
+		errors.log_error("Failed to create directory: %s", out_dir)
+		return 1
+	}
+
+	for shell in COMPLETION_SHELLS {
+		script, _ := completion_script(shell, names_path)
+		path := utils.path_join(out_dir, fmt.tprintf("vuru.%s", shell), allocator = context.temp_allocator)
+		if !utils.write_file(path, script) {
+			errors.log_error("Failed to write %s", path)
+			return 1
+		}
+	}
+
+	errors.log_info("Wrote %d package names to %s", len(idx.packages), names_path)
+	errors.log_info("Completion scripts are in %s ('vuru sync' keeps the names current)", out_dir)
+	return 0
+}
+
+@(private)
+COMPLETION_NAMES_PLACEHOLDER :: "@VURU_NAMES_FILE@"
+
+@(private)
+BASH_COMPLETION :: `# bash completion for vuru
+_vuru() {
+	local cur prev cmd i
+	cur="${COMP_WORDS[COMP_CWORD]}"
+	prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+	local commands="query install remove update build sync fetch clean clone src version completion gen-completion-cache list-packages index-stats revdeps why check pin unpin apply repo tui watch history help"
+	local opts="-y --yes --default-no --interactive -n --dry-run -b --build -d --desc -v --verbose -q --quiet
+		-r --rootdir -S --sync -u --update -R --recursive --clean-deps -o --orphans --clean-orphans
+		-O --clean-cache -l --list -f --files -x --deps --ownedby --prune-cache --force-cache-rebuild --binpkgs --all --no-templates --no-index --no-binpkgs --test
+		--preview --no-review --confirm-each --stdin --keep-diff-files --vup-only --installed --any --porcelain --json --since --new-since --license --sort --group-by-category --available-updates --repo-version --max-per-category --max-concurrent --max-download --stats --notify --as-dependency --trust-repo --tree --print-repo --select-version --idempotent --verify-installed --from-repo --download-first --allow-insecure-repo --simulate --template-ref --color --quiet-if-unchanged --list-shells
+		--template-base --output --out --apply --from-file --save-to --arch --env --repo-only --repo-priority -V --version -h --help"
+
+	# First non-option word is the command (skip values of options that take one)
+	cmd=""
+	for ((i = 1; i < COMP_CWORD; i++)); do
+		case "${COMP_WORDS[i]}" in
+		-r|--rootdir|--template-ref|--template-base|--output|--out|--apply|--from-file|--save-to|--arch|--env|--repo-only|--repo-priority|--since|--new-since|--license|--sort|--color|--max-per-category|--max-concurrent|--max-download) ((i++)) ;;
+		-*) ;;
+		*) cmd="${COMP_WORDS[i]}"; break ;;
+		esac
+	done
+
+	case "$prev" in
+	-r|--rootdir|--save-to) COMPREPLY=($(compgen -d -- "$cur")); return ;;
+	--output|--out|--apply|--from-file) COMPREPLY=($(compgen -f -- "$cur")); return ;;
+	--arch) COMPREPLY=($(compgen -W "x86_64 x86_64-musl i686 aarch64 aarch64-musl armv7l armv7l-musl armv6l armv6l-musl" -- "$cur")); return ;;
+	--sort) COMPREPLY=($(compgen -W "relevance version name" -- "$cur")); return ;;
+	--color) COMPREPLY=($(compgen -W "auto always never" -- "$cur")); return ;;
+	--template-ref|--template-base|--env|--repo-only|--repo-priority|--since|--new-since|--license|--max-per-category|--max-concurrent|--max-download) return ;;
+	esac
+
+	if [[ "$cur" == -* ]]; then
+		COMPREPLY=($(compgen -W "$opts" -- "$cur"))
+		return
+	fi
+
+	case "$cmd" in
+	"")
+		COMPREPLY=($(compgen -W "$commands" -- "$cur"))
+		;;
+	remove|r|uninstall|revdeps|why|check|pin)
+		COMPREPLY=($(compgen -W "$(xbps-query -l 2>/dev/null | awk '{print $2}' | sed 's/-[^-]*$//')" -- "$cur"))
+		;;
+	install|i|build|query|q|info|show)
+		[[ -r "@VURU_NAMES_FILE@" ]] && COMPREPLY=($(compgen -W "$(< "@VURU_NAMES_FILE@")" -- "$cur"))
+		;;
+	completion)
+		COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur"))
+		;;
+	repo)
+		COMPREPLY=($(compgen -W "list add remove conflicts priority" -- "$cur"))
+		;;
+	unpin)
+		COMPREPLY=($(compgen -W "$(vuru pin 2>/dev/null)" -- "$cur"))
+		;;
+	apply)
+		COMPREPLY=($(compgen -f -- "$cur"))
+		;;
+	esac
+}
+complete -F _vuru vuru
+`
+
+@(private)
+ZSH_COMPLETION :: `#compdef vuru
+
+_vuru() {
+	local curcontext="$curcontext" state line
+	local -a commands
+	commands=(
+		'query:Show package info'
+		'install:Install packages (VUP + official)'
+		'remove:Remove packages'
+		'update:Update all packages'
+		'build:Build packages from source'
+		'sync:Sync repository index'
+		'fetch:Download files from URLs'
+		'clean:Clean vuru caches'
+		'clone:Clone/update VUP repository'
+		'src:Run xbps-src with VUP deps'
+		'version:Show version'
+		'completion:Print shell completion script'
+		'gen-completion-cache:Write package names and completion scripts for offline completion'
+		'revdeps:Show installed packages depending on a package'
+		'why:Explain why a package is installed'
+		'list-packages:Print all index packages'
+		'index-stats:Check the index and print package counts'
+		'check:Report installed packages with changed upstream templates'
+		'pin:Hold packages back from update'
+		'unpin:Allow pinned packages to be updated'
+		'apply:Install the packages of a manifest'
+		'repo:Manage overlay repos'
+		'tui:Browse and install packages interactively'
+		'watch:Notify about VUP updates periodically'
+		'history:Show past operations'
+		'help:Show help'
+	)
+
+	_arguments -C \
+		'(-y --yes)'{-y,--yes}'[Skip confirmations]' \
+		'--default-no[Treat an empty answer as no]' \
+		'--interactive[Prompt even in CI or without a terminal]' \
+		'(-n --dry-run)'{-n,--dry-run}'[Show what would be done]' \
+		'(-b --build)'{-b,--build}'[Force build from source]' \
+		'(-d --desc)'{-d,--desc}'[Include descriptions in search]' \
+		'(-v --verbose)'{-v,--verbose}'[Verbose output]' \
+		'(-q --quiet)'{-q,--quiet}'[Only print warnings and errors]' \
+		'--quiet-if-unchanged[sync: no output unless the index changed]' \
+		'(-r --rootdir)'{-r,--rootdir}'[Alternate root directory]:directory:_files -/' \
+		'(-S --sync)'{-S,--sync}'[Sync repos before operation]' \
+		'(-u --update)'{-u,--update}'[Update mode (system upgrade)]' \
+		'(-R --recursive --clean-deps)'{-R,--recursive,--clean-deps}'[Recursive remove/deps]' \
+		'(-o --orphans)'{-o,--orphans}'[Remove orphan packages]' \
+		'--clean-orphans[Remove orphans after removing packages]' \
+		'(-O --clean-cache)'{-O,--clean-cache}'[Clean package cache]' \
+		'(-l --list)'{-l,--list}'[List installed packages]' \
+		'(-f --files)'{-f,--files}'[Show package files]' \
+		'(-x --deps)'{-x,--deps}'[Show dependencies]' \
+		'--ownedby[Find package owning a file]' \
+		'--prune-cache[Drop templates of uninstalled packages]' \
+		'--binpkgs[Clear the xbps binary package cache]' \
+		'--all[clean: templates, cached indexes and binpkgs]' \
+		'--no-templates[clean --all: keep cached templates]' \
+		'--no-index[clean --all: keep cached indexes]' \
+		'--no-binpkgs[clean --all: keep the binpkg cache]' \
+		'--test[repo add: only check that the URL serves an index]' \
+		'--force-cache-rebuild[Delete cached indexes and fetch them again]' \
+		'--preview[Show templates and xbps dry run, then ask (even with -y)]' \
+		'--no-review[Install without fetching templates]' \
+		'--confirm-each[Confirm every install step]' \
+		'--stdin[Read package names from stdin]' \
+		'--keep-diff-files[Keep old/new templates of reviews]' \
+		'--vup-only[VUP packages only]' \
+		'--installed[Installed packages only]' \
+		'--any[Match any search word instead of all]' \
+		'--porcelain[Stable tab-separated output]' \
+		'--json[JSON output (history, list-packages, index-stats)]' \
+		'--since[Only history newer than a duration]:duration:' \
+		'--sort[Order search results]:key:(relevance version name)' \
+		'--group-by-category[Group search results by category]' \
+		'--available-updates[Show installed and index versions of installed matches]' \
+		'--repo-version[Compare index versions with what the repos serve]' \
+		'--list-shells[List the shells completion supports]' \
+		'--max-per-category[Search results shown per category]:count:' \
+		'--new-since[Only packages changed since a date]:date (YYYY-MM-DD):' \
+		'--license[Only packages whose license contains this]:license:' \
+		'--max-concurrent[Parallel template downloads]:count:' \
+		'--max-download[Total download budget]:size:' \
+		'--stats[Show pending upgrades per category]' \
+		'--notify[Desktop notification when install/update finishes]' \
+		'--as-dependency[Mark installed packages as dependencies]' \
+		'--trust-repo[Import signing keys of VUP repos without asking]' \
+		'--tree[Show the dependency tree before installing]' \
+		'--print-repo[Print version and repo URL instead of installing]' \
+		'--select-version[Choose among the versions the repo offers]' \
+		'--idempotent[Skip packages already installed at the index version]' \
+		'--verify-installed[Check installed versions against the index after installing]' \
+		'--from-repo[Fetch VUP packages only from their index repo]' \
+		'--download-first[Download all upgrades before installing any]' \
+		'--allow-insecure-repo[Accept plain http:// repos without asking]' \
+		'--template-ref[VUP branch, tag or commit for templates]:ref:' \
+		'--color[When to use colors]:when:(auto always never)' \
+		'--template-base[Raw template host of a fork/mirror]:url:' \
+		'(--output --out)'{--output,--out}'[Write completion script or update plan to file]:file:_files' \
+		'--simulate[Write the upgrade or removal plan instead of acting]' \
+		'--apply[Install the upgrades of a saved plan]:plan:_files' \
+		'--from-file[Review a local template instead of the upstream one]:template:_files' \
+		'--save-to[Archive installed templates in a directory]:directory:_files -/' \
+		'--repo-only[Only packages of one repo]:repo:' \
+		'--repo-priority[Priority of a repo added with repo add]:priority:' \
+		'--arch[Target architecture]:arch:(x86_64 x86_64-musl i686 aarch64 aarch64-musl armv7l armv7l-musl armv6l armv6l-musl)' \
+		'*--env[Environment variable for xbps commands]:KEY=VALUE:' \
+		'(-V --version)'{-V,--version}'[Show version]' \
+		'(-h --help)'{-h,--help}'[Show help]' \
+		'1: :->command' \
+		'*:: :->args'
+
+	case $state in
+	command)
+		_describe -t commands 'vuru command' commands
+		;;
+	args)
+		case $words[1] in
+		remove|r|uninstall|revdeps|why|check|pin)
+			local -a pkgs
+			pkgs=(${(f)"$(xbps-query -l 2>/dev/null | awk '{print $2}' | sed 's/-[^-]*$//')"})
+			_describe -t packages 'installed package' pkgs
+			;;
+		install|i|build|query|q|info|show)
+			if [[ -r "@VURU_NAMES_FILE@" ]]; then
+				local -a names
+				names=(${(f)"$(<"@VURU_NAMES_FILE@")"})
+				_describe -t packages 'package' names
+			fi
+			;;
+		completion)
+			_values 'shell' bash zsh fish
+			;;
+		repo)
+			_values 'repo command' list add remove conflicts priority
+			;;
+		unpin)
+			local -a pinned
+			pinned=(${(f)"$(vuru pin 2>/dev/null)"})
+			_describe -t packages 'pinned package' pinned
+			;;
+		apply)
+			_files
+			;;
+		esac
+		;;
+	esac
+}
+
+_vuru "$@"
+`
+
+@(private)
+FISH_COMPLETION :: `# fish completion for vuru
+set -l commands query install remove update build sync fetch clean clone src version completion gen-completion-cache list-packages index-stats revdeps why check pin unpin apply repo tui watch history help
+
+complete -c vuru -f
+
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a query -d 'Show package info'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a install -d 'Install packages (VUP + official)'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a remove -d 'Remove packages'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a update -d 'Update all packages'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a build -d 'Build packages from source'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a sync -d 'Sync repository index'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a fetch -d 'Download files from URLs'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a clean -d 'Clean vuru caches'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a clone -d 'Clone/update VUP repository'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a src -d 'Run xbps-src with VUP deps'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a version -d 'Show version'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a completion -d 'Print shell completion script'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a gen-completion-cache -d 'Write package names for offline completion'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a revdeps -d 'Show installed packages depending on a package'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a why -d 'Explain why a package is installed'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a list-packages -d 'Print all index packages'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a index-stats -d 'Check the index and print package counts'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a check -d 'Report installed packages with changed upstream templates'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a pin -d 'Hold packages back from update'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a unpin -d 'Allow pinned packages to be updated'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a apply -d 'Install the packages of a manifest'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a repo -d 'Manage overlay repos'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a tui -d 'Browse and install packages interactively'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a watch -d 'Notify about VUP updates periodically'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a history -d 'Show past operations'
+complete -c vuru -n "not __fish_seen_subcommand_from $commands" -a help -d 'Show help'
+
+complete -c vuru -n "__fish_seen_subcommand_from remove r uninstall revdeps why check pin" -a '(xbps-query -l 2>/dev/null | string split -f2 " " | string replace -r -- "-[^-]*\$" "")'
+complete -c vuru -n "__fish_seen_subcommand_from install i build query q info show" -a '(test -r "@VURU_NAMES_FILE@"; and cat "@VURU_NAMES_FILE@")'
+complete -c vuru -n "__fish_seen_subcommand_from completion" -a 'bash zsh fish'
+complete -c vuru -n "__fish_seen_subcommand_from completion" -l list-shells -d 'List supported shells'
+complete -c vuru -n "__fish_seen_subcommand_from repo" -a 'list add remove conflicts priority'
+complete -c vuru -n "__fish_seen_subcommand_from unpin" -a '(vuru pin 2>/dev/null)'
+complete -c vuru -n "__fish_seen_subcommand_from apply" -F
+
+complete -c vuru -s y -l yes -d 'Skip confirmations'
+complete -c vuru -l default-no -d 'Treat an empty answer as no'
+complete -c vuru -l interactive -d 'Prompt even in CI or without a terminal'
+complete -c vuru -s n -l dry-run -d 'Show what would be done'
+complete -c vuru -s b -l build -d 'Force build from source'
+complete -c vuru -s d -l desc -d 'Include descriptions in search'
+complete -c vuru -s v -l verbose -d 'Verbose output'
+complete -c vuru -s q -l quiet -d 'Only print warnings and errors'
+complete -c vuru -l quiet-if-unchanged -d 'sync: no output unless the index changed'
+complete -c vuru -s r -l rootdir -x -a '(__fish_complete_directories)' -d 'Alternate root directory'
+complete -c vuru -s S -l sync -d 'Sync repos before operation'
+complete -c vuru -s u -l update -d 'Update mode (system upgrade)'
+complete -c vuru -s R -l recursive -d 'Recursive remove/deps'
+complete -c vuru -l clean-deps -d 'Also remove deps no longer needed'
+complete -c vuru -s o -l orphans -d 'Remove orphan packages'
+complete -c vuru -l clean-orphans -d 'Remove orphans after removing packages'
+complete -c vuru -s O -l clean-cache -d 'Clean package cache'
+complete -c vuru -s l -l list -d 'List installed packages'
+complete -c vuru -s f -l files -d 'Show package files'
+complete -c vuru -s x -l deps -d 'Show dependencies'
+complete -c vuru -l ownedby -d 'Find package owning a file'
+complete -c vuru -l prune-cache -d 'Drop templates of uninstalled packages'
+complete -c vuru -l binpkgs -d 'Clear the xbps binary package cache'
+complete -c vuru -l all -d 'clean: templates, cached indexes and binpkgs'
+complete -c vuru -l no-templates -d 'clean --all: keep cached templates'
+complete -c vuru -l no-index -d 'clean --all: keep cached indexes'
+complete -c vuru -l no-binpkgs -d 'clean --all: keep the binpkg cache'
+complete -c vuru -l test -d 'repo add: only check that the URL serves an index'
+complete -c vuru -l force-cache-rebuild -d 'Delete cached indexes and fetch them again'
+complete -c vuru -l preview -d 'Show templates and xbps dry run, then ask (even with -y)'
+complete -c vuru -l no-review -d 'Install without fetching templates'
+complete -c vuru -l confirm-each -d 'Confirm every install step'
+complete -c vuru -l stdin -d 'Read package names from stdin'
+complete -c vuru -l keep-diff-files -d 'Keep old/new templates of reviews'
+complete -c vuru -l vup-only -d 'VUP packages only'
+complete -c vuru -l installed -d 'Installed packages only'
+complete -c vuru -l any -d 'Match any search word instead of all'
+complete -c vuru -l porcelain -d 'Stable tab-separated output'
+complete -c vuru -l json -d 'JSON output (history, list-packages, index-stats)'
+complete -c vuru -l since -x -d 'Only history newer than a duration'
+complete -c vuru -l new-since -x -d 'Only packages changed since a date'
+complete -c vuru -l license -x -d 'Only packages whose license contains this'
+complete -c vuru -l group-by-category -d 'Group search results by category'
+complete -c vuru -l available-updates -d 'Show installed and index versions of installed matches'
+complete -c vuru -l repo-version -d 'Compare index versions with what the repos serve'
+complete -c vuru -l max-per-category -x -d 'Search results shown per category'
+complete -c vuru -l sort -x -a 'relevance version name' -d 'Order search results'
+complete -c vuru -l max-concurrent -x -d 'Parallel template downloads'
+complete -c vuru -l max-download -x -d 'Total download budget (e.g. 20M)'
+complete -c vuru -l stats -d 'Show pending upgrades per category'
+complete -c vuru -l notify -d 'Desktop notification when install/update finishes'
+complete -c vuru -l as-dependency -d 'Mark installed packages as dependencies'
+complete -c vuru -l trust-repo -d 'Import signing keys of VUP repos without asking'
+complete -c vuru -l tree -d 'Show the dependency tree before installing'
+complete -c vuru -l print-repo -d 'Print version and repo URL instead of installing'
+complete -c vuru -l select-version -d 'Choose among the versions the repo offers'
+complete -c vuru -l idempotent -d 'Skip packages already installed at the index version'
+complete -c vuru -l verify-installed -d 'Check installed versions against the index after installing'
+complete -c vuru -l from-repo -d 'Fetch VUP packages only from their index repo'
+complete -c vuru -l download-first -d 'Download all upgrades before installing any'
+complete -c vuru -l allow-insecure-repo -d 'Accept plain http:// repos without asking'
+complete -c vuru -l template-ref -x -d 'VUP branch, tag or commit for templates'
+complete -c vuru -l color -x -a 'auto always never' -d 'When to use colors'
+complete -c vuru -l template-base -x -d 'Raw template host of a fork/mirror'
+complete -c vuru -l output -r -F -d 'Write completion script or update plan to file'
+complete -c vuru -l out -r -F -d 'Write completion script or update plan to file'
+complete -c vuru -l simulate -d 'Write the upgrade or removal plan instead of acting'
+complete -c vuru -l apply -r -F -d 'Install the upgrades of a saved plan'
+complete -c vuru -l from-file -r -F -d 'Review a local template instead of the upstream one'
+complete -c vuru -l save-to -x -a '(__fish_complete_directories)' -d 'Archive installed templates in a directory'
+complete -c vuru -l repo-only -x -d 'Only packages of one repo'
+complete -c vuru -l repo-priority -x -d 'Priority of a repo added with repo add'
+complete -c vuru -l arch -x -a 'x86_64 x86_64-musl i686 aarch64 aarch64-musl armv7l armv7l-musl armv6l armv6l-musl' -d 'Target architecture'
+complete -c vuru -l env -x -d 'Environment variable for xbps commands (KEY=VALUE)'
+complete -c vuru -s V -l version -d 'Show version'
+complete -c vuru -s h -l help -d 'Show help'
+`
@@ -2,6 +2,8 @@ package commands
 
 import "core:mem"
 
+import template "../core/template"
+
 // Free owned resources in Config
 config_free :: proc(c: ^Config) {
 	if c.allocator.procedure == nil {
@@ -20,4 +22,51 @@ config_free :: proc(c: ^Config) {
 	if len(c.rootdir) > 0 {
 		delete(c.rootdir, c.allocator)
 	}
+	for kv in c.env_vars {
+		delete(kv, c.allocator)
+	}
+	delete(c.env_vars)
+	if len(c.template_ref) > 0 {
+		delete(c.template_ref, c.allocator)
+	}
+	if len(c.template_base) > 0 {
+		delete(c.template_base, c.allocator)
+	}
+	if len(c.output_path) > 0 {
+		delete(c.output_path, c.allocator)
+	}
+	if len(c.template_file) > 0 {
+		delete(c.template_file, c.allocator)
+	}
+	if len(c.save_to) > 0 {
+		delete(c.save_to, c.allocator)
+	}
+	if len(c.apply_plan) > 0 {
+		delete(c.apply_plan, c.allocator)
+	}
+	if len(c.search_sort) > 0 {
+		delete(c.search_sort, c.allocator)
+	}
+	if len(c.new_since) > 0 {
+		delete(c.new_since, c.allocator)
+	}
+	if len(c.license) > 0 {
+		delete(c.license, c.allocator)
+	}
+	if len(c.since) > 0 {
+		delete(c.since, c.allocator)
+	}
+	if len(c.repo_only) > 0 {
+		delete(c.repo_only, c.allocator)
+	}
+}
+
+// Template source selected on the command line (or environment)
+template_source :: proc(c: ^Config) -> template.Template_Source {
+	return template.Template_Source{git_ref = c.template_ref, base_url = c.template_base}
+}
+
+// Parallel template downloads (--max-concurrent, template.DEFAULT_MAX_CONCURRENT without it)
+max_concurrent :: proc(c: ^Config) -> int {
+	return c.max_concurrent if c.max_concurrent > 0 else template.DEFAULT_MAX_CONCURRENT
 }
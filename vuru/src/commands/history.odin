@@ -0,0 +1,101 @@
+package commands
+
+import "core:encoding/json"
+import "core:fmt"
+import "core:time"
+
+import audit "../core/audit"
+import errors "../core/errors"
+import utils "../utils"
+
+// History command - timeline of what vuru installed, upgraded and removed
+history_run :: proc(args: []string, config: ^Config) -> int {
+	entries, ok := audit.audit_read(context.temp_allocator)
+	if !ok {
+		errors.log_error("Could not determine history log location")
+		return 1
+	}
+
+	// --since 7d: drop older entries
+	if len(config.since) > 0 {
+		window, window_ok := audit.parse_duration(config.since)
+		if !window_ok {
+			errors.log_error("Invalid duration: %s (e.g. 30m, 12h, 7d, 2w)", config.since)
+			return 1
+		}
+
+		cutoff := time.to_unix_seconds(time.now()) - window
+		kept := 0
+		for e in entries {
+			if e.timestamp >= cutoff {
+				entries[kept] = e
+				kept += 1
+			}
+		}
+		entries = entries[:kept]
+	}
+
+	if config.json {
+		return history_print_json(entries)
+	}
+
+	if len(entries) == 0 {
+		errors.log_info("No history recorded yet")
+		return 0
+	}
+
+	fmt.printf("%-23s %-8s %-30s %-15s %s\n", "TIME", "ACTION", "PACKAGE", "VERSION", "RESULT")
+	for e in entries {
+		result := e.result
+		if result != "ok" {
+			result = fmt.tprintf("%s%s%s", errors.COLOR_RED, e.result, errors.COLOR_RESET)
+		}
+		fmt.printf(
+			"%-23s %-8s %-30s %-15s %s\n",
+			history_time(e.timestamp),
+			e.action,
+			e.pkg,
+			e.version if len(e.version) > 0 else "-",
+			result,
+		)
+	}
+	return 0
+}
+
+@(private)
+history_time :: proc(timestamp: i64) -> string {
+	return utils.format_timestamp(time.unix(timestamp, 0), context.temp_allocator)
+}
+
+// JSON array of entries, oldest first
+@(private)
+history_print_json :: proc(entries: []audit.Audit_Entry) -> int {
+	Json_Entry :: struct {
+		time:      string `json:"time"`,
+		timestamp: i64 `json:"timestamp"`,
+		action:    string `json:"action"`,
+		pkg:       string `json:"package"`,
+		version:   string `json:"version"`,
+		result:    string `json:"result"`,
+	}
+
+	out := make([]Json_Entry, len(entries), context.temp_allocator)
+	for e, i in entries {
+		out[i] = Json_Entry {
+			time = history_time(e.timestamp),
+			timestamp = e.timestamp,
+			action = e.action,
+			pkg = e.pkg,
+			version = e.version,
+			result = e.result,
+		}
+	}
+
+	data, err := json.marshal(out, {pretty = true}, context.temp_allocator)
+	if err != nil {
+		errors.log_error("Failed to encode history")
+		return 1
+	}
+	fmt.println(string(data))
+	return 0
+}
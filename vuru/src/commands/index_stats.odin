@@ -0,0 +1,132 @@
+package commands
+
+import "core:encoding/json"
+import "core:fmt"
+import "core:slice"
+
+import errors "../core/errors"
+import index "../core/index"
+import xbps "../core/xbps"
+import utils "../utils"
+
+// One questionable index entry
+@(private)
+Index_Problem :: struct {
+	name:    string `json:"name"`,
+	problem: string `json:"problem"`,
+}
+
+// `index-stats --json`
+@(private)
+Index_Stats :: struct {
+	packages:   int `json:"packages"`,
+	repo_urls:  int `json:"repo_urls"`,
+	categories: []Category_Count `json:"categories"`,
+	problems:   []Index_Problem `json:"problems"`,
+}
+
+// Index-stats command - a quality check of the loaded index for maintainers:
+// package count, packages per category, distinct repo URLs and entries with
+// empty or invalid fields. Fails when there are problems, so it can gate CI.
+index_stats_run :: proc(args: []string, config: ^Config) -> int {
+	idx, ok := index.index_load_or_fetch(config.index_url, false)
+	if !ok {
+		errors.log_error("Failed to load package index")
+		return errors.EXIT_INDEX_FAILED
+	}
+
+	names := make([dynamic]string, 0, len(idx.packages), context.temp_allocator)
+	for name in idx.packages {
+		append(&names, name)
+	}
+	slice.sort(names[:])
+
+	categories := make([]string, len(names), context.temp_allocator)
+	repo_urls := make(map[string]bool, context.temp_allocator)
+	problems := make([dynamic]Index_Problem, context.temp_allocator)
+
+	for name, i in names {
+		pkg := idx.packages[name]
+		categories[i] = pkg.category if len(pkg.category) > 0 else "?"
+		for _, url in pkg.repo_urls {
+			repo_urls[url] = true
+		}
+
+		for problem in index_entry_problems(name, pkg) {
+			append(&problems, Index_Problem{name, problem})
+		}
+	}
+
+	stats := Index_Stats {
+		packages   = len(names),
+		repo_urls  = len(repo_urls),
+		categories = count_by_category(categories),
+		problems   = problems[:],
+	}
+
+	if config.json {
+		data, err := json.marshal(stats, {pretty = true}, context.temp_allocator)
+		if err != nil {
+			errors.log_error("Failed to encode index stats")
+			return 1
+		}
+		fmt.println(string(data))
+	} else {
+		fmt.printf("Packages:  %d\n", stats.packages)
+		fmt.printf("Repo URLs: %d\n", stats.repo_urls)
+		fmt.println()
+		fmt.printf("%-20s %s\n", "CATEGORY", "PACKAGES")
+		for c in stats.categories {
+			fmt.printf("%-20s %d\n", c.category, c.count)
+		}
+
+		fmt.println()
+		if len(problems) == 0 {
+			errors.log_success("No problems found")
+		} else {
+			fmt.printf("Problems (%d):\n", len(problems))
+			for p in problems {
+				fmt.printf("  %-30s %s\n", p.name, p.problem)
+			}
+		}
+	}
+
+	return 1 if len(problems) > 0 else 0
+}
+
+// What's wrong with one index entry, if anything
+@(private)
+index_entry_problems :: proc(name: string, pkg: index.Package_Info) -> []string {
+	problems := make([dynamic]string, context.temp_allocator)
+
+	if !utils.is_valid_package_name(name) {
+		append(&problems, "invalid package name")
+	}
+	if len(pkg.version) == 0 {
+		append(&problems, "empty version")
+	} else if _, has_revision := xbps.version_revision(pkg.version); !has_revision {
+		append(&problems, fmt.tprintf("version %q has no revision", pkg.version))
+	}
+	if len(pkg.category) == 0 {
+		append(&problems, "empty category")
+	}
+	if len(pkg.short_desc) == 0 {
+		append(&problems, "empty short_desc")
+	}
+	if len(pkg.source_pkg) > 0 && !utils.is_valid_package_name(pkg.source_pkg) {
+		append(&problems, fmt.tprintf("invalid source_pkg %q", pkg.source_pkg))
+	}
+	if len(pkg.updated_at) > 0 && !utils.is_valid_date(pkg.updated_at[:min(len(pkg.updated_at), 10)]) {
+		append(&problems, fmt.tprintf("invalid updated_at %q", pkg.updated_at))
+	}
+	if len(pkg.repo_urls) == 0 {
+		append(&problems, "no repo_urls")
+	}
+	for arch, url in pkg.repo_urls {
+		if !index.is_valid_url(url) {
+			append(&problems, fmt.tprintf("invalid repo URL for %s: %q", arch, url))
+		}
+	}
+
+	return problems[:]
+}
@@ -20,7 +20,7 @@ info_run :: proc(args: []string, config: ^Config) -> int {
 	idx, ok := index.index_load_or_fetch(config.index_url, false)
 	if !ok {
 		errors.log_error("Failed to load package index")
-		return 1
+		return errors.EXIT_INDEX_FAILED
 	}
 
 	for pkg_name in args {
@@ -30,8 +30,18 @@ info_run :: proc(args: []string, config: ^Config) -> int {
 			fmt.printf("Package: %s\n", pkg_name)
 			fmt.printf("Version: %s\n", pkg.version)
 			fmt.printf("Category: %s\n", pkg.category)
+			if len(pkg.updated_at) > 0 {
+				fmt.printf("Updated: %s\n", pkg.updated_at)
+			}
 			fmt.printf("Description: %s\n", pkg.short_desc)
-			fmt.printf("Source: VUP\n")
+			if len(pkg.license) > 0 {
+				fmt.printf("License: %s\n", pkg.license)
+			}
+			if len(pkg.source_repo) > 0 {
+				fmt.printf("Source: VUP (repo %s)\n", pkg.source_repo)
+			} else {
+				fmt.printf("Source: VUP\n")
+			}
 
 			// Show architectures
 			fmt.print("Architectures: ")
@@ -46,7 +56,8 @@ info_run :: proc(args: []string, config: ^Config) -> int {
 			// Try to fetch and show template info
 			if tmpl, tmpl_ok := resolve.fetch_and_parse_template(
 				pkg.category,
-				pkg_name,
+				index.package_template_name(pkg, pkg_name),
+				template_source(config),
 				context.temp_allocator,
 			); tmpl_ok {
 				if len(tmpl.depends) > 0 {
@@ -68,7 +79,7 @@ info_run :: proc(args: []string, config: ^Config) -> int {
 			// Check official repos
 			if utils.run_command({"xbps-query", "-R", pkg_name}) != 0 {
 				errors.log_error("Package '%s' not found", pkg_name)
-				return 1
+				return errors.EXIT_NOT_FOUND
 			}
 		}
 	}
@@ -1,12 +1,18 @@
 package commands
 
 import "core:fmt"
+import "core:slice"
 import "core:strings"
+import "core:time"
 
+import audit "../core/audit"
 import builder "../core/builder"
+import cfg "../core/config"
 import errors "../core/errors"
+import hooks "../core/hooks"
 import index "../core/index"
 import resolve "../core/resolve"
+import template "../core/template"
 import transaction "../core/transaction"
 import xbps "../core/xbps"
 import utils "../utils"
@@ -18,39 +24,118 @@ install_run :: proc(args: []string, config: ^Config) -> int {
 		errors.log_info("Syncing repository index...")
 		if xbps.sync_repos(utils.run_command) != 0 {
 			errors.log_error("Failed to sync repositories")
-			return 1
+			return errors.EXIT_XBPS_FAILED
 		}
 	}
 
-	// Update mode: -u (system upgrade)
+	// Update mode: -u (system upgrade), or -u <packages> for just those
 	if config.update_system {
+		if len(args) > 0 {
+			return install_update_packages(args, config)
+		}
 		return install_update(config)
 	}
 
+	// "-" or --stdin: package names come from stdin (stdin is then no TTY,
+	// so non-interactive mode has already been picked in main)
+	args := args
+	if config.read_stdin || slice.contains(args, "-") {
+		args = install_args_from_stdin(args)
+		if len(args) == 0 {
+			errors.log_info("No package names on stdin, nothing to do")
+			return 0
+		}
+	}
+
 	if len(args) == 0 {
 		fmt.println("Usage: vuru install <package> [packages...]")
+		fmt.println("       vuru install <category>/<package>  (template category override)")
 		fmt.println("       vuru install -S       (sync repos)")
 		fmt.println("       vuru install -Su      (full system update)")
+		fmt.println("       vuru install -        (package names from stdin)")
+		return 1
+	}
+
+	// --no-review downloads no templates, so there is nothing to preview
+	if config.no_review && (config.preview || len(config.template_file) > 0 || len(config.save_to) > 0) {
+		errors.log_error("--no-review can't be combined with --preview, --from-file or --save-to")
+		return 1
+	}
+
+	// The version picker needs someone to answer it
+	if config.select_version && config.yes && !config.dry_run {
+		errors.log_error("--select-version asks which version to install; it can't be used with -y or without a terminal")
 		return 1
 	}
 
+	// "category/package" (devel/vlang) names the template category
+	split_names, categories, categories_ok := install_split_categories(args)
+	if !categories_ok {
+		return 1
+	}
+
+	names, names_ok := normalize_package_names(split_names)
+	if !names_ok {
+		return 1
+	}
+	args = names
+
 	// Load index
 	idx, ok := index.index_load_or_fetch(config.index_url, false)
 	if !ok {
 		errors.log_error("Failed to load package index")
-		return 1
+		return errors.EXIT_INDEX_FAILED
+	}
+
+	if config.print_repo {
+		return install_print_repo(args, &idx)
+	}
+
+	if !install_fix_name_case(args, &idx) {
+		return errors.EXIT_NOT_FOUND
+	}
+
+	if !install_override_categories(args, categories, &idx) {
+		return errors.EXIT_NOT_FOUND
+	}
+
+	if config.idempotent {
+		args = install_skip_current(args, &idx)
+		if len(args) == 0 {
+			return 0
+		}
+	}
+
+	// Resolving VUP dependencies downloads templates: the first step
+	follow_templates := !config.no_review
+	if follow_templates {
+		switch install_step(config, "Fetch templates to resolve dependencies?") {
+		case .Continue:
+		case .Skip:
+			errors.log_info("Not fetching templates, xbps resolves VUP dependencies")
+			follow_templates = false
+		case .Abort:
+			errors.log_info("Installation cancelled")
+			return errors.EXIT_ABORTED
+		}
 	}
 
 	// Resolve dependencies for all packages at once
-	res, res_ok := resolve.resolve_deps(args, &idx, config.force_build)
+	res, res_ok := resolve.resolve_deps(
+		args,
+		&idx,
+		config.force_build,
+		template_source(config),
+		follow_templates = follow_templates,
+	)
 	if !res_ok {
 		if len(res.errors) > 0 {
 			for err in res.errors {
 				errors.print_error(err)
 			}
-		} else {
-			errors.log_error("Failed to resolve dependencies")
+			return errors.exit_code(res.errors[0].kind)
 		}
+		errors.log_error("Failed to resolve dependencies")
 		return 1
 	}
 
@@ -66,7 +151,7 @@ install_run :: proc(args: []string, config: ^Config) -> int {
 				strings.join(res.missing[:], ", ", context.temp_allocator),
 			)
 		}
-		return 1
+		return errors.EXIT_NOT_FOUND
 	}
 
 	// Dry run - just show what would happen
@@ -78,12 +163,101 @@ install_run :: proc(args: []string, config: ^Config) -> int {
 	// Create transaction
 	tx := transaction.transaction_from_resolution(&res)
 
+	// Template content as reviewed (or used to resolve), for --save-to
+	reviewed := make(map[string]string, context.temp_allocator)
+	for name, content in res.templates {
+		reviewed[name] = content
+	}
+
+	if config.select_version && !install_select_versions(&tx, args) {
+		errors.log_info("Installation cancelled")
+		return errors.EXIT_ABORTED
+	}
+
 	transaction.transaction_print(&tx)
 
-	// Confirm unless -y
-	if !config.yes && !transaction.transaction_confirm(&tx) {
+	if config.tree {
+		install_print_tree(&tx, config.rootdir)
+	}
+
+	// Preview - templates and xbps dry run, read-only: installing afterwards
+	// takes its own yes, even with -y, and without anyone to give it (CI, no
+	// TTY) the preview is all that happens. --confirm-each walks through the
+	// same steps one prompt at a time.
+	step_by_step := config.confirm_each && !config.yes && !config.no_review
+	if (config.preview || step_by_step) && !install_preview(&tx, &idx, config, &reviewed) {
 		errors.log_info("Installation cancelled")
-		return 0
+		return errors.EXIT_ABORTED
+	}
+	if config.preview && !config.interactive {
+		if reason, non_interactive := utils.non_interactive_reason(); non_interactive {
+			errors.log_info("Preview only (%s), nothing was installed", reason)
+			return 0
+		}
+	}
+
+	if len(config.template_file) > 0 && !install_review_local_template(&tx, args, config, &reviewed) {
+		errors.log_info("Installation cancelled")
+		return errors.EXIT_ABORTED
+	}
+
+	ret, installed := install_transaction(&tx, config, always_confirm = config.preview)
+	if ret != 0 || !installed {
+		return ret
+	}
+
+	if len(config.save_to) > 0 {
+		install_save_templates(&tx, reviewed, config)
+	}
+
+	if config.as_dependency {
+		if xbps.mark_automatic(args, config.rootdir, utils.run_command) != 0 {
+			errors.log_warning("Failed to mark %s as dependencies", strings.join(args, ", ", context.temp_allocator))
+		} else {
+			errors.log_info("Marked as installed automatically: %s", strings.join(args, ", ", context.temp_allocator))
+		}
+	}
+
+	print_binpkg_cache_usage(config.rootdir)
+
+	suggests_code := install_offer_suggests(args, &idx, config)
+
+	if config.notify {
+		utils.desktop_notify(
+			fmt.tprintf("vuru: %d package(s) installed", len(tx.items)),
+			strings.join(args, " ", context.temp_allocator),
+		)
+	}
+
+	return suggests_code
+}
+
+// Confirm and run a planned install transaction: repo checks, the prompt,
+// hooks, xbps and the history log. Shared by install and apply; returns the
+// exit code, 0 once everything is installed or when xbps-install was skipped
+// at --confirm-each; installed tells the two apart, so callers only run their
+// post-install steps after a real install. always_confirm asks even with -y.
+install_transaction :: proc(tx: ^transaction.Transaction, config: ^Config, always_confirm := false) -> (code: int, installed: bool) {
+	if !confirm_insecure_repos(install_repo_urls(tx), config) {
+		errors.log_info("Installation cancelled")
+		return errors.EXIT_ABORTED, false
+	}
+
+	// Confirm unless -y; --confirm-each asks about this step like the others
+	if config.confirm_each {
+		switch install_step(config, "Run xbps-install now?") {
+		case .Continue:
+		case .Skip:
+			// Asked for, so not a failure; abort is the way to stop with one
+			errors.log_info("Skipped xbps-install, nothing was installed")
+			return 0, false
+		case .Abort:
+			errors.log_info("Installation cancelled")
+			return errors.EXIT_ABORTED, false
+		}
+	} else if (!config.yes || always_confirm) && !transaction.transaction_confirm(tx, !config.default_no) {
+		errors.log_info("Installation cancelled")
+		return errors.EXIT_ABORTED, false
 	}
 
 	// Get build config if needed
@@ -100,17 +274,821 @@ install_run :: proc(args: []string, config: ^Config) -> int {
 		cfg_result, cfg_ok := builder.default_build_config()
 		if !cfg_ok {
 			errors.log_error("VUP repository not found. Run 'vuru clone' first.")
-			return 1
+			return 1, false
 		}
 		build_cfg = cfg_result
 	}
 
+	if !install_check_repo_keys(tx, config) {
+		return errors.EXIT_ABORTED, false
+	}
+
+	// Pre-install hooks can veto the whole transaction
+	hook_targets := install_hook_targets(tx)
+	if !hooks.hooks_run(.Pre_Install, hook_targets, config.rootdir) {
+		errors.log_error("Installation aborted by pre-install hook")
+		return errors.EXIT_ABORTED, false
+	}
+
 	// Execute
-	if !transaction.transaction_execute(&tx, &build_cfg, config.yes) {
+	executed := transaction.transaction_execute(
+		tx,
+		&build_cfg,
+		transaction.Execute_Options {
+			yes = config.yes,
+			progress = !config.quiet,
+			ignore_conf_repos = config.ignore_conf_repos,
+		},
+	)
+	install_record_history(hook_targets, executed)
+	if !executed && utils.command_interrupted() {
+		return errors.EXIT_ABORTED, false
+	}
+	if !executed {
+		if config.notify {
+			utils.desktop_notify("vuru: install failed", "See the terminal for details")
+		}
+		return errors.EXIT_XBPS_FAILED, false
+	}
+
+	if config.verify_installed {
+		install_verify_installed(tx)
+	}
+
+	// Packages are already installed, so a failing post-install hook only warns
+	if !hooks.hooks_run(.Post_Install, hook_targets, config.rootdir) {
+		errors.log_warning("A post-install hook failed")
+	}
+
+	return 0, true
+}
+
+// --verify-installed: xbps can report success without installing a package
+// (held packages, conflicts), so ask xbps-query for every VUP package of the
+// transaction and warn when it isn't there at the index version
+install_verify_installed :: proc(tx: ^transaction.Transaction) {
+	for item in tx.items {
+		if item.op != .Install_VUP && item.op != .Build_Install {
+			continue
+		}
+
+		installed, installed_ok := get_installed_version(item.name, context.temp_allocator)
+		if !installed_ok {
+			errors.log_warning("%s was not installed", item.name)
+		} else if installed != item.new_version {
+			errors.log_warning("%s is installed at %s, expected %s", item.name, installed, item.new_version)
+		}
+	}
+}
+
+// --idempotent: drop packages that are already installed at the index
+// version (or newer), so provisioning scripts can run install repeatedly.
+// Packages vuru doesn't index only have to be installed.
+install_skip_current :: proc(names: []string, idx: ^index.Index) -> []string {
+	remaining := make([dynamic]string, context.temp_allocator)
+	for name in names {
+		installed, installed_ok := get_installed_version(name, context.temp_allocator)
+		if !installed_ok {
+			append(&remaining, name)
+			continue
+		}
+
+		if pkg, found := index.index_get_package(idx, name);
+		   found && xbps.version_compare(installed, pkg.version) < 0 {
+			append(&remaining, name)
+			continue
+		}
+		errors.log_info("%s already at %s", name, installed)
+	}
+	return remaining[:]
+}
+
+// --select-version: for each requested VUP binary package, list the versions
+// its repo (and the system repos) offer and let the user pick one. The choice
+// is installed as an exact pkgver; Enter keeps the index version. Returns
+// false when the prompt is aborted (EOF).
+install_select_versions :: proc(tx: ^transaction.Transaction, names: []string) -> bool {
+	for &item in tx.items {
+		if !slice.contains(names, item.name) {
+			continue
+		}
+		if item.op == .Build_Install {
+			errors.log_warning("%s is built from its template, --select-version only applies to binary packages", item.name)
+			continue
+		}
+		if item.op != .Install_VUP {
+			continue
+		}
+
+		versions, ok := xbps.get_repo_versions(item.name, item.repo_url, utils.run_command_output, context.temp_allocator)
+		if !ok || len(versions) == 0 {
+			errors.log_warning("Failed to query the versions of %s, installing %s", item.name, item.new_version)
+			continue
+		}
+		if len(versions) == 1 {
+			errors.log_info("%s: only %s is available", item.name, versions[0])
+			continue
+		}
+
+		fmt.printf("\nVersions of %s:\n", item.name)
+		for v, i in versions {
+			fmt.printf("  %d) %s%s\n", i + 1, v, " (index)" if v == item.new_version else "")
+		}
+
+		choice, choice_ok := utils.prompt_number(
+			fmt.tprintf("Version to install [1-%d, Enter for %s]:", len(versions), item.new_version),
+			len(versions),
+		)
+		if !choice_ok {
+			return false
+		}
+		if choice > 0 {
+			item.new_version = strings.clone(versions[choice - 1], tx.allocator)
+			item.exact = true
+		}
+	}
+
+	return true
+}
+
+// --print-repo: print "<name>\t<version>\t<repo url>" per package for this
+// architecture and stop, without templates or installing. Fails when a
+// package isn't in the index or has no build for the architecture.
+install_print_repo :: proc(names: []string, idx: ^index.Index) -> int {
+	arch, arch_ok := cfg.get_arch()
+	if !arch_ok {
+		errors.print_error(errors.make_error(.Arch_Detection_Failed))
 		return 1
 	}
 
-	return 0
+	ret := 0
+	for name in names {
+		pkg, found := index.index_get_package(idx, name)
+		if !found {
+			errors.log_error("%s is not a VUP package", name)
+			ret = errors.EXIT_NOT_FOUND
+			continue
+		}
+
+		repo_url, url_ok := pkg.repo_urls[arch]
+		if !url_ok {
+			errors.log_error("%s has no package for %s", name, arch)
+			ret = errors.EXIT_NOT_FOUND
+			continue
+		}
+		fmt.printf("%s\t%s\t%s\n", name, pkg.version, repo_url)
+	}
+	return ret
+}
+
+// Offer the "suggests" of the requested packages that aren't installed yet.
+// Nothing is installed without an explicit yes, not even with -y. Returns the
+// exit code of installing them, 0 when none were offered or taken.
+install_offer_suggests :: proc(names: []string, idx: ^index.Index, config: ^Config) -> int {
+	offered := make([dynamic]string, context.temp_allocator)
+	for name in names {
+		pkg, ok := index.index_get_package(idx, name)
+		if !ok {
+			continue
+		}
+		for s in pkg.suggests {
+			if !utils.is_valid_package_name(s) || slice.contains(offered[:], s) || slice.contains(names, s) {
+				continue
+			}
+			if _, installed := get_installed_version(s, context.temp_allocator); installed {
+				continue
+			}
+			append(&offered, s)
+		}
+	}
+
+	if len(offered) == 0 {
+		return 0
+	}
+
+	fmt.println()
+	fmt.println("Suggested packages:")
+	for s in offered {
+		fmt.printf("  %s\n", s)
+	}
+
+	if config.yes {
+		errors.log_info("Install them with: vuru install %s", strings.join(offered[:], " ", context.temp_allocator))
+		return 0
+	}
+
+	if !utils.prompt_yes_no("Install suggested packages too?", false) {
+		return 0
+	}
+
+	code := install_run(offered[:], config)
+	if code != 0 {
+		errors.log_warning("Suggested packages were not installed, the requested ones were")
+	}
+	return code
+}
+
+// Replace names that only miss the index by case ("Firefox") with the
+// indexed name. Fails if a name matches several packages ignoring case.
+install_fix_name_case :: proc(names: []string, idx: ^index.Index) -> bool {
+	for &name in names {
+		if index.index_has_package(idx, name) {
+			continue
+		}
+
+		matches := index.index_find_package_ci(idx, name, context.temp_allocator)
+		switch len(matches) {
+		case 0:
+			// Not a VUP package; official repos are searched as typed
+		case 1:
+			errors.log_info("Resolved %s to %s", name, matches[0])
+			name = matches[0]
+		case:
+			slice.sort(matches)
+			errors.log_error(
+				"'%s' is ambiguous, did you mean one of: %s",
+				name,
+				strings.join(matches, ", ", context.temp_allocator),
+			)
+			return false
+		}
+	}
+	return true
+}
+
+// Trim package names from the command line and reject malformed ones
+// (slashes, whitespace, control characters) before they reach template
+// URLs or sudo xbps-* commands
+normalize_package_names :: proc(names: []string) -> ([]string, bool) {
+	result := make([]string, len(names), context.temp_allocator)
+	ok := true
+	for name, i in names {
+		result[i] = strings.trim_space(name)
+		if !utils.is_valid_package_name(result[i]) {
+			errors.log_error("Invalid package name: %q", name)
+			ok = false
+		}
+	}
+	return result, ok
+}
+
+// Split "category/package" arguments. Returns the package names and, at the
+// same positions, the categories given (empty for plain names).
+install_split_categories :: proc(args: []string) -> (names: []string, categories: []string, ok: bool) {
+	names = make([]string, len(args), context.temp_allocator)
+	categories = make([]string, len(args), context.temp_allocator)
+	ok = true
+
+	for arg, i in args {
+		names[i] = arg
+		slash := strings.index_byte(arg, '/')
+		if slash < 0 {
+			continue
+		}
+
+		category := strings.trim_space(arg[:slash])
+		if !utils.is_valid_identifier(category) || strings.count(arg, "/") > 1 {
+			errors.log_error("Invalid package: %q (expected <category>/<package>)", arg)
+			ok = false
+			continue
+		}
+		categories[i] = category
+		names[i] = arg[slash + 1:]
+	}
+	return
+}
+
+// Use the categories given as "category/package" for this run, so templates
+// are fetched from there even when the index files the package elsewhere.
+// The package itself still has to be in the index. The package map is shared
+// with the index cached for the whole process (index_load_or_fetch), so the
+// overrides go into a copy that only this idx, the resolver input, sees.
+install_override_categories :: proc(names: []string, categories: []string, idx: ^index.Index) -> bool {
+	copied := false
+	for category, i in categories {
+		if len(category) == 0 {
+			continue
+		}
+
+		name := names[i]
+		pkg, found := index.index_get_package(idx, name)
+		if !found {
+			errors.print_error(errors.make_error(.Package_Not_Found, name))
+			return false
+		}
+		if pkg.category == category {
+			continue
+		}
+
+		if len(pkg.category) > 0 {
+			errors.log_info("Using category %s for %s (the index says %s)", category, name, pkg.category)
+		}
+		if !copied {
+			packages := make(map[string]index.Package_Info, len(idx.packages), context.temp_allocator)
+			for k, v in idx.packages {
+				packages[k] = v
+			}
+			idx.packages = packages
+			copied = true
+		}
+		pkg.category = category
+		idx.packages[name] = pkg
+	}
+	return true
+}
+
+// Replace "-" in args with the package names read from stdin
+install_args_from_stdin :: proc(args: []string) -> []string {
+	result := make([dynamic]string, context.temp_allocator)
+	for arg in args {
+		if arg != "-" {
+			append(&result, arg)
+		}
+	}
+	append(&result, ..utils.read_stdin_words(context.temp_allocator))
+	return result[:]
+}
+
+// Packages of a transaction, as passed to install hooks
+install_hook_targets :: proc(tx: ^transaction.Transaction) -> []hooks.Hook_Target {
+	targets := make([dynamic]hooks.Hook_Target, context.temp_allocator)
+
+	for item in tx.items {
+		source: string
+		#partial switch item.op {
+		case .Install_VUP:
+			source = "vup"
+		case .Install_Official:
+			source = "official"
+		case .Build_Install:
+			source = "build"
+		case:
+			continue
+		}
+		append(&targets, hooks.Hook_Target{item.name, item.new_version, source})
+	}
+
+	return targets[:]
+}
+
+// Gate plain http:// repos, which are handed to sudo xbps-install -R and can
+// be tampered with in transit (signatures only help once the key is trusted).
+// --allow-insecure-repo accepts them; otherwise the user is asked, and -y
+// refuses.
+confirm_insecure_repos :: proc(repo_urls: []string, config: ^Config) -> bool {
+	insecure := make([dynamic]string, context.temp_allocator)
+	for url in repo_urls {
+		if utils.is_plain_http_url(url) && !slice.contains(insecure[:], url) {
+			append(&insecure, url)
+		}
+	}
+	if len(insecure) == 0 {
+		return true
+	}
+
+	for url in insecure {
+		errors.log_warning("Repository uses plain HTTP: %s", url)
+	}
+	if config.allow_insecure {
+		return true
+	}
+
+	if config.yes {
+		errors.log_error("Refusing plain HTTP repositories without --allow-insecure-repo")
+		return false
+	}
+	return utils.prompt_yes_no("Packages could be tampered with in transit. Continue anyway?", false)
+}
+
+// Repo URLs of the VUP binary installs of a transaction
+install_repo_urls :: proc(tx: ^transaction.Transaction) -> []string {
+	urls := make([dynamic]string, context.temp_allocator)
+	for item in tx.items {
+		if item.op == .Install_VUP {
+			append(&urls, item.repo_url)
+		}
+	}
+	return urls[:]
+}
+
+// Make sure xbps won't stop at a signing key question nobody can answer.
+// Known but untrusted keys are imported with --trust-repo, otherwise the
+// install stops with instructions under -y. Repos never synced have no
+// known key yet, so xbps may still ask about those.
+install_check_repo_keys :: proc(tx: ^transaction.Transaction, config: ^Config) -> bool {
+	checked := make(map[string]bool, context.temp_allocator)
+
+	for item in tx.items {
+		if item.op != .Install_VUP || item.repo_url in checked {
+			continue
+		}
+		checked[item.repo_url] = true
+
+		fingerprint, ok := xbps.repo_key_fingerprint(
+			item.repo_url,
+			utils.run_command_output,
+			context.temp_allocator,
+		)
+		if ok && len(fingerprint) > 0 && xbps.repo_key_trusted(fingerprint) {
+			continue
+		}
+
+		if config.trust_repo {
+			errors.log_info("Importing signing key of %s", item.repo_url)
+			if xbps.repo_import_key(item.repo_url, utils.run_command_with_input) != 0 {
+				errors.log_error("Failed to import signing key of %s", item.repo_url)
+				return false
+			}
+			continue
+		}
+
+		if !ok || len(fingerprint) == 0 {
+			if config.yes {
+				errors.log_warning(
+					"%s has not been synced yet; if xbps asks to import its key, re-run with --trust-repo",
+					item.repo_url,
+				)
+			}
+			continue
+		}
+
+		if config.yes {
+			errors.log_error("Signing key %s of %s is not trusted", fingerprint, item.repo_url)
+			fmt.println("xbps can't ask to import it with -y. Re-run with --trust-repo, or import it once with:")
+			fmt.printf("  sudo xbps-install -S --repository=%s\n", item.repo_url)
+			return false
+		}
+		errors.log_info("xbps will ask to import signing key %s of %s", fingerprint, item.repo_url)
+	}
+
+	return true
+}
+
+// Log installed packages to the audit log (vuru history)
+install_record_history :: proc(targets: []hooks.Hook_Target, ok: bool) {
+	for t in targets {
+		audit.audit_record("build" if t.source == "build" else "install", t.name, t.version, ok)
+	}
+}
+
+// --confirm-each: ask before a step of the install, which just continues
+// without the flag or with -y
+install_step :: proc(config: ^Config, question: string) -> utils.Step_Answer {
+	if !config.confirm_each || config.yes {
+		return .Continue
+	}
+	return utils.prompt_step(question)
+}
+
+// Show what an install would do: repo URLs, templates (or their diff against
+// the cached copy) and the transaction xbps plans, without changing anything.
+// Templates fetched here are recorded in reviewed. False when the user aborts
+// at a --confirm-each step.
+install_preview :: proc(
+	tx: ^transaction.Transaction,
+	idx: ^index.Index,
+	config: ^Config,
+	reviewed: ^map[string]string,
+) -> bool {
+	official_pkgs := make([dynamic]string, context.temp_allocator)
+
+	for item in tx.items {
+		if item.op == .Install_Official {
+			append(&official_pkgs, item.name)
+			continue
+		}
+		if item.op != .Install_VUP {
+			continue
+		}
+
+		fmt.printf(
+			"%s==> %s-%s%s\n",
+			errors.COLOR_INFO,
+			item.name,
+			item.new_version,
+			errors.COLOR_RESET,
+		)
+		fmt.printf("Repository: %s\n", item.repo_url)
+
+		tmpl, tmpl_ok := "", false
+		switch install_step(config, fmt.tprintf("Fetch the template of %s?", item.name)) {
+		case .Continue:
+			tmpl, tmpl_ok = install_item_template(item, idx, config)
+			if tmpl_ok {
+				reviewed[item.name] = tmpl
+			}
+		case .Skip:
+		case .Abort:
+			return false
+		}
+
+		show := utils.Step_Answer.Skip
+		if tmpl_ok {
+			show = install_step(config, fmt.tprintf("Show the template of %s?", item.name))
+		}
+		if show == .Abort {
+			return false
+		}
+		if show == .Continue {
+			cached, cached_ok := template.cache_get_template(item.name, context.temp_allocator)
+			if cached_ok && cached == tmpl {
+				fmt.println("Template: unchanged since last install")
+			} else {
+				fmt.println(
+					"Template (diff against cached):" if cached_ok else "Template (new package):",
+				)
+				diff, diff_ok := utils.diff_generate(
+					cached if cached_ok else "",
+					tmpl,
+					context.temp_allocator,
+					item.name if config.keep_diff_files else "",
+				)
+				if diff_ok {
+					fmt.println(diff)
+				}
+			}
+		}
+
+		entries, dry_ok := xbps.install_dry_run(
+			item.repo_url,
+			{item.name},
+			config.rootdir,
+			utils.run_command_output,
+			context.temp_allocator,
+		)
+		print_dry_run(entries, dry_ok)
+		fmt.println()
+	}
+
+	if len(official_pkgs) > 0 {
+		fmt.printf("%s==> Official packages%s\n", errors.COLOR_INFO, errors.COLOR_RESET)
+		entries, dry_ok := xbps.install_dry_run(
+			"",
+			official_pkgs[:],
+			config.rootdir,
+			utils.run_command_output,
+			context.temp_allocator,
+		)
+		print_dry_run(entries, dry_ok)
+		fmt.println()
+	}
+
+	return true
+}
+
+// --save-to: archive the templates of the VUP packages just installed as
+// <dir>/<pkg>-<version>-<YYYY-MM-DD>.template, for records of what was
+// reviewed: the content shown or used during this install (reviewed), never a
+// fresh download that could differ. Built packages get the template xbps-src
+// built them from. The packages are installed by then, so failures only warn.
+install_save_templates :: proc(tx: ^transaction.Transaction, reviewed: map[string]string, config: ^Config) {
+	if !utils.mkdir_p(config.save_to) {
+		errors.log_warning("Failed to create directory: %s", config.save_to)
+		return
+	}
+
+	year, month, day := time.date(time.now())
+	date := fmt.tprintf("%04d-%02d-%02d", year, int(month), day)
+
+	build_cfg: builder.Build_Config
+	build_cfg_ok := false
+
+	for item in tx.items {
+		tmpl: string
+		tmpl_ok := false
+		#partial switch item.op {
+		case .Install_VUP:
+			tmpl, tmpl_ok = reviewed[item.name]
+		case .Build_Install:
+			if !build_cfg_ok {
+				build_cfg, build_cfg_ok = builder.default_build_config(context.temp_allocator)
+			}
+			if build_cfg_ok {
+				tmpl, tmpl_ok = utils.read_file(
+					utils.path_join(
+						build_cfg.vup_dir,
+						"srcpkgs",
+						item.category,
+						item.name,
+						"template",
+						allocator = context.temp_allocator,
+					),
+					context.temp_allocator,
+				)
+			}
+		case:
+			continue
+		}
+		if !tmpl_ok {
+			errors.log_warning("The template of %s wasn't fetched during this install, not saved", item.name)
+			continue
+		}
+
+		path := utils.path_join(
+			config.save_to,
+			fmt.tprintf("%s-%s-%s.template", item.name, item.new_version, date),
+			allocator = context.temp_allocator,
+		)
+		if !utils.write_file_atomic(path, tmpl) {
+			errors.log_warning("Failed to write %s", path)
+			continue
+		}
+		errors.log_info("Saved template of %s to %s", item.name, path)
+	}
+}
+
+// Template of a VUP install: the --from-file template for the requested
+// package, the upstream one otherwise
+@(private)
+install_item_template :: proc(
+	item: transaction.Transaction_Item,
+	idx: ^index.Index,
+	config: ^Config,
+) -> (
+	string,
+	bool,
+) {
+	if len(config.template_file) > 0 && item.reason == "explicit" {
+		return utils.read_file(config.template_file, context.temp_allocator)
+	}
+
+	pkg, _ := index.index_get_package(idx, item.name)
+	return template.fetch_template(
+		item.category,
+		index.package_template_name(pkg, item.name),
+		template_source(config),
+		context.temp_allocator,
+	)
+}
+
+// Review a locally edited template (--from-file) against the cached one
+// before installing. Only the review uses it: the binpkg still comes from the
+// package's repo in the index, and the local template is not cached.
+install_review_local_template :: proc(
+	tx: ^transaction.Transaction,
+	args: []string,
+	config: ^Config,
+	reviewed: ^map[string]string,
+) -> bool {
+	if len(args) != 1 {
+		errors.log_error("--from-file takes exactly one package to install")
+		return false
+	}
+
+	repo_url := ""
+	for item in tx.items {
+		if item.name == args[0] && item.op == .Install_VUP {
+			repo_url = item.repo_url
+		}
+	}
+	if len(repo_url) == 0 {
+		errors.log_error("--from-file only applies to VUP binary installs, %s is not one", args[0])
+		return false
+	}
+
+	local, ok := utils.read_file(config.template_file, context.temp_allocator)
+	if !ok || len(local) == 0 {
+		errors.log_error("Failed to read template: %s", config.template_file)
+		return false
+	}
+	reviewed[args[0]] = local
+
+	errors.log_info(
+		"Reviewing local template %s; %s itself is installed from %s",
+		config.template_file,
+		args[0],
+		repo_url,
+	)
+	if config.yes {
+		return true
+	}
+
+	cached, _ := template.cache_get_template(args[0], context.temp_allocator)
+	return utils.review_changes(args[0], local, cached, !config.default_no, config.keep_diff_files)
+}
+
+// Print a parsed xbps dry run with size totals
+@(private)
+print_dry_run :: proc(entries: []xbps.Dry_Run_Entry, ok: bool) {
+	if !ok {
+		errors.log_warning("xbps dry run unavailable (repository not synced yet? try 'vuru install -S')")
+		return
+	}
+
+	fmt.println("xbps transaction:")
+	installed_total: i64 = 0
+	download_total: i64 = 0
+	for e in entries {
+		fmt.printf(
+			"  %-40s %-10s %10s\n",
+			e.pkgver,
+			e.action,
+			utils.format_size(e.download_size, context.temp_allocator),
+		)
+		installed_total += e.installed_size
+		download_total += e.download_size
+	}
+	fmt.printf(
+		"Download: %s, installed size: %s\n",
+		utils.format_size(download_total, context.temp_allocator),
+		utils.format_size(installed_total, context.temp_allocator),
+	)
+}
+
+// Show what xbps would pull in as a dependency tree (--tree). The package
+// set comes from an xbps dry run per repo, the edges from xbps-query -x;
+// dependencies that are already installed (in rootdir, if set) are left out.
+install_print_tree :: proc(tx: ^transaction.Transaction, rootdir: string) {
+	// Requested packages per repo ("" = official repos)
+	roots := make(map[string][dynamic]string, context.temp_allocator)
+	for item in tx.items {
+		url: string
+		#partial switch item.op {
+		case .Install_Official:
+			url = ""
+		case .Install_VUP:
+			url = item.repo_url
+		case:
+			continue
+		}
+		if url not_in roots {
+			roots[url] = make([dynamic]string, context.temp_allocator)
+		}
+		append(&roots[url], item.name)
+	}
+
+	fmt.println("Dependency tree:")
+	for url, names in roots {
+		entries, ok := xbps.install_dry_run(url, names[:], rootdir, utils.run_command_output, context.temp_allocator)
+		if !ok {
+			errors.log_warning("xbps dry run unavailable (repository not synced yet? try 'vuru install -S')")
+			continue
+		}
+
+		by_name := make(map[string]xbps.Dry_Run_Entry, context.temp_allocator)
+		for e in entries {
+			if name, _, parse_ok := xbps.parse_pkgver_simple(e.pkgver); parse_ok {
+				by_name[name] = e
+			}
+		}
+
+		children := make(map[string][]string, context.temp_allocator)
+		for name, e in by_name {
+			deps, deps_ok := xbps.get_repo_deps(e.pkgver, url, rootdir, utils.run_command_output, context.temp_allocator)
+			if !deps_ok {
+				continue
+			}
+			pulled := make([dynamic]string, context.temp_allocator)
+			for dep in deps {
+				if dep in by_name && dep != name {
+					append(&pulled, dep)
+				}
+			}
+			children[name] = pulled[:]
+		}
+
+		seen := make(map[string]bool, context.temp_allocator)
+		for name in names {
+			print_tree_node(name, "", "", by_name, children, &seen)
+		}
+	}
+	fmt.println()
+}
+
+// Print a package and, the first time it appears, its dependencies
+@(private)
+print_tree_node :: proc(
+	name: string,
+	prefix: string,
+	child_prefix: string,
+	by_name: map[string]xbps.Dry_Run_Entry,
+	children: map[string][]string,
+	seen: ^map[string]bool,
+) {
+	e, in_tx := by_name[name]
+	if !in_tx {
+		return
+	}
+
+	if name in seen^ {
+		fmt.printf("%s%s (see above)\n", prefix, e.pkgver)
+		return
+	}
+	seen^[name] = true
+	fmt.printf("%s%s %s(%s)%s\n", prefix, e.pkgver, errors.COLOR_DIM, e.action, errors.COLOR_RESET)
+
+	deps := children[name]
+	for dep, i in deps {
+		last := i == len(deps) - 1
+		print_tree_node(
+			dep,
+			strings.concatenate({child_prefix, "└── " if last else "├── "}, context.temp_allocator),
+			strings.concatenate({child_prefix, "    " if last else "│   "}, context.temp_allocator),
+			by_name,
+			children,
+			seen,
+		)
+	}
 }
 
 // System upgrade (xbps-install -u)
@@ -135,9 +1113,54 @@ install_update :: proc(config: ^Config) -> int {
 	sys_ret := utils.run_command(cmd[:])
 
 	if sys_ret != 0 {
-		return sys_ret
+		return errors.EXIT_XBPS_FAILED
 	}
 
 	// Also update VUP packages
 	return update_run(nil, config)
 }
+
+// -u with package names ("vuru -u foo"): update just those packages, and only
+// when an update is available. Unlike a plain install nothing is reinstalled,
+// and packages that aren't installed are an error rather than installed.
+install_update_packages :: proc(args: []string, config: ^Config) -> int {
+	names, names_ok := normalize_package_names(args)
+	if !names_ok {
+		return 1
+	}
+
+	idx, ok := index.index_load_or_fetch(config.index_url, true)
+	if !ok {
+		errors.log_error("Failed to load package index")
+		return errors.EXIT_INDEX_FAILED
+	}
+
+	vup := make([dynamic]string, context.temp_allocator)
+	official := make([dynamic]string, context.temp_allocator)
+	for name in names {
+		if _, installed := get_installed_version(name, context.temp_allocator); !installed {
+			errors.log_error("%s is not installed (use 'vuru install %s')", name, name)
+			return errors.EXIT_NOT_FOUND
+		}
+		if _, in_index := index.index_get_package(&idx, name); in_index {
+			append(&vup, name)
+		} else {
+			append(&official, name)
+		}
+	}
+
+	if len(official) > 0 {
+		errors.log_info("Updating %s...", strings.join(official[:], ", ", context.temp_allocator))
+		if xbps.upgrade_official_packages(official[:], config.yes, config.dry_run, utils.run_command) != 0 {
+			return errors.EXIT_XBPS_FAILED
+		}
+	}
+
+	if len(vup) == 0 {
+		return 0
+	}
+	if config.dry_run {
+		return update_list(&idx, config, vup[:])
+	}
+	return xbps_upgrade_all(&idx, config, vup[:])
+}
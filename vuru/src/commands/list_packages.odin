@@ -0,0 +1,76 @@
+package commands
+
+import "core:encoding/json"
+import "core:fmt"
+import "core:slice"
+
+import errors "../core/errors"
+import index "../core/index"
+
+// One package of `list-packages --json`
+@(private)
+Package_Record :: struct {
+	name:       string `json:"name"`,
+	version:    string `json:"version"`,
+	category:   string `json:"category"`,
+	short_desc: string `json:"short_desc"`,
+	source_pkg: string `json:"source_pkg"`,
+	repo:       string `json:"repo"`,
+	updated_at: string `json:"updated_at,omitempty"`,
+	license:    string `json:"license,omitempty"`,
+	suggests:   []string `json:"suggests"`,
+	repo_urls:  map[string]string `json:"repo_urls"`,
+}
+
+// List-packages command - every index package name, one per line, for
+// scripts and completion. With --json each line is the full package as a
+// JSON object (NDJSON), written as it is encoded. --new-since keeps packages
+// the index dates on or after that day.
+list_packages_run :: proc(args: []string, config: ^Config) -> int {
+	idx, ok := index.index_load_or_fetch(config.index_url, false)
+	if !ok {
+		errors.log_error("Failed to load package index")
+		return errors.EXIT_INDEX_FAILED
+	}
+
+	names := make([dynamic]string, 0, len(idx.packages), context.temp_allocator)
+	for name, pkg in idx.packages {
+		if len(config.new_since) > 0 && !index.package_updated_since(pkg, config.new_since) {
+			continue
+		}
+		append(&names, name)
+	}
+	slice.sort(names[:])
+
+	if !config.json {
+		for name in names {
+			fmt.println(name)
+		}
+		return 0
+	}
+
+	for name in names {
+		pkg := idx.packages[name]
+		record := Package_Record {
+			name = name,
+			version = pkg.version,
+			category = pkg.category,
+			short_desc = pkg.short_desc,
+			source_pkg = pkg.source_pkg,
+			repo = index.package_repo_name(pkg),
+			updated_at = pkg.updated_at,
+			license = pkg.license,
+			suggests = pkg.suggests,
+			repo_urls = pkg.repo_urls,
+		}
+
+		data, err := json.marshal(record, allocator = context.temp_allocator)
+		if err != nil {
+			errors.log_error("Failed to encode %s", name)
+			return 1
+		}
+		fmt.println(string(data))
+	}
+
+	return 0
+}
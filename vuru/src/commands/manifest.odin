@@ -0,0 +1,222 @@
+package commands
+
+import "core:fmt"
+import "core:strings"
+
+import errors "../core/errors"
+import index "../core/index"
+import xbps "../core/xbps"
+import utils "../utils"
+
+// Manifests - `vuru apply system.toml` installs the packages a manifest lists.
+// The format is a small TOML subset:
+//
+//     # Plain package names
+//     packages = ["vlang", "odin"]
+//
+//     # Packages with options
+//     [package.visual-studio-code]
+//     version = "1.90.0_1"    # exact pkgver, revision included
+//     repo = "https://..."    # install from this repo instead of the index one
+//     pin = true              # hold back from `vuru update` (see `vuru pin`)
+
+Manifest_Package :: struct {
+	name:    string,
+	version: string,
+	repo:    string,
+	pin:     bool,
+}
+
+Manifest :: struct {
+	packages: []Manifest_Package,
+}
+
+// Read and check a manifest. Everything lives in the temp allocator.
+manifest_read :: proc(path: string) -> (Manifest, bool) {
+	content, ok := utils.read_file(path, context.temp_allocator)
+	if !ok {
+		errors.log_error("Failed to read manifest: %s", path)
+		return {}, false
+	}
+
+	packages := make([dynamic]Manifest_Package, context.temp_allocator)
+	positions := make(map[string]int, context.temp_allocator)
+	tables := make(map[string]bool, context.temp_allocator)
+	current := -1 // Package of the current [package.<name>] table
+
+	// Index of a package's entry, added on first mention
+	entry :: proc(packages: ^[dynamic]Manifest_Package, positions: ^map[string]int, name: string) -> int {
+		if pos, found := positions^[name]; found {
+			return pos
+		}
+		append(packages, Manifest_Package{name = name})
+		positions^[name] = len(packages) - 1
+		return len(packages) - 1
+	}
+
+	lines := strings.split_lines(content, context.temp_allocator)
+	for i := 0; i < len(lines); i += 1 {
+		line_no := i + 1
+		line := manifest_strip_comment(lines[i])
+		if len(line) == 0 {
+			continue
+		}
+
+		if line[0] == '[' {
+			if line[len(line) - 1] != ']' || !strings.has_prefix(line, "[package.") {
+				errors.log_error("%s:%d: expected [package.<name>]", path, line_no)
+				return {}, false
+			}
+			name := strings.trim_space(line[len("[package."):len(line) - 1])
+			if unquoted, quoted := manifest_string(name); quoted {
+				name = unquoted
+			}
+			if !utils.is_valid_package_name(name) {
+				errors.log_error("%s:%d: invalid package name: %s", path, line_no, name)
+				return {}, false
+			}
+			if name in tables {
+				errors.log_error("%s:%d: [package.%s] appears twice", path, line_no, name)
+				return {}, false
+			}
+			tables[name] = true
+			current = entry(&packages, &positions, name)
+			continue
+		}
+
+		eq := strings.index_byte(line, '=')
+		if eq < 0 {
+			errors.log_error("%s:%d: expected key = value", path, line_no)
+			return {}, false
+		}
+		key := strings.trim_space(line[:eq])
+		value := strings.trim_space(line[eq + 1:])
+
+		if current < 0 {
+			if key != "packages" {
+				errors.log_error("%s:%d: unknown key %q (expected packages)", path, line_no, key)
+				return {}, false
+			}
+
+			// Arrays may span lines up to the closing bracket
+			for strings.has_prefix(value, "[") && !strings.has_suffix(value, "]") && i + 1 < len(lines) {
+				i += 1
+				value = strings.concatenate({value, " ", manifest_strip_comment(lines[i])}, context.temp_allocator)
+			}
+
+			names, names_ok := manifest_string_array(value)
+			if !names_ok {
+				errors.log_error("%s:%d: packages must be an array of strings", path, line_no)
+				return {}, false
+			}
+			for name in names {
+				if !utils.is_valid_package_name(name) {
+					errors.log_error("%s:%d: invalid package name: %s", path, line_no, name)
+					return {}, false
+				}
+				entry(&packages, &positions, name)
+			}
+			continue
+		}
+
+		pkg := &packages[current]
+		switch key {
+		case "version":
+			version, version_ok := manifest_string(value)
+			if _, has_revision := xbps.version_revision(version); !version_ok || !has_revision {
+				errors.log_error(
+					"%s:%d: version of %s must be a string with its revision, e.g. \"1.2.3_1\"",
+					path,
+					line_no,
+					pkg.name,
+				)
+				return {}, false
+			}
+			pkg.version = version
+		case "repo":
+			repo, repo_ok := manifest_string(value)
+			if !repo_ok || !index.is_valid_url(repo) {
+				errors.log_error("%s:%d: repo of %s must be a repository URL", path, line_no, pkg.name)
+				return {}, false
+			}
+			pkg.repo = repo
+		case "pin":
+			if value != "true" && value != "false" {
+				errors.log_error("%s:%d: pin of %s must be true or false", path, line_no, pkg.name)
+				return {}, false
+			}
+			pkg.pin = value == "true"
+		case:
+			errors.log_error("%s:%d: unknown key %q (expected version, repo or pin)", path, line_no, key)
+			return {}, false
+		}
+	}
+
+	return Manifest{packages = packages[:]}, true
+}
+
+// A manifest line without its comment and surrounding whitespace. A '#'
+// inside a string doesn't start a comment.
+@(private)
+manifest_strip_comment :: proc(line: string) -> string {
+	quote: byte = 0
+	for i := 0; i < len(line); i += 1 {
+		c := line[i]
+		switch {
+		case quote != 0:
+			if c == quote {
+				quote = 0
+			}
+		case c == '"' || c == '\'':
+			quote = c
+		case c == '#':
+			return strings.trim_space(line[:i])
+		}
+	}
+	return strings.trim_space(line)
+}
+
+// Contents of a "double" or 'single' quoted string (no escapes)
+@(private)
+manifest_string :: proc(value: string) -> (string, bool) {
+	if len(value) < 2 || (value[0] != '"' && value[0] != '\'') || value[len(value) - 1] != value[0] {
+		return "", false
+	}
+	s := value[1:len(value) - 1]
+	if strings.index_byte(s, value[0]) >= 0 {
+		return "", false
+	}
+	return s, true
+}
+
+// Strings of a ["a", "b"] array, trailing comma allowed
+@(private)
+manifest_string_array :: proc(value: string) -> ([]string, bool) {
+	if !strings.has_prefix(value, "[") || !strings.has_suffix(value, "]") {
+		return nil, false
+	}
+
+	result := make([dynamic]string, context.temp_allocator)
+	inner := value[1:len(value) - 1]
+	for item in strings.split_iterator(&inner, ",") {
+		trimmed := strings.trim_space(item)
+		if len(trimmed) == 0 {
+			continue
+		}
+		s, ok := manifest_string(trimmed)
+		if !ok {
+			return nil, false
+		}
+		append(&result, s)
+	}
+	return result[:], true
+}
+
+// "name" or "name version" for listings
+@(private)
+manifest_package_label :: proc(pkg: Manifest_Package) -> string {
+	if len(pkg.version) > 0 {
+		return fmt.tprintf("%s %s", pkg.name, pkg.version)
+	}
+	return pkg.name
+}
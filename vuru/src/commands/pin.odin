@@ -0,0 +1,82 @@
+package commands
+
+import "core:fmt"
+import "core:slice"
+
+import errors "../core/errors"
+import pins "../core/pins"
+import utils "../utils"
+
+// Pin command - hold packages back from `vuru update`; lists pins without arguments
+pin_run :: proc(args: []string, config: ^Config) -> int {
+	pinned, ok := pins.pins_load(context.temp_allocator)
+	if !ok {
+		return 1
+	}
+
+	if len(args) == 0 {
+		for name in pinned {
+			fmt.println(name)
+		}
+		return 0
+	}
+
+	updated := make([dynamic]string, context.temp_allocator)
+	append(&updated, ..pinned)
+	for name in args {
+		if !utils.is_valid_package_name(name) {
+			errors.log_error("Invalid package name: %s", name)
+			return 1
+		}
+		if slice.contains(updated[:], name) {
+			errors.log_info("%s is already pinned", name)
+			continue
+		}
+		append(&updated, name)
+		errors.log_info("Pinned %s", name)
+	}
+
+	slice.sort(updated[:])
+	if !pins.pins_save(updated[:]) {
+		errors.log_error("Failed to save pin list")
+		return 1
+	}
+	return 0
+}
+
+// Unpin command - let packages be upgraded again
+unpin_run :: proc(args: []string, config: ^Config) -> int {
+	if len(args) == 0 {
+		fmt.println("Usage: vuru unpin <package> [packages...]")
+		return 1
+	}
+
+	pinned, ok := pins.pins_load(context.temp_allocator)
+	if !ok {
+		return 1
+	}
+
+	updated := make([dynamic]string, context.temp_allocator)
+	for name in pinned {
+		if !slice.contains(args, name) {
+			append(&updated, name)
+		}
+	}
+
+	for name in args {
+		if !slice.contains(pinned, name) {
+			errors.log_warning("%s is not pinned", name)
+		} else {
+			errors.log_info("Unpinned %s", name)
+		}
+	}
+
+	if len(updated) == len(pinned) {
+		return 0
+	}
+	if !pins.pins_save(updated[:]) {
+		errors.log_error("Failed to save pin list")
+		return 1
+	}
+	return 0
+}
@@ -3,6 +3,7 @@ package commands
 import "core:fmt"
 import "core:strings"
 
+import cfg "../core/config"
 import errors "../core/errors"
 import index "../core/index"
 import resolve "../core/resolve"
@@ -95,7 +96,7 @@ query_info :: proc(args: []string, config: ^Config) -> int {
 	idx, ok := index.index_load_or_fetch(config.index_url, false)
 	if !ok {
 		errors.log_error("Failed to load package index")
-		return 1
+		return errors.EXIT_INDEX_FAILED
 	}
 
 	for pkg_name in args {
@@ -104,7 +105,8 @@ query_info :: proc(args: []string, config: ^Config) -> int {
 			// Always fetch template for complete info
 			tmpl, tmpl_ok := resolve.fetch_and_parse_template(
 				pkg.category,
-				pkg_name,
+				index.package_template_name(pkg, pkg_name),
+				template_source(config),
 				context.temp_allocator,
 			)
 			if tmpl_ok {
@@ -119,7 +121,13 @@ query_info :: proc(args: []string, config: ^Config) -> int {
 			// Output in xbps-query style
 			fmt.printf("pkgname: %s\n", pkg_name)
 			fmt.printf("pkgver: %s-%s\n", pkg_name, pkg.version)
+			if config.repo_version {
+				query_print_repo_version(pkg_name, pkg)
+			}
 			fmt.printf("category: %s\n", pkg.category)
+			if len(pkg.source_pkg) > 0 {
+				fmt.printf("sourcepkg: %s\n", pkg.source_pkg)
+			}
 			fmt.printf("source: VUP\n")
 
 			// Show architectures
@@ -174,17 +182,37 @@ query_info :: proc(args: []string, config: ^Config) -> int {
 			}
 			if utils.run_command(cmd[:]) != 0 {
 				errors.log_error("Package '%s' not found", pkg_name)
-				return 1
+				return errors.EXIT_NOT_FOUND
 			}
 		} else {
 			errors.log_error("VUP package '%s' not found", pkg_name)
-			return 1
+			return errors.EXIT_NOT_FOUND
 		}
 	}
 
 	return 0
 }
 
+// --repo-version: the version the package's repo serves, next to the index one
+query_print_repo_version :: proc(name: string, pkg: index.Package_Info) {
+	arch, arch_ok := cfg.get_arch()
+	if !arch_ok {
+		errors.print_error(errors.make_error(.Arch_Detection_Failed))
+		return
+	}
+
+	repo_ver, status := repo_version_status(name, pkg, arch)
+	switch status {
+	case "current":
+		fmt.printf("repo-pkgver: %s-%s\n", name, repo_ver)
+	case "missing":
+		errors.log_warning("%s is not in its repo for %s (index says %s)", name, arch, pkg.version)
+	case:
+		fmt.printf("repo-pkgver: %s-%s\n", name, repo_ver)
+		errors.log_warning("The index says %s %s, the repo has %s (%s)", name, pkg.version, repo_ver, status)
+	}
+}
+
 // Print help for query command
 query_help :: proc() {
 	fmt.println("Usage: vuru query [options] <package>")
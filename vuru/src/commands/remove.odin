@@ -1,8 +1,12 @@
 package commands
 
+import "core:encoding/json"
 import "core:fmt"
+import "core:slice"
 import "core:strings"
+import "core:time"
 
+import audit "../core/audit"
 import errors "../core/errors"
 import xbps "../core/xbps"
 import utils "../utils"
@@ -22,12 +26,38 @@ remove_run :: proc(args: []string, config: ^Config) -> int {
 	// Standard remove
 	if len(args) == 0 {
 		fmt.println("Usage: vuru remove <package> [packages...]")
+		fmt.println("       vuru remove --clean-deps <package>    (also remove unneeded deps)")
 		fmt.println("       vuru remove -o    (remove orphans)")
 		fmt.println("       vuru remove -O    (clean cache)")
 		return 1
 	}
 
-	cmd: [dynamic; 64]string
+	args := args
+	names, names_ok := normalize_package_names(args)
+	if !names_ok {
+		return 1
+	}
+	args = names
+
+	// --simulate: write what would be removed as JSON, remove nothing
+	if config.simulate {
+		return remove_write_plan(args, config)
+	}
+
+	// Removal always defaults to "no"; once confirmed here xbps doesn't ask again
+	confirmed := config.yes
+	if !config.yes && !config.dry_run {
+		count := print_removal_plan(args, config)
+		question := fmt.tprintf("Remove %d package(s)?", count)
+		if !utils.prompt_yes_no(question, false) {
+			errors.log_info("Removal cancelled")
+			return errors.EXIT_ABORTED
+		}
+		confirmed = true
+	}
+
+	// Sized by the package list: a fixed buffer would silently drop names
+	cmd := make([dynamic]string, 0, len(args) + 8, context.temp_allocator)
 
 	if !config.dry_run {
 		append(&cmd, "sudo")
@@ -37,7 +67,7 @@ remove_run :: proc(args: []string, config: ^Config) -> int {
 	if config.dry_run {
 		append(&cmd, "-n")
 	}
-	if config.yes {
+	if confirmed {
 		append(&cmd, "-y")
 	}
 	if config.recursive {
@@ -56,13 +86,144 @@ remove_run :: proc(args: []string, config: ^Config) -> int {
 
 	errors.log_info("Removing %s...", strings.join(args[:], ", ", context.temp_allocator))
 
-	if utils.run_command(cmd[:]) == 0 {
+	// Versions for the audit log, looked up while the packages still exist
+	versions := make([]string, len(args), context.temp_allocator)
+	if !config.dry_run {
+		for pkg, i in args {
+			versions[i], _ = get_installed_version(pkg, context.temp_allocator)
+		}
+	}
+
+	removed := utils.run_command(cmd[:]) == 0
+	if !config.dry_run {
+		for pkg, i in args {
+			audit.audit_record("remove", pkg, versions[i], removed)
+		}
+	}
+
+	if removed {
 		errors.log_info("Successfully removed package(s)")
+		if config.clean_orphans && !config.dry_run {
+			return remove_clean_orphans(config)
+		}
 		return 0
 	}
 
+	if utils.command_interrupted() {
+		errors.log_error("Removal interrupted")
+		return errors.EXIT_ABORTED
+	}
 	errors.log_error("xbps-remove failed")
-	return 1
+	return errors.EXIT_XBPS_FAILED
+}
+
+// One package of a removal plan
+Removal_Plan_Entry :: struct {
+	name:       string `json:"name"`,
+	version:    string `json:"version"`,
+	dependency: bool `json:"dependency"`, // Not named, taken along by --clean-deps
+}
+
+// `remove --simulate`, for reviewing cascading removals
+Removal_Plan :: struct {
+	created:   string `json:"created"`,
+	requested: []string `json:"requested"`, // Package names as given
+	packages:  []Removal_Plan_Entry `json:"packages"`,
+}
+
+// Write what `vuru remove` would remove with the same flags (xbps-remove -n,
+// with -R for --clean-deps) as JSON, to --out or stdout
+remove_write_plan :: proc(args: []string, config: ^Config) -> int {
+	pkgvers, ok := xbps.remove_dry_run(
+		args,
+		config.recursive,
+		config.rootdir,
+		utils.run_command_output,
+		context.temp_allocator,
+	)
+	if !ok {
+		errors.log_error("xbps-remove -n failed (are all packages installed?)")
+		return errors.EXIT_XBPS_FAILED
+	}
+
+	entries := make([]Removal_Plan_Entry, len(pkgvers), context.temp_allocator)
+	for pkgver, i in pkgvers {
+		name, version, _ := xbps.parse_pkgver(pkgver)
+		entries[i] = Removal_Plan_Entry {
+			name       = name,
+			version    = version,
+			dependency = !slice.contains(args, name),
+		}
+	}
+
+	plan := Removal_Plan {
+		created   = utils.format_timestamp(time.now(), context.temp_allocator),
+		requested = args,
+		packages  = entries,
+	}
+
+	data, err := json.marshal(plan, {pretty = true}, context.temp_allocator)
+	if err != nil {
+		errors.log_error("Failed to encode removal plan")
+		return 1
+	}
+
+	if len(config.output_path) == 0 {
+		fmt.println(string(data))
+		return 0
+	}
+
+	if !utils.write_file(config.output_path, string(data)) {
+		errors.log_error("Failed to write %s", config.output_path)
+		return 1
+	}
+
+	errors.log_info("Wrote removal plan with %d package(s) to %s", len(entries), config.output_path)
+	return 0
+}
+
+// After a removal (--clean-orphans): show the dependencies nothing needs
+// anymore and remove them, asking first unless -y
+remove_clean_orphans :: proc(config: ^Config) -> int {
+	orphans, ok := xbps.list_orphans(config.rootdir, utils.run_command_output, context.temp_allocator)
+	if !ok {
+		errors.log_error("Failed to list orphaned packages")
+		return errors.EXIT_XBPS_FAILED
+	}
+	if len(orphans) == 0 {
+		errors.log_info("No orphaned packages left")
+		return 0
+	}
+
+	fmt.println()
+	fmt.printf("%d orphaned package(s):\n", len(orphans))
+	for pkgver in orphans {
+		fmt.printf("  %s\n", pkgver)
+	}
+
+	if !config.yes && !utils.prompt_yes_no("Remove orphaned packages?", false) {
+		errors.log_info("Orphans kept")
+		return 0
+	}
+
+	cmd := make([dynamic]string, context.temp_allocator)
+	append(&cmd, "sudo", "xbps-remove", "-o", "-y")
+	if len(config.rootdir) > 0 {
+		append(&cmd, "-r", config.rootdir)
+	}
+
+	removed := utils.run_command(cmd[:]) == 0
+	for pkgver in orphans {
+		name, version, _ := xbps.parse_pkgver(pkgver)
+		audit.audit_record("remove", name, version, removed)
+	}
+
+	if !removed {
+		errors.log_error("Failed to remove orphaned packages")
+		return errors.EXIT_XBPS_FAILED
+	}
+	errors.log_info("Removed %d orphaned package(s)", len(orphans))
+	return 0
 }
 
 // Remove orphan packages (xbps-remove -o)
@@ -116,5 +277,68 @@ remove_cache :: proc(config: ^Config) -> int {
 	}
 
 	errors.log_info("Cleaning package cache...")
-	return xbps.clean_cache(utils.run_command)
+	return utils.run_command(cmd[:])
+}
+
+// Show what xbps-remove would take out, marking packages that were not named.
+// Returns the number of packages to be removed.
+@(private)
+print_removal_plan :: proc(args: []string, config: ^Config) -> int {
+	pkgvers, ok := xbps.remove_dry_run(
+		args,
+		config.recursive,
+		config.rootdir,
+		utils.run_command_output,
+		context.temp_allocator,
+	)
+	if !ok || len(pkgvers) == 0 {
+		// Let xbps-remove report unknown packages itself
+		return len(args)
+	}
+
+	fmt.println("Packages to remove:")
+	extra := 0
+	for pkgver in pkgvers {
+		// pkgver is "<name>-<version>_<revision>"
+		pkg_name := pkgver
+		if dash := strings.last_index_byte(pkgver, '-'); dash > 0 {
+			pkg_name = pkgver[:dash]
+		}
+
+		named := false
+		for name in args {
+			if name == pkg_name {
+				named = true
+				break
+			}
+		}
+
+		if named {
+			fmt.printf("  %s\n", pkgver)
+		} else {
+			fmt.printf("  %s (dependency)\n", pkgver)
+			extra += 1
+		}
+	}
+
+	if extra > 0 {
+		errors.log_info("%d unneeded dependency package(s) will also be removed (--clean-deps)", extra)
+	}
+
+	// Point out packages that still need what is being removed
+	for name in args {
+		revdeps, revdeps_ok := xbps.get_reverse_deps(
+			name,
+			utils.run_command_output,
+			context.temp_allocator,
+		)
+		if revdeps_ok && len(revdeps) > 0 {
+			errors.log_warning(
+				"%s is required by: %s",
+				name,
+				strings.join(revdeps, ", ", context.temp_allocator),
+			)
+		}
+	}
+	return len(pkgvers)
 }
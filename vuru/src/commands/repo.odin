@@ -0,0 +1,252 @@
+package commands
+
+import "core:fmt"
+
+import errors "../core/errors"
+import index "../core/index"
+import utils "../utils"
+
+// Repo command - manage overlay repos merged into the VUP index
+repo_run :: proc(args: []string, config: ^Config) -> int {
+	if len(args) == 0 {
+		repo_usage()
+		return 1
+	}
+
+	switch args[0] {
+	case "list", "ls":
+		return repo_list(config)
+	case "add":
+		if len(args) != 3 {
+			repo_usage()
+			return 1
+		}
+		return repo_add(args[1], args[2], config)
+	case "conflicts":
+		return repo_conflicts(config)
+	case "priority":
+		if len(args) != 3 {
+			repo_usage()
+			return 1
+		}
+		return repo_set_priority(args[1], args[2])
+	case "remove", "rm":
+		if len(args) != 2 {
+			repo_usage()
+			return 1
+		}
+		return repo_remove(args[1])
+	}
+
+	repo_usage()
+	return 1
+}
+
+// Print registered overlay repos
+repo_list :: proc(config: ^Config) -> int {
+	repos, ok := index.repos_load(context.temp_allocator)
+	if !ok {
+		return 1
+	}
+
+	if config.porcelain {
+		fmt.printf("%s\t%s\t%d\n", index.MAIN_REPO_NAME, config.index_url, index.MAIN_REPO_PRIORITY)
+		for r in repos {
+			fmt.printf("%s\t%s\t%d\n", r.name, r.url, r.priority)
+		}
+		return 0
+	}
+
+	fmt.printf("%-20s %4d  %s (main)\n", index.MAIN_REPO_NAME, index.MAIN_REPO_PRIORITY, config.index_url)
+	for r in repos {
+		fmt.printf("%-20s %4d  %s\n", r.name, r.priority, r.url)
+	}
+	return 0
+}
+
+// List package names defined by more than one repo and which one is used
+repo_conflicts :: proc(config: ^Config) -> int {
+	conflicts, ok := index.index_repo_conflicts(config.index_url, context.temp_allocator)
+	if !ok {
+		errors.log_error("Failed to load package index")
+		return errors.EXIT_INDEX_FAILED
+	}
+
+	if config.porcelain {
+		for c in conflicts {
+			fmt.printf(
+				"%s\t%s\t%s\t%s\t%s\n",
+				c.name,
+				c.winner,
+				c.winner_version,
+				c.loser,
+				c.loser_version,
+			)
+		}
+		return 0
+	}
+
+	if len(conflicts) == 0 {
+		fmt.println("No package is defined by more than one repo")
+		return 0
+	}
+
+	for c in conflicts {
+		fmt.printf(
+			"%-30s %s %s (used), %s %s (ignored)\n",
+			c.name,
+			c.winner,
+			c.winner_version,
+			c.loser,
+			c.loser_version,
+		)
+	}
+	fmt.println()
+	fmt.println("The repo with the highest priority wins; on a tie the main index, then overlays")
+	fmt.println("in the order they were added. Change it with 'vuru repo priority <name> <n>'.")
+	return 0
+}
+
+// Register an overlay repo, once its URL has been checked to serve an index.
+// With --test only the check is done.
+repo_add :: proc(name: string, url: string, config: ^Config) -> int {
+	if !index.is_valid_repo_name(name) {
+		errors.log_error("Invalid repo name: %s", name)
+		return 1
+	}
+	if !index.is_valid_url(url) {
+		errors.log_error("Invalid or unsafe URL: %s", url)
+		return 1
+	}
+
+	repos, ok := index.repos_load(context.temp_allocator)
+	if !ok {
+		return 1
+	}
+
+	for r in repos {
+		if r.name == name {
+			errors.log_error("Repo %s already exists (%s)", name, r.url)
+			return 1
+		}
+	}
+
+	errors.log_info("Checking %s...", url)
+	packages, probe_ok := index.index_probe(url)
+	if !probe_ok {
+		errors.log_error("Not adding repo %s", name)
+		return errors.EXIT_INDEX_FAILED
+	}
+	if packages == 0 {
+		errors.log_warning("The index of %s lists no packages", name)
+	}
+	if config.repo_test {
+		errors.log_success("%s serves a valid index with %d package(s)", url, packages)
+		return 0
+	}
+
+	updated := make([dynamic]index.Overlay_Repo, context.temp_allocator)
+	append(&updated, ..repos)
+	append(&updated, index.Overlay_Repo{name, url, config.repo_priority})
+
+	if !index.repos_save(updated[:]) {
+		errors.log_error("Failed to save repo list")
+		return 1
+	}
+
+	errors.log_info("Added repo %s (%d package(s)), run 'vuru sync' to fetch its index", name, packages)
+	return 0
+}
+
+// Change the priority of an overlay repo
+repo_set_priority :: proc(name: string, priority_arg: string) -> int {
+	if !index.is_valid_repo_priority(priority_arg) {
+		errors.log_error("Invalid priority: %s (expected a non-negative number)", priority_arg)
+		return 1
+	}
+
+	repos, ok := index.repos_load(context.temp_allocator)
+	if !ok {
+		return 1
+	}
+
+	found := false
+	for &r in repos {
+		if r.name == name {
+			r.priority = utils.parse_int(priority_arg)
+			found = true
+		}
+	}
+	if !found {
+		errors.log_error("No such repo: %s", name)
+		return 1
+	}
+
+	if !index.repos_save(repos) {
+		errors.log_error("Failed to save repo list")
+		return 1
+	}
+
+	errors.log_info("Repo %s now has priority %s", name, priority_arg)
+	return 0
+}
+
+// Unregister an overlay repo and drop its cached index
+repo_remove :: proc(name: string) -> int {
+	repos, ok := index.repos_load(context.temp_allocator)
+	if !ok {
+		return 1
+	}
+
+	updated := make([dynamic]index.Overlay_Repo, context.temp_allocator)
+	found := false
+	for r in repos {
+		if r.name == name {
+			found = true
+			continue
+		}
+		append(&updated, r)
+	}
+
+	if !found {
+		errors.log_error("No such repo: %s", name)
+		return 1
+	}
+
+	if !index.repos_save(updated[:]) {
+		errors.log_error("Failed to save repo list")
+		return 1
+	}
+
+	index.repo_cache_remove(name)
+	errors.log_info("Removed repo %s", name)
+	return 0
+}
+
+// Whether name is the main index or a registered overlay repo
+repo_exists :: proc(name: string) -> bool {
+	if name == index.MAIN_REPO_NAME {
+		return true
+	}
+
+	repos, _ := index.repos_load(context.temp_allocator)
+	for r in repos {
+		if r.name == name {
+			return true
+		}
+	}
+	return false
+}
+
+repo_usage :: proc() {
+	fmt.println("Usage: vuru repo <command>")
+	fmt.println()
+	fmt.println("Commands:")
+	fmt.println("  list                 List the main index and overlay repos")
+	fmt.println("  add <name> <url>     Add an overlay repo (URL of its index.json);")
+	fmt.println("                       --repo-priority <n> lets it override lower repos,")
+	fmt.println("                       --test only checks that the URL serves an index")
+	fmt.println("  priority <name> <n>  Set the priority of an overlay repo (main index: 0)")
+	fmt.println("  remove <name>        Remove an overlay repo")
+	fmt.println("  conflicts            List packages defined by more than one repo")
+}
@@ -0,0 +1,52 @@
+package commands
+
+import "core:fmt"
+
+import errors "../core/errors"
+import xbps "../core/xbps"
+import utils "../utils"
+
+// Revdeps command - show installed packages that depend on each argument
+revdeps_run :: proc(args: []string, config: ^Config) -> int {
+	if len(args) == 0 {
+		fmt.println("Usage: vuru revdeps <package> [packages...]")
+		return 1
+	}
+
+	for pkg_name, i in args {
+		if !utils.is_valid_package_name(pkg_name) {
+			errors.log_error("Invalid package name: %s", pkg_name)
+			return 1
+		}
+
+		revdeps, ok := xbps.get_reverse_deps(
+			pkg_name,
+			utils.run_command_output,
+			context.temp_allocator,
+		)
+		if !ok {
+			errors.log_error("Failed to query reverse dependencies of %s", pkg_name)
+			return 1
+		}
+
+		if config.porcelain {
+			for r in revdeps {
+				fmt.printf("%s\t%s\n", pkg_name, r)
+			}
+			continue
+		}
+
+		if i > 0 {fmt.println()}
+		if len(revdeps) == 0 {
+			fmt.printf("%s: no installed package depends on it\n", pkg_name)
+			continue
+		}
+
+		fmt.printf("%s is required by %d package(s):\n", pkg_name, len(revdeps))
+		for r in revdeps {
+			fmt.printf("  %s\n", r)
+		}
+	}
+
+	return 0
+}
@@ -1,11 +1,13 @@
 package commands
 
 import "core:fmt"
-import "core:os"
+import "core:slice"
 import "core:strings"
 
+import cfg "../core/config"
 import errors "../core/errors"
 import index "../core/index"
+import xbps "../core/xbps"
 import utils "../utils"
 
 // Threshold for using pager
@@ -13,18 +15,124 @@ PAGER_THRESHOLD :: 5
 
 // Search result entry
 Search_Result :: struct {
-	name:      string,
-	version:   string,
-	desc:      string,
-	source:    string, // "vup", "official", "installed"
-	installed: bool,
-	category:  string, // For VUP packages
+	name:          string,
+	version:       string,
+	desc:          string,
+	source:        string, // "vup", "official", "installed"
+	installed:     bool,
+	installed_ver: string, // Installed version, VUP results only
+	category:      string, // For VUP packages
+	repo:          string, // VUP repo the package comes from (index.MAIN_REPO_NAME = main index)
+	rank:          int, // Relevance tier, lower is better (see search_rank)
+}
+
+// Search filters, taken from the global flags
+Search_Options :: struct {
+	vup_only:           bool, // --vup-only
+	description_search: bool, // -d, --desc
+	installed_only:     bool, // --installed
+	match_any:          bool, // --any, OR the query terms instead of AND
+	repo_only:          string, // --repo-only
+	sort_by:            string, // --sort: relevance (default), version or name
+	new_since:          string, // --new-since: only VUP packages changed since YYYY-MM-DD
+	license:            string, // --license: only VUP packages whose license contains this
+	show_repo:          bool, // -v: show which repo each VUP package comes from
+	group_by_category:  bool, // --group-by-category: VUP results under category headers
+	max_per_category:   int, // --max-per-category: rows shown per category group (0 = all)
+	available_updates:  bool, // --available-updates: installed VUP matches with installed and index version
+	repo_version:       bool, // --repo-version: VUP matches with index and actual repo version
+}
+
+// Accepted --sort values
+SEARCH_SORT_KEYS :: []string{"relevance", "version", "name"}
+
+search_options_from_config :: proc(config: ^Config) -> Search_Options {
+	return Search_Options {
+		vup_only = config.vup_only,
+		description_search = config.description_search,
+		installed_only = config.installed_only,
+		match_any = config.search_any,
+		repo_only = config.repo_only,
+		sort_by = config.search_sort,
+		new_since = config.new_since,
+		license = config.license,
+		show_repo = config.verbose,
+		group_by_category = config.search_group || config.max_per_category > 0,
+		max_per_category = config.max_per_category,
+		available_updates = config.available_updates,
+		repo_version = config.repo_version,
+	}
+}
+
+// Relevance tier of a match: exact name, name prefix, name substring, description only
+search_rank :: proc(name_lower: string, query_lower: string) -> int {
+	switch {
+	case name_lower == query_lower:
+		return 0
+	case strings.has_prefix(name_lower, query_lower):
+		return 1
+	case strings.contains(name_lower, query_lower):
+		return 2
+	}
+	return 3
+}
+
+// Best relevance tier of a name over all query terms
+search_rank_terms :: proc(name: string, terms: []string) -> int {
+	name_lower := strings.to_lower(name, context.temp_allocator)
+	best := 3
+	for term in terms {
+		best = min(best, search_rank(name_lower, term))
+	}
+	return best
+}
+
+// Sort results by relevance tier, alphabetically within a tier. With
+// sort_by "version" the highest version comes first (xbps version order, so
+// 1.10 sorts above 1.9), with "name" results are alphabetical.
+sort_search_results :: proc(results: []Search_Result, sort_by := "") {
+	switch sort_by {
+	case "version":
+		slice.sort_by(results, proc(a, b: Search_Result) -> bool {
+			if c := xbps.version_compare(a.version, b.version); c != 0 {
+				return c > 0
+			}
+			return a.name < b.name
+		})
+		return
+	case "name":
+		slice.sort_by(results, proc(a, b: Search_Result) -> bool {
+			return a.name < b.name
+		})
+		return
+	}
+
+	slice.sort_by(results, proc(a, b: Search_Result) -> bool {
+		if a.rank != b.rank {
+			return a.rank < b.rank
+		}
+		return a.name < b.name
+	})
 }
 
 // Search command implementation
 search_run :: proc(args: []string, config: ^Config) -> int {
 	if len(args) == 0 {
-		errors.log_error("Usage: vuru search <query>")
+		errors.log_error("Usage: vuru search <query> [queries...]")
+		return 1
+	}
+
+	if len(config.search_sort) > 0 && !slice.contains(SEARCH_SORT_KEYS, config.search_sort) {
+		errors.log_error(
+			"Unknown sort key: %s (expected %s)",
+			config.search_sort,
+			strings.join(SEARCH_SORT_KEYS, ", ", context.temp_allocator),
+		)
+		return 1
+	}
+
+	if len(config.repo_only) > 0 && !repo_exists(config.repo_only) {
+		errors.log_error("Unknown repo: %s (see 'vuru repo list')", config.repo_only)
 		return 1
 	}
 
@@ -32,98 +140,162 @@ search_run :: proc(args: []string, config: ^Config) -> int {
 	idx, ok := index.index_load_or_fetch(config.index_url, false)
 	if !ok {
 		errors.log_error("Failed to load package index")
-		return 1
+		return errors.EXIT_INDEX_FAILED
+	}
+
+	opts := search_options_from_config(config)
+
+	if config.porcelain {
+		for query in args {
+			if opts.available_updates {
+				search_updates_porcelain(&idx, query, opts)
+			} else if opts.repo_version {
+				search_repo_versions_porcelain(&idx, query, opts)
+			} else {
+				search_porcelain(&idx, query, opts)
+			}
+		}
+		return 0
 	}
 
+	// Collect every query into one buffer so multiple queries share a single pager
+	builder := strings.builder_make(context.temp_allocator)
+	total := 0
+
 	for query, i in args {
-		if i > 0 {fmt.println()}
-		unified_search(&idx, query, config.vup_only, config.description_search)
+		if len(args) > 1 {
+			if i > 0 {strings.write_string(&builder, "\n")}
+			fmt.sbprintf(
+				&builder,
+				"%s:: Results for '%s'%s\n",
+				errors.COLOR_BOLD,
+				query,
+				errors.COLOR_RESET,
+			)
+		}
+		if opts.available_updates {
+			total += search_updates(&builder, &idx, query, opts)
+		} else if opts.repo_version {
+			total += search_repo_versions(&builder, &idx, query, opts)
+		} else {
+			total += unified_search(&builder, &idx, query, opts)
+		}
 	}
 
+	show_search_output(strings.to_string(builder), total)
 	return 0
 }
 
-// Search VUP index for packages matching a query
-search_vup :: proc(
-	idx: ^index.Index,
-	query: string,
-	description_search: bool,
-) -> [dynamic]Search_Result {
+// Search VUP index for packages matching a query.
+// Space-separated terms must all match, or any of them with --any.
+search_vup :: proc(idx: ^index.Index, query: string, opts: Search_Options) -> [dynamic]Search_Result {
 	results := make([dynamic]Search_Result, context.temp_allocator)
-	query_lower := strings.to_lower(query, context.temp_allocator)
+	terms := index.search_terms(query, context.temp_allocator)
+	installed_versions := installed_package_versions()
 
 	for name, pkg in idx.packages {
-		name_lower := strings.to_lower(name, context.temp_allocator)
-		desc_lower := strings.to_lower(pkg.short_desc, context.temp_allocator)
-
-		match_name := strings.contains(name_lower, query_lower)
-		match_desc := description_search && strings.contains(desc_lower, query_lower)
-
-		if match_name || match_desc {
-			installed := utils.run_command_silent({"xbps-query", name}) == 0
+		if len(opts.repo_only) > 0 && index.package_repo_name(pkg) != opts.repo_only {
+			continue
+		}
+		if len(opts.new_since) > 0 && !index.package_updated_since(pkg, opts.new_since) {
+			continue
+		}
+		if len(opts.license) > 0 && !index.license_matches(pkg.license, opts.license) {
+			continue
+		}
 
-			append(
-				&results,
-				Search_Result {
-					name = name,
-					version = pkg.version,
-					desc = pkg.short_desc,
-					source = "vup",
-					installed = installed,
-					category = pkg.category,
-				},
-			)
+		if !index.search_matches(name, pkg.short_desc, terms, opts.match_any, opts.description_search) {
+			continue
 		}
+
+		append(
+			&results,
+			Search_Result {
+				name = name,
+				version = pkg.version,
+				desc = pkg.short_desc,
+				source = "vup",
+				installed = name in installed_versions,
+				installed_ver = installed_versions[name],
+				category = pkg.category,
+				repo = index.package_repo_name(pkg),
+				rank = search_rank_terms(name, terms),
+			},
+		)
 	}
 
+	sort_search_results(results[:], opts.sort_by)
 	return results
 }
 
 // Search official Void repos
-search_official :: proc(query: string, description_search: bool) -> [dynamic]Search_Result {
+search_official :: proc(query: string, opts: Search_Options) -> [dynamic]Search_Result {
 	results := make([dynamic]Search_Result, context.temp_allocator)
-
-	output, ok := utils.run_command_output({"xbps-query", "-Rs", query}, context.temp_allocator)
-	if !ok {
+	terms := index.search_terms(query, context.temp_allocator)
+	if len(terms) == 0 {
 		return results
 	}
 
-	// Parse output: [*] pkgname-version  description
-	for line in strings.split_lines_iterator(&output) {
-		if len(line) < 5 {
-			continue
+	// xbps-query takes one pattern: with AND, query the longest term (fewest
+	// hits) and filter the rest locally; with OR, query each term
+	patterns := terms
+	if !opts.match_any {
+		longest := 0
+		for term, i in terms {
+			if len(term) > len(terms[longest]) {
+				longest = i
+			}
 		}
+		patterns = terms[longest:longest + 1]
+	}
 
-		installed := line[0] == '*' || (len(line) > 1 && line[1] == '*')
+	seen := make(map[string]bool, context.temp_allocator)
 
-		// Skip the [*] or [-] prefix
-		rest := strings.trim_left(line[3:], " ")
-		if len(rest) == 0 {
+	for pattern in patterns {
+		output, ok := utils.run_command_output({"xbps-query", "-Rs", pattern}, context.temp_allocator)
+		if !ok {
 			continue
 		}
 
-		// Split into pkgver and description
-		parts := strings.split_n(rest, " ", 2, context.temp_allocator)
-		if len(parts) < 1 {
-			continue
-		}
+		// Parse output: [*] pkgname-version  description
+		for line in strings.split_lines_iterator(&output) {
+			if len(line) < 5 {
+				continue
+			}
+
+			installed := line[0] == '*' || (len(line) > 1 && line[1] == '*')
+
+			// Skip the [*] or [-] prefix
+			rest := strings.trim_left(line[3:], " ")
+			if len(rest) == 0 {
+				continue
+			}
+
+			// Split into pkgver and description
+			parts := strings.split_n(rest, " ", 2, context.temp_allocator)
+			if len(parts) < 1 {
+				continue
+			}
 
-		pkgver := parts[0]
-		desc := strings.trim_space(parts[1]) if len(parts) > 1 else ""
+			pkgver := parts[0]
+			desc := strings.trim_space(parts[1]) if len(parts) > 1 else ""
 
-		// Parse pkgname-version
-		if idx := strings.last_index(pkgver, "-"); idx > 0 {
+			// Parse pkgname-version
+			idx := strings.last_index(pkgver, "-")
+			if idx <= 0 {
+				continue
+			}
 			name := pkgver[:idx]
+			if name in seen {
+				continue
+			}
 
-			// Filter by name if not searching descriptions
-			// xbps-query -Rs searches both, so we manually filter if needed
-			if !description_search {
-				name_lower := strings.to_lower(name, context.temp_allocator)
-				query_lower := strings.to_lower(query, context.temp_allocator)
-				if !strings.contains(name_lower, query_lower) {
-					continue
-				}
+			// xbps-query -Rs searches both name and description, so apply
+			// the name-only and multi-term rules here
+			if !index.search_matches(name, desc, terms, opts.match_any, opts.description_search) {
+				continue
 			}
+			seen[name] = true
 
 			append(
 				&results,
@@ -133,18 +305,98 @@ search_official :: proc(query: string, description_search: bool) -> [dynamic]Sea
 					desc = desc,
 					source = "official",
 					installed = installed,
+					rank = search_rank_terms(name, terms),
 				},
 			)
 		}
 	}
 
+	sort_search_results(results[:], opts.sort_by)
 	return results
 }
 
+// Installed packages and their versions, queried once per run
+@(private)
+installed_package_versions :: proc() -> map[string]string {
+	@(static) versions: map[string]string
+	@(static) loaded: bool
+
+	if !loaded {
+		versions = make(map[string]string)
+		if pkgs, ok := xbps.list_installed(utils.run_command_output); ok {
+			for p in pkgs {
+				versions[p[0]] = p[1]
+			}
+		}
+		loaded = true
+	}
+	return versions
+}
+
+// Drop results that are not installed (--installed)
+filter_installed :: proc(results: ^[dynamic]Search_Result) {
+	kept := 0
+	for r in results {
+		if r.installed {
+			results[kept] = r
+			kept += 1
+		}
+	}
+	resize(results, kept)
+}
+
+// Print results in the stable porcelain format, one match per line:
+// query, source, name, version, category, installed (yes/no), description
+search_porcelain :: proc(idx: ^index.Index, query: string, opts: Search_Options) {
+	vup_results := search_vup(idx, query, opts)
+
+	// Official packages belong to no VUP repo and carry no dates or licenses
+	official_results: [dynamic]Search_Result
+	if !opts.vup_only && len(opts.repo_only) == 0 && len(opts.new_since) == 0 && len(opts.license) == 0 {
+		official_results = search_official(query, opts)
+	}
+
+	if opts.installed_only {
+		filter_installed(&vup_results)
+		filter_installed(&official_results)
+	}
+
+	print_porcelain_rows(query, vup_results[:])
+	print_porcelain_rows(query, official_results[:])
+}
+
+@(private)
+print_porcelain_rows :: proc(query: string, results: []Search_Result) {
+	for r in results {
+		fmt.printf(
+			"%s\t%s\t%s\t%s\t%s\t%s\t%s\n",
+			query,
+			r.source,
+			r.name,
+			r.version,
+			r.category,
+			"yes" if r.installed else "no",
+			porcelain_field(r.desc),
+		)
+	}
+}
+
+// Keep a free-text field on one porcelain line
+porcelain_field :: proc(s: string) -> string {
+	if !strings.contains_any(s, "\t\n\r") {
+		return s
+	}
+	out, _ := strings.replace_all(s, "\t", " ", context.temp_allocator)
+	out, _ = strings.replace_all(out, "\n", " ", context.temp_allocator)
+	out, _ = strings.replace_all(out, "\r", " ", context.temp_allocator)
+	return out
+}
+
 // Format search results into a string
 format_search_results :: proc(
 	vup_results: []Search_Result,
 	official_results: []Search_Result,
+	opts := Search_Options{},
 	allocator := context.allocator,
 ) -> string {
 	builder := strings.builder_make(allocator)
@@ -160,28 +412,35 @@ format_search_results :: proc(
 			len(vup_results),
 			errors.COLOR_RESET,
 		)
-		fmt.sbprintf(
-			&builder,
-			"%-30s %-15s %-12s %s\n",
-			"NAME",
-			"VERSION",
-			"CATEGORY",
-			"DESCRIPTION",
-		)
-		strings.write_string(&builder, strings.repeat("-", 80, context.temp_allocator))
-		strings.write_string(&builder, "\n")
-
-		for r in vup_results {
-			status := "[installed]" if r.installed else ""
+		if opts.show_repo {
 			fmt.sbprintf(
 				&builder,
-				"%-30s %-15s %-12s %s %s\n",
-				r.name,
-				r.version if len(r.version) > 0 else "?",
-				r.category if len(r.category) > 0 else "?",
-				truncate(r.desc, 30),
-				status,
+				"%-30s %-15s %-12s %-12s %s\n",
+				"NAME",
+				"VERSION",
+				"CATEGORY",
+				"REPO",
+				"DESCRIPTION",
 			)
+		} else {
+			fmt.sbprintf(
+				&builder,
+				"%-30s %-15s %-12s %s\n",
+				"NAME",
+				"VERSION",
+				"CATEGORY",
+				"DESCRIPTION",
+			)
+		}
+		strings.write_string(&builder, strings.repeat("-", 80, context.temp_allocator))
+		strings.write_string(&builder, "\n")
+
+		if opts.group_by_category {
+			write_vup_groups(&builder, vup_results, opts)
+		} else {
+			for r in vup_results {
+				write_vup_row(&builder, r, opts.show_repo)
+			}
 		}
 		strings.write_string(&builder, "\n")
 	}
@@ -219,36 +478,268 @@ format_search_results :: proc(
 	return strings.to_string(builder)
 }
 
-// Unified search across VUP and official repos
+@(private)
+write_vup_row :: proc(builder: ^strings.Builder, r: Search_Result, show_repo: bool) {
+	fmt.sbprintf(
+		builder,
+		"%-30s %-15s %-12s ",
+		r.name,
+		r.version if len(r.version) > 0 else "?",
+		search_category(r),
+	)
+	if show_repo {
+		fmt.sbprintf(builder, "%-12s ", r.repo)
+	}
+	fmt.sbprintf(builder, "%s %s\n", truncate(r.desc, 30), "[installed]" if r.installed else "")
+}
+
+@(private)
+search_category :: proc(r: Search_Result) -> string {
+	return r.category if len(r.category) > 0 else "?"
+}
+
+// VUP results under a header per category, largest category first, keeping
+// the result order within each. Past max_per_category a group ends with a
+// "(N more in <category>)" line.
+@(private)
+write_vup_groups :: proc(builder: ^strings.Builder, results: []Search_Result, opts: Search_Options) {
+	categories := make([]string, len(results), context.temp_allocator)
+	for r, i in results {
+		categories[i] = search_category(r)
+	}
+
+	for group in count_by_category(categories) {
+		fmt.sbprintf(builder, "%s%s (%d)%s\n", errors.COLOR_BOLD, group.category, group.count, errors.COLOR_RESET)
+
+		shown := 0
+		for r in results {
+			if search_category(r) != group.category {
+				continue
+			}
+			if opts.max_per_category > 0 && shown >= opts.max_per_category {
+				break
+			}
+			write_vup_row(builder, r, opts.show_repo)
+			shown += 1
+		}
+		if shown < group.count {
+			fmt.sbprintf(builder, "  (%d more in %s)\n", group.count - shown, group.category)
+		}
+	}
+}
+
+// Unified search across VUP and official repos, appending a section to builder.
+// Returns the number of matches.
 unified_search :: proc(
+	builder: ^strings.Builder,
 	idx: ^index.Index,
 	query: string,
-	vup_only: bool,
-	description_search: bool,
-) {
-	vup_results := search_vup(idx, query, description_search)
+	opts: Search_Options,
+) -> int {
+	vup_results := search_vup(idx, query, opts)
 
+	// Official packages belong to no VUP repo and carry no dates or licenses
 	official_results: [dynamic]Search_Result
-	if !vup_only {
-		official_results = search_official(query, description_search)
+	if !opts.vup_only && len(opts.repo_only) == 0 && len(opts.new_since) == 0 && len(opts.license) == 0 {
+		official_results = search_official(query, opts)
+	}
+
+	if opts.installed_only {
+		filter_installed(&vup_results)
+		filter_installed(&official_results)
 	}
 
 	total := len(vup_results) + len(official_results)
 
 	if total == 0 {
-		fmt.printf("No packages found matching '%s'\n", query)
-		return
+		if opts.installed_only {
+			fmt.sbprintf(builder, "No installed packages found matching '%s'\n", query)
+		} else {
+			fmt.sbprintf(builder, "No packages found matching '%s'\n", query)
+		}
+		return 0
 	}
 
 	// Format results
-	output := format_search_results(vup_results[:], official_results[:], context.temp_allocator)
+	strings.write_string(
+		builder,
+		format_search_results(vup_results[:], official_results[:], opts, context.temp_allocator),
+	)
+	return total
+}
+
+// Update status of an installed VUP search result: "update" when the index
+// has a newer version, "newer" when the installed one is ahead of the index
+search_update_status :: proc(r: Search_Result) -> string {
+	switch c := xbps.version_compare(r.version, r.installed_ver); {
+	case c > 0:
+		return "update"
+	case c < 0:
+		return "newer"
+	}
+	return "current"
+}
+
+// --available-updates: installed VUP matches of a query with their installed
+// and index versions, appending a section to builder. Returns the number of
+// matches.
+search_updates :: proc(
+	builder: ^strings.Builder,
+	idx: ^index.Index,
+	query: string,
+	opts: Search_Options,
+) -> int {
+	results := search_vup(idx, query, opts)
+	filter_installed(&results)
+
+	if len(results) == 0 {
+		fmt.sbprintf(builder, "No installed VUP packages found matching '%s'\n", query)
+		return 0
+	}
+
+	fmt.sbprintf(builder, "\n%-30s %-18s %-18s %s\n", "NAME", "INSTALLED", "AVAILABLE", "STATUS")
+	strings.write_string(builder, strings.repeat("-", 80, context.temp_allocator))
+	strings.write_string(builder, "\n")
+
+	updates := 0
+	for r in results {
+		status := search_update_status(r)
+		if status == "update" {
+			updates += 1
+			fmt.sbprintf(
+				builder,
+				"%-30s %-18s %-18s %s%s%s\n",
+				r.name,
+				r.installed_ver,
+				r.version,
+				errors.COLOR_WARNING,
+				status,
+				errors.COLOR_RESET,
+			)
+		} else {
+			fmt.sbprintf(builder, "%-30s %-18s %-18s %s\n", r.name, r.installed_ver, r.version, status)
+		}
+	}
+
+	fmt.sbprintf(builder, "\n%d of %d installed package(s) have updates available\n", updates, len(results))
+	return len(results)
+}
+
+// --available-updates --porcelain: query, name, installed, available, status
+search_updates_porcelain :: proc(idx: ^index.Index, query: string, opts: Search_Options) {
+	results := search_vup(idx, query, opts)
+	filter_installed(&results)
+
+	for r in results {
+		fmt.printf(
+			"%s\t%s\t%s\t%s\t%s\n",
+			query,
+			r.name,
+			r.installed_ver,
+			r.version,
+			search_update_status(r),
+		)
+	}
+}
+
+// Version the repo of a VUP package actually serves for this architecture,
+// and how the index version compares: "current", "stale" (the repo has a
+// newer build than the index says), "ahead" (the index names a version the
+// repo doesn't have yet) or "missing" (no such package in the repo)
+repo_version_status :: proc(name: string, pkg: index.Package_Info, arch: string) -> (repo_ver: string, status: string) {
+	repo_url, url_ok := pkg.repo_urls[arch]
+	if !url_ok {
+		return "", "missing"
+	}
+
+	version, ok := xbps.get_repo_version(name, repo_url, utils.run_command_output, context.temp_allocator)
+	if !ok {
+		return "", "missing"
+	}
+
+	switch c := xbps.version_compare(version, pkg.version); {
+	case c > 0:
+		return version, "stale"
+	case c < 0:
+		return version, "ahead"
+	}
+	return version, "current"
+}
+
+// --repo-version: VUP matches of a query with the version the index claims
+// and the one their repo serves, asking each repo with xbps-query. Appends a
+// section to builder and returns the number of matches.
+search_repo_versions :: proc(
+	builder: ^strings.Builder,
+	idx: ^index.Index,
+	query: string,
+	opts: Search_Options,
+) -> int {
+	results := search_vup(idx, query, opts)
+	if len(results) == 0 {
+		fmt.sbprintf(builder, "No VUP packages found matching '%s'\n", query)
+		return 0
+	}
+
+	arch, arch_ok := cfg.get_arch()
+	if !arch_ok {
+		errors.print_error(errors.make_error(.Arch_Detection_Failed))
+		return 0
+	}
+
+	errors.log_info("Querying the repos of %d package(s)...", len(results))
+
+	fmt.sbprintf(builder, "\n%-30s %-18s %-18s %s\n", "NAME", "INDEX", "REPO", "STATUS")
+	strings.write_string(builder, strings.repeat("-", 80, context.temp_allocator))
+	strings.write_string(builder, "\n")
+
+	mismatches := 0
+	for r in results {
+		repo_ver, status := repo_version_status(r.name, idx.packages[r.name], arch)
+		if status == "current" {
+			fmt.sbprintf(builder, "%-30s %-18s %-18s %s\n", r.name, r.version, repo_ver, status)
+			continue
+		}
+		mismatches += 1
+		fmt.sbprintf(
+			builder,
+			"%-30s %-18s %-18s %s%s%s\n",
+			r.name,
+			r.version,
+			repo_ver if len(repo_ver) > 0 else "-",
+			errors.COLOR_WARNING,
+			status,
+			errors.COLOR_RESET,
+		)
+	}
+
+	fmt.sbprintf(builder, "\n%d of %d package(s) differ from their repo\n", mismatches, len(results))
+	return len(results)
+}
+
+// --repo-version --porcelain: query, name, index version, repo version, status
+search_repo_versions_porcelain :: proc(idx: ^index.Index, query: string, opts: Search_Options) {
+	arch, arch_ok := cfg.get_arch()
+	if !arch_ok {
+		errors.print_error(errors.make_error(.Arch_Detection_Failed))
+		return
+	}
+
+	results := search_vup(idx, query, opts)
+	for r in results {
+		repo_ver, status := repo_version_status(r.name, idx.packages[r.name], arch)
+		fmt.printf("%s\t%s\t%s\t%s\t%s\n", query, r.name, r.version, repo_ver, status)
+	}
+}
 
+// Print search output, paging it when there are many results
+show_search_output :: proc(output: string, total: int) {
 	// Use pager if more than threshold
 	if total > PAGER_THRESHOLD {
 		// Write to temp file and show in less
 		path, ok := utils.diff_write_temp_file(output, context.temp_allocator)
 		if ok {
-			defer os.remove(path)
+			defer utils.temp_file_remove(path)
 			utils.run_command({"less", "-R", path})
 		} else {
 			// Fallback to direct print
@@ -1,17 +1,56 @@
 package commands
 
+import "core:os"
+
 import errors "../core/errors"
 import index "../core/index"
 
 // Sync command implementation
 sync_run :: proc(args: []string, config: ^Config) -> int {
+	// Dry run - only report whether there is something to sync
+	if config.dry_run {
+		available, ok := index.index_check_for_update(config.index_url)
+		if !ok {
+			errors.log_error("Failed to check for index updates")
+			return errors.EXIT_INDEX_FAILED
+		}
+
+		if available {
+			errors.log_info("Index update available")
+		} else {
+			errors.log_info("Index is up to date")
+		}
+		return 0
+	}
+
+	// For cron: nothing at all unless the index changed (warnings still show)
+	if config.quiet_unchanged {
+		errors.quiet = true
+	}
+
 	// Force sync
 	idx, ok := index.index_load_or_fetch(config.index_url, true)
+	errors.quiet = config.quiet
 	if !ok {
 		errors.log_error("Failed to sync package index")
-		return 1
+		return errors.EXIT_INDEX_FAILED
+	}
+
+	// Keep the completion names current once gen-completion-cache was used
+	names_path, names_ok := index.completion_names_path(context.temp_allocator)
+	if names_ok && os.exists(names_path) && !index.write_completion_names(&idx) {
+		errors.log_warning("Failed to update %s", names_path)
+	}
+
+	if config.quiet_unchanged && !index.index_was_updated() {
+		return 0
+	}
+
+	if index.index_count(&idx) == 0 {
+		errors.log_warning("Package index synchronized, but it lists no packages (possibly a broken publish)")
+		return errors.EXIT_INDEX_FAILED
 	}
 
-	errors.log_info("Package index synchronized")
+	errors.log_info("Package index synchronized (%d packages)", index.index_count(&idx))
 	return 0
 }
@@ -0,0 +1,366 @@
+package commands
+
+import "core:fmt"
+import "core:mem/virtual"
+import "core:os"
+import "core:slice"
+import "core:strings"
+
+import errors "../core/errors"
+import index "../core/index"
+import utils "../utils"
+
+// Interactive package browser, only built with `make TUI=1`
+VURU_TUI :: #config(VURU_TUI, false)
+
+// TUI command - browse the index, mark packages and install them
+tui_run :: proc(args: []string, config: ^Config) -> int {
+	when !VURU_TUI {
+		errors.log_error("This vuru was built without the TUI (rebuild with 'make TUI=1')")
+		return 1
+	} else {
+		return tui_main(args, config)
+	}
+}
+
+when VURU_TUI {
+	@(private)
+	Tui_State :: struct {
+		idx:       ^index.Index,
+		names:     []string, // all index packages, sorted
+		visible:   [dynamic]string, // names matching the filter
+		filter:    strings.Builder,
+		marked:    map[string]bool,
+		installed: map[string]string,
+		cursor:    int,
+		scroll:    int,
+		details:   bool,
+		rows:      int,
+		cols:      int,
+	}
+
+	// Undoes the alternate screen and hidden cursor of tui_enter_raw_mode
+	@(private)
+	TUI_LEAVE_SCREEN :: "\x1b[?25h\x1b[?1049l"
+
+	@(private)
+	Tui_Key :: enum {
+		None,
+		Char,
+		Backspace,
+		Up,
+		Down,
+		Page_Up,
+		Page_Down,
+		Enter,
+		Tab,
+		Install,
+		Quit,
+	}
+
+	@(private)
+	tui_main :: proc(args: []string, config: ^Config) -> int {
+		if _, non_interactive := utils.non_interactive_reason(); non_interactive {
+			errors.log_error("vuru tui needs an interactive terminal")
+			return 1
+		}
+
+		idx, ok := index.index_load_or_fetch(config.index_url, false)
+		if !ok {
+			errors.log_error("Failed to load package index")
+			return errors.EXIT_INDEX_FAILED
+		}
+
+		// The state lives for the whole session, on its own arena; everything
+		// else is per key press in the temp allocator, freed every round
+		state_arena: virtual.Arena
+		if virtual.arena_init_growing(&state_arena) != nil {
+			errors.log_error("Failed to allocate TUI state")
+			return 1
+		}
+		defer virtual.arena_destroy(&state_arena)
+		state_allocator := virtual.arena_allocator(&state_arena)
+
+		state := Tui_State {
+			idx = &idx,
+			visible = make([dynamic]string, 0, len(idx.packages), state_allocator),
+			filter = strings.builder_make(state_allocator),
+			marked = make(map[string]bool, state_allocator),
+			installed = installed_package_versions(),
+		}
+
+		names := make([dynamic]string, 0, len(idx.packages), state_allocator)
+		for name in idx.packages {
+			append(&names, name)
+		}
+		slice.sort(names[:])
+		state.names = names[:]
+
+		// An initial query can be passed as arguments
+		strings.write_string(&state.filter, strings.join(args, " ", context.temp_allocator))
+		tui_apply_filter(&state)
+
+		saved_tty, raw_ok := tui_enter_raw_mode(state_allocator)
+		if !raw_ok {
+			errors.log_error("Failed to switch the terminal to raw mode")
+			return 1
+		}
+		state.rows, state.cols = tui_terminal_size()
+
+		install := false
+		for {
+			free_all(context.temp_allocator)
+			tui_render(&state)
+
+			key, ch := tui_read_key()
+			#partial switch key {
+			case .Quit:
+			case .Install:
+				install = len(state.marked) > 0
+				if !install {
+					continue
+				}
+			case:
+				tui_handle_key(&state, key, ch)
+				continue
+			}
+			break
+		}
+
+		tui_leave_raw_mode(saved_tty)
+		free_all(context.temp_allocator)
+
+		if !install {
+			return 0
+		}
+
+		marked := make([dynamic]string, 0, len(state.marked), context.temp_allocator)
+		for name in state.marked {
+			append(&marked, name)
+		}
+		slice.sort(marked[:])
+
+		// The regular install path: dependency resolution, review, hooks
+		return install_run(marked[:], config)
+	}
+
+	// Put the terminal in raw mode, returns the settings to restore. A signal
+	// (SIGTERM, or SIGINT from outside: raw mode reads Ctrl-C as a key)
+	// restores them too, through the terminal guard.
+	@(private)
+	tui_enter_raw_mode :: proc(allocator := context.allocator) -> (string, bool) {
+		saved, ok := utils.run_command_output({"stty", "-g"}, allocator)
+		if !ok {
+			return "", false
+		}
+		if !utils.terminal_guard_begin(TUI_LEAVE_SCREEN) {
+			return "", false
+		}
+		if utils.run_command({"stty", "raw", "-echo"}) != 0 {
+			utils.terminal_guard_end()
+			return "", false
+		}
+		// Alternate screen, hidden cursor
+		fmt.print("\x1b[?1049h\x1b[?25l")
+		return strings.trim_space(saved), true
+	}
+
+	@(private)
+	tui_leave_raw_mode :: proc(saved: string) {
+		fmt.print(TUI_LEAVE_SCREEN)
+		utils.run_command({"stty", saved})
+		utils.terminal_guard_end()
+	}
+
+	// Terminal rows and columns (24x80 if stty can't tell)
+	@(private)
+	tui_terminal_size :: proc() -> (rows: int, cols: int) {
+		rows, cols = 24, 80
+		output, ok := utils.run_command_output({"stty", "size"}, context.temp_allocator)
+		if !ok {
+			return
+		}
+		fields := strings.fields(output, context.temp_allocator)
+		if len(fields) == 2 {
+			r := utils.parse_int(fields[0])
+			c := utils.parse_int(fields[1])
+			if r > 0 && c > 0 {
+				rows, cols = r, c
+			}
+		}
+		return
+	}
+
+	// Read one key press; escape sequences are decoded to arrow/page keys
+	@(private)
+	tui_read_key :: proc() -> (Tui_Key, u8) {
+		buf: [8]u8
+		n, err := os.read(os.stdin, buf[:])
+		if n <= 0 || err != nil {
+			return .Quit, 0
+		}
+
+		c := buf[0]
+		switch {
+		case c == 27 && n == 1:
+			return .Quit, 0
+		case c == 27 && n >= 3 && buf[1] == '[':
+			switch buf[2] {
+			case 'A':
+				return .Up, 0
+			case 'B':
+				return .Down, 0
+			case '5':
+				return .Page_Up, 0
+			case '6':
+				return .Page_Down, 0
+			}
+			return .None, 0
+		case c == 3: // Ctrl-C
+			return .Quit, 0
+		case c == 15: // Ctrl-O
+			return .Install, 0
+		case c == 14: // Ctrl-N
+			return .Down, 0
+		case c == 16: // Ctrl-P
+			return .Up, 0
+		case c == 9:
+			return .Tab, 0
+		case c == '\r' || c == '\n':
+			return .Enter, 0
+		case c == 127 || c == 8:
+			return .Backspace, 0
+		case c >= 32 && c < 127:
+			return .Char, c
+		}
+		return .None, 0
+	}
+
+	@(private)
+	tui_handle_key :: proc(state: ^Tui_State, key: Tui_Key, ch: u8) {
+		page := max(tui_list_rows(state), 1)
+
+		#partial switch key {
+		case .Char:
+			strings.write_byte(&state.filter, ch)
+			tui_apply_filter(state)
+		case .Backspace:
+			strings.pop_byte(&state.filter)
+			tui_apply_filter(state)
+		case .Up:
+			state.cursor -= 1
+		case .Down:
+			state.cursor += 1
+		case .Page_Up:
+			state.cursor -= page
+		case .Page_Down:
+			state.cursor += page
+		case .Enter:
+			state.details = !state.details
+		case .Tab:
+			if len(state.visible) > 0 {
+				name := state.visible[state.cursor]
+				if name in state.marked {
+					delete_key(&state.marked, name)
+				} else {
+					state.marked[name] = true
+				}
+				state.cursor += 1
+			}
+		}
+
+		state.cursor = clamp(state.cursor, 0, max(len(state.visible) - 1, 0))
+	}
+
+	// Recompute the visible list from the filter (same term rules as search)
+	@(private)
+	tui_apply_filter :: proc(state: ^Tui_State) {
+		clear(&state.visible)
+		terms := index.search_terms(strings.to_string(state.filter), context.temp_allocator)
+
+		for name in state.names {
+			pkg := state.idx.packages[name]
+			if len(terms) == 0 || index.search_matches(name, pkg.short_desc, terms, false, true) {
+				append(&state.visible, name)
+			}
+		}
+
+		state.cursor = 0
+		state.scroll = 0
+	}
+
+	// Rows left for the package list below the header and above the details
+	@(private)
+	tui_list_rows :: proc(state: ^Tui_State) -> int {
+		DETAILS_ROWS :: 6
+		return state.rows - 3 - (DETAILS_ROWS if state.details else 0)
+	}
+
+	@(private)
+	tui_render :: proc(state: ^Tui_State) {
+		b := strings.builder_make(context.temp_allocator)
+		list_rows := tui_list_rows(state)
+		installed_set := state.installed
+
+		// Keep the cursor on screen
+		if state.cursor < state.scroll {
+			state.scroll = state.cursor
+		} else if state.cursor >= state.scroll + list_rows {
+			state.scroll = state.cursor - list_rows + 1
+		}
+
+		strings.write_string(&b, "\x1b[H\x1b[2J")
+		fmt.sbprintf(
+			&b,
+			"%sSearch:%s %s\r\n",
+			errors.COLOR_BOLD,
+			errors.COLOR_RESET,
+			strings.to_string(state.filter),
+		)
+		fmt.sbprintf(
+			&b,
+			"%d/%d packages, %d marked  [Tab] mark  [Enter] details  [Ctrl-O] install  [Esc] quit\r\n",
+			len(state.visible),
+			len(state.names),
+			len(state.marked),
+		)
+
+		for row in 0 ..< list_rows {
+			i := state.scroll + row
+			if i >= len(state.visible) {
+				strings.write_string(&b, "\r\n")
+				continue
+			}
+
+			name := state.visible[i]
+			pkg := state.idx.packages[name]
+			line := fmt.tprintf(
+				"%s %-30s %-15s %s",
+				"*" if name in state.marked else " ",
+				name,
+				pkg.version,
+				"[installed]" if name in installed_set else "",
+			)
+			line = truncate(line, state.cols - 1)
+
+			if i == state.cursor {
+				fmt.sbprintf(&b, "\x1b[7m%s%s\r\n", line, errors.COLOR_RESET)
+			} else {
+				fmt.sbprintf(&b, "%s\r\n", line)
+			}
+		}
+
+		if state.details && len(state.visible) > 0 {
+			name := state.visible[state.cursor]
+			pkg := state.idx.packages[name]
+			fmt.sbprintf(&b, "%s%s%s\r\n", errors.COLOR_BOLD, name, errors.COLOR_RESET)
+			fmt.sbprintf(&b, "  Version:     %s\r\n", pkg.version)
+			fmt.sbprintf(&b, "  Category:    %s\r\n", pkg.category)
+			fmt.sbprintf(&b, "  Repo:        %s\r\n", index.package_repo_name(pkg))
+			fmt.sbprintf(&b, "  Installed:   %s\r\n", "yes" if name in installed_set else "no")
+			fmt.sbprintf(&b, "  %s\r\n", truncate(pkg.short_desc, state.cols - 3))
+		}
+
+		fmt.print(strings.to_string(b))
+	}
+}
@@ -9,25 +9,80 @@ Config :: struct {
 	vup_dir:            string,
 	arch:               string,
 	rootdir:            string, // -r, --rootdir
+	env_vars:           [dynamic]string, // --env, KEY=VALUE for spawned xbps commands (repeatable)
+	template_ref:       string, // --template-ref
+	template_base:      string, // --template-base, VURU_TEMPLATE_BASE
+	output_path:        string, // --output/--out, completion script or update plan file
+	apply_plan:         string, // --apply, update: install exactly the upgrades of a plan file
+	template_file:      string, // --from-file, install: local template to review instead of upstream
+	save_to:            string, // --save-to, install: directory archiving the installed templates
+	since:              string, // --since, history: only entries newer than a duration (7d)
+	repo_only:          string, // --repo-only, search: a single repo ("vup" = main index)
+	search_sort:        string, // --sort, search: relevance, version or name
+	new_since:          string, // --new-since, search/list-packages: only packages changed since YYYY-MM-DD
+	license:            string, // --license, search: only VUP packages whose license contains this
+	max_per_category:   int, // --max-per-category, search: rows per category group (implies grouping)
+	max_concurrent:     int, // --max-concurrent, parallel template downloads (0 = default)
+	max_download:       i64, // --max-download, byte budget for index and template downloads (-1 = none)
+	repo_priority:      int, // --repo-priority, repo add: priority of the new repo
 
 	// Runtime flags
 	yes:                bool, // -y, --yes
+	default_no:         bool, // --default-no, empty answer to prompts means "no"
+	interactive:        bool, // --interactive, prompt even in CI / without a TTY
+	read_stdin:         bool, // --stdin, install: package names from stdin (same as "-")
 	dry_run:            bool, // -n, --dry-run
 	force_build:        bool, // -b, --build
 	vup_only:           bool, // --vup-only
+	installed_only:     bool, // --installed, search: only installed packages
 	description_search: bool, // -d, --desc
+	search_any:         bool, // --any, search: match any query term instead of all
+	search_group:       bool, // --group-by-category, search: VUP results under category headers
+	available_updates:  bool, // --available-updates, search: installed vs index version of installed matches
+	repo_version:       bool, // --repo-version, search/info: ask the repos for their actual version
 	verbose:            bool, // -v, --verbose
+	quiet:              bool, // -q, --quiet, only warnings and errors
+	quiet_unchanged:    bool, // --quiet-if-unchanged, sync: no output when the index didn't change
+	preview:            bool, // install: show templates and xbps dry run first
+	confirm_each:       bool, // --confirm-each, install: continue/skip/abort prompt before every step
+	no_review:          bool, // --no-review, install: fetch no templates, xbps resolves VUP dependencies
+	keep_diff_files:    bool, // --keep-diff-files, leave <pkg>.old/.new in TMPDIR
+	stats:              bool, // --stats, update: group pending upgrades by category
+	simulate:           bool, // --simulate, update/remove: write a plan (to --out) instead of acting
+	porcelain:          bool, // --porcelain, stable tab-separated output
+	list_shells:        bool, // --list-shells, completion: supported shells instead of a script
+	json:               bool, // --json, history: JSON output
+	notify:             bool, // --notify, desktop notification when install/update finishes
+	as_dependency:      bool, // --as-dependency, install: mark as automatically installed
+	trust_repo:         bool, // --trust-repo, install: import signing keys of VUP repos
+	tree:               bool, // --tree, install: show the dependency tree xbps would pull in
+	print_repo:         bool, // --print-repo, install: print name, version and repo URL, install nothing
+	select_version:     bool, // --select-version, install: pick one of the versions the repos offer
+	idempotent:         bool, // --idempotent, install: succeed quietly for packages already at the index version
+	verify_installed:   bool, // --verify-installed, install: check installed versions against the index
+	ignore_conf_repos:  bool, // --from-repo, install/update: VUP packages only from their index repo
+	download_first:     bool, // --download-first, update: download every upgrade before installing any
+	allow_insecure:     bool, // --allow-insecure-repo, accept plain http:// repos without asking
 
 	// XBPS-aligned flags
 	sync:               bool, // -S, sync repos
 	update_system:      bool, // -u, update packages
 	recursive:          bool, // -R, recursive remove/deps
 	orphans:            bool, // -o, remove orphans
+	clean_orphans:      bool, // --clean-orphans, remove: drop orphans after removing
 	clean_cache:        bool, // -O, clean cache
 	list_pkgs:          bool, // -l, list installed
 	show_files:         bool, // -f, show files
 	show_deps:          bool, // -x, show deps
 	ownedby:            bool, // query: find file owner
+	prune_cache:        bool, // clean: drop templates of uninstalled packages
+	rebuild_cache:      bool, // --force-cache-rebuild, delete cached indexes and refetch
+	clean_binpkgs:      bool, // clean: clear the xbps binpkg cache
+	clean_all:          bool, // --all, clean: templates, cached indexes and binpkgs
+	no_templates:       bool, // --no-templates, clean --all: keep cached templates
+	no_index:           bool, // --no-index, clean --all: keep cached indexes
+	no_binpkgs:         bool, // --no-binpkgs, clean --all: keep the binpkg cache
+	repo_test:          bool, // --test, repo add: check the URL without saving the repo
 
 	// Allocator for owned strings
 	allocator:          mem.Allocator,
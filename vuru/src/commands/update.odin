@@ -1,12 +1,14 @@
 package commands
 
 import "core:fmt"
-import "core:os"
+import "core:slice"
 import "core:strings"
 
+import audit "../core/audit"
 import config "../core/config"
 import errors "../core/errors"
 import index "../core/index"
+import pins "../core/pins"
 import template "../core/template"
 import xbps "../core/xbps"
 import utils "../utils"
@@ -19,6 +21,7 @@ Upgrade_Info :: struct {
 	new_ver:         string,
 	repo_url:        string,
 	category:        string,
+	template_name:   string, // srcpkgs directory of the template
 	new_template:    string,
 	cached_template: string,
 }
@@ -28,26 +31,73 @@ update_run :: proc(args: []string, config: ^Config) -> int {
 	// args ignored for update (except maybe specific packages, but update usually means all)
 	// cmd_update in main.odin called xbps_upgrade_all(idx, args.yes)
 
+	// A saved plan carries its own repo URLs, no index needed
+	if len(config.apply_plan) > 0 {
+		return update_apply_plan(config.apply_plan, config)
+	}
+
 	// Load index (force sync)
 	idx, ok := index.index_load_or_fetch(config.index_url, true)
 	if !ok {
 		errors.log_error("Failed to load package index")
-		return 1
+		return errors.EXIT_INDEX_FAILED
+	}
+
+	// --simulate: write the pending VUP upgrades as a plan, install nothing
+	if config.simulate {
+		return update_write_plan(&idx, config)
+	}
+
+	// Dry run - list pending VUP upgrades only
+	if config.dry_run {
+		return update_list(&idx, config)
+	}
+
+	// --download-first: the official upgrades are downloaded here, then
+	// installed with the VUP ones once those are downloaded too
+	if config.download_first {
+		errors.log_info("Downloading official upgrades...")
+		if xbps.download_all_official(config.yes, utils.run_command) != 0 {
+			if utils.command_interrupted() {
+				errors.log_error("Download interrupted")
+			} else {
+				errors.log_error("Failed to download official upgrades")
+			}
+			errors.log_info("Nothing was installed")
+			return errors.EXIT_ABORTED if utils.command_interrupted() else errors.EXIT_XBPS_FAILED
+		}
+		return xbps_upgrade_all(&idx, config, upgrade_official = true)
 	}
 
 	// Update official Void packages first
 	ret := xbps.upgrade_all_official(config.yes, utils.run_command)
 	if ret != 0 {
-		return ret
+		if config.notify {
+			utils.desktop_notify("vuru: update failed", "Upgrading official packages failed")
+		}
+		return errors.EXIT_XBPS_FAILED
 	}
 
 	// Then update VUP packages
-	return xbps_upgrade_all(&idx, config.yes)
+	return xbps_upgrade_all(&idx, config)
 }
 
-// Compare versions using xbps-uhelper
+// Install the official upgrades (--download-first, after the downloads)
+@(private)
+update_official :: proc(yes: bool) -> bool {
+	errors.log_info("Upgrading official packages...")
+	if xbps.upgrade_all_official(yes, utils.run_command) != 0 {
+		errors.log_error("Upgrading official packages failed")
+		return false
+	}
+	return true
+}
+
+// Compare versions (xbps dewey order). Done in-process, like the pkgver
+// parsing, so update detection costs a single xbps-query -l however many
+// packages are installed.
 version_gt :: proc(v1: string, v2: string) -> bool {
-	return xbps.version_greater_than(v1, v2, utils.run_command)
+	return xbps.version_greater_than(v1, v2)
 }
 
 // Get the currently installed version of a package
@@ -57,16 +107,11 @@ get_installed_version :: proc(pkg_name: string, allocator := context.allocator)
 
 // Parse installed package line from xbps-query -l
 parse_installed_pkg :: proc(line: string) -> (name: string, version: string, ok: bool) {
-	parts := strings.fields(line, context.temp_allocator)
-	if len(parts) < 2 {
-		return "", "", false
-	}
-
-	return xbps.parse_pkgver(parts[1])
+	return xbps.parse_installed_line(line)
 }
 
 // Show batched diffs in less pager
-show_batch_review :: proc(upgrades: []Upgrade_Info) -> bool {
+show_batch_review :: proc(upgrades: []Upgrade_Info, default_yes: bool, keep_files: bool) -> bool {
 	builder := strings.builder_make(context.temp_allocator)
 
 	strings.write_string(&builder, "VUP Package Upgrade Review\n")
@@ -102,12 +147,16 @@ show_batch_review :: proc(upgrades: []Upgrade_Info) -> bool {
 				u.cached_template,
 				u.new_template,
 				context.temp_allocator,
+				u.name if keep_files else "",
 			)
 			if diff_ok && len(diff) > 0 {
 				strings.write_string(&builder, diff)
 				strings.write_string(&builder, "\n")
 			}
 		} else {
+			if keep_files {
+				utils.diff_keep_files(u.name, "", u.new_template)
+			}
 			strings.write_string(&builder, "(New package - showing full template)\n\n")
 			strings.write_string(&builder, u.new_template)
 			strings.write_string(&builder, "\n")
@@ -121,42 +170,125 @@ show_batch_review :: proc(upgrades: []Upgrade_Info) -> bool {
 		errors.log_error("Failed to create review file")
 		return false
 	}
-	defer os.remove(review_path)
+	defer utils.temp_file_remove(review_path)
 
 	// Show in less
 	utils.diff_show_pager(review_path)
 
 	// Prompt for confirmation
-	fmt.printf("Proceed with %d upgrade(s)? [Y/n] ", len(upgrades))
+	return utils.prompt_yes_no(
+		fmt.tprintf("Proceed with %d upgrade(s)?", len(upgrades)),
+		default_yes,
+	)
+}
 
-	buf: [100]u8
-	n, _ := os.read(os.stdin, buf[:])
+// List pending VUP upgrades (update -n), optionally of some packages only
+update_list :: proc(idx: ^index.Index, config: ^Config, only: []string = nil) -> int {
+	upgrades, ok := collect_upgrades(idx, only)
+	if !ok {
+		return 1
+	}
 
-	if n <= 0 {
-		return false
+	// Porcelain: name, installed version, new version, category (tab-separated)
+	if config.porcelain {
+		for u in upgrades {
+			fmt.printf("%s\t%s\t%s\t%s\n", u.name, u.installed_ver, u.new_ver, u.category)
+		}
+		return 0
+	}
+
+	if len(upgrades) == 0 {
+		errors.log_info("All VUP packages are up to date")
+		return 0
+	}
+
+	print_upgrade_summary(upgrades[:])
+	if config.stats {
+		print_upgrade_stats(upgrades[:])
+	}
+	return 0
+}
+
+// Print the list of pending upgrades
+print_upgrade_summary :: proc(upgrades: []Upgrade_Info) {
+	fmt.println()
+	fmt.printf("%d package(s) to upgrade:\n", len(upgrades))
+	for u in upgrades {
+		fmt.printf("  %s: %s -> %s\n", u.name, u.installed_ver, u.new_ver)
+	}
+	fmt.println()
+}
+
+// Number of packages in one category
+Category_Count :: struct {
+	category: string,
+	count:    int,
+}
+
+// Count how often each category occurs, largest group first
+count_by_category :: proc(categories: []string) -> []Category_Count {
+	counts := make([dynamic]Category_Count, context.temp_allocator)
+	outer: for category in categories {
+		for &c in counts {
+			if c.category == category {
+				c.count += 1
+				continue outer
+			}
+		}
+		append(&counts, Category_Count{category, 1})
+	}
+
+	slice.sort_by(counts[:], proc(a, b: Category_Count) -> bool {
+		if a.count != b.count {
+			return a.count > b.count
+		}
+		return a.category < b.category
+	})
+	return counts[:]
+}
+
+// Print pending upgrades grouped by category, largest group first
+print_upgrade_stats :: proc(upgrades: []Upgrade_Info) {
+	categories := make([]string, len(upgrades), context.temp_allocator)
+	for u, i in upgrades {
+		categories[i] = u.category
 	}
 
-	input := strings.trim_space(string(buf[:n]))
-	input_lower := strings.to_lower(input, context.temp_allocator)
+	parts := make([dynamic]string, context.temp_allocator)
+	for c in count_by_category(categories) {
+		append(&parts, fmt.tprintf("%s: %d", c.category, c.count))
+	}
 
-	return len(input) == 0 || input_lower == "y" || input_lower == "yes"
+	fmt.printf("By category: %s\n", strings.join(parts[:], ", ", context.temp_allocator))
+	fmt.println()
 }
 
-// Upgrade all VUP packages
-xbps_upgrade_all :: proc(idx: ^index.Index, yes: bool) -> int {
+// Collect installed VUP packages that have a newer version in the index
+collect_upgrades :: proc(idx: ^index.Index, only: []string = nil) -> ([dynamic]Upgrade_Info, bool) {
 	errors.log_info("Checking for VUP package updates...")
 
+	upgrades: [dynamic]Upgrade_Info
+
 	output, ok := utils.run_command_output({"xbps-query", "-l"})
 	if !ok {
 		errors.log_error("Failed to run xbps-query")
-		return -1
+		return upgrades, false
 	}
 
+	// Get architecture-specific repo URLs
+	arch, arch_ok := config.get_arch()
+	if !arch_ok {
+		errors.print_error(errors.make_error(.Arch_Detection_Failed))
+		return upgrades, false
+	}
 
-	upgrades: [dynamic]Upgrade_Info
-
+	// Pinned packages (vuru pin) are held back
+	pinned, pins_ok := pins.pins_load(context.temp_allocator)
+	if !pins_ok {
+		return upgrades, false
+	}
+	held := make([dynamic]string, context.temp_allocator)
 
-	// Phase 1: Collect packages needing upgrade
 	lines := output
 	for line in strings.split_lines_iterator(&lines) {
 		if len(upgrades) >= MAX_UPGRADES {
@@ -164,7 +296,13 @@ xbps_upgrade_all :: proc(idx: ^index.Index, yes: bool) -> int {
 		}
 
 		name, installed_ver, parse_ok := parse_installed_pkg(line)
-		if !parse_ok {continue}
+		if !parse_ok {
+			if !xbps.pkgver_is_readable(line) {
+				errors.log_warning("Skipping an installed package with an unreadable name: %q", line)
+			}
+			continue
+		}
+		if len(only) > 0 && !slice.contains(only, name) {continue}
 
 		pkg, pkg_ok := index.index_get_package(idx, name)
 		if !pkg_ok {continue}
@@ -173,15 +311,14 @@ xbps_upgrade_all :: proc(idx: ^index.Index, yes: bool) -> int {
 			continue
 		}
 
-		// Get architecture-specific repo URL
-		arch, arch_ok := config.get_arch()
-		if !arch_ok {continue}
-
-
 		repo_url, url_ok := pkg.repo_urls[arch]
 		if !url_ok {continue}
 
 		if version_gt(pkg.version, installed_ver) {
+			if slice.contains(pinned, name) {
+				append(&held, fmt.tprintf("%s (%s)", name, pkg.version))
+				continue
+			}
 			append(
 				&upgrades,
 				Upgrade_Info {
@@ -190,34 +327,82 @@ xbps_upgrade_all :: proc(idx: ^index.Index, yes: bool) -> int {
 					new_ver = strings.clone(pkg.version),
 					repo_url = strings.clone(repo_url),
 					category = strings.clone(pkg.category),
+					template_name = strings.clone(index.package_template_name(pkg, name)),
 				},
 			)
 		}
 	}
 
+	if len(held) > 0 {
+		errors.log_info("Holding back pinned: %s", strings.join(held[:], ", ", context.temp_allocator))
+	}
+
+	return upgrades, true
+}
+
+// Upgrade all VUP packages, or only the ones listed in only. With
+// upgrade_official the official upgrades are installed too, right before the
+// VUP ones (after their downloads with --download-first).
+xbps_upgrade_all :: proc(
+	idx: ^index.Index,
+	config: ^Config,
+	only: []string = nil,
+	upgrade_official := false,
+) -> int {
+	yes := config.yes
+	source := template_source(config)
+
+	// Phase 1: Collect packages needing upgrade
+	upgrades, ok := collect_upgrades(idx, only)
+	if !ok {
+		return 1
+	}
+
 	if len(upgrades) == 0 {
-		errors.log_info("All VUP packages are up to date")
+		if upgrade_official && !update_official(config.yes) {
+			if config.notify {
+				utils.desktop_notify("vuru: update failed", "Upgrading official packages failed")
+			}
+			return errors.EXIT_XBPS_FAILED
+		}
+		if len(only) > 0 {
+			errors.log_info("No updates for %s", strings.join(only, ", ", context.temp_allocator))
+		} else {
+			errors.log_info("All VUP packages are up to date")
+		}
 		return 0
 	}
 
-	// Print summary
-	fmt.println()
-	fmt.printf("%d package(s) to upgrade:\n", len(upgrades))
-	for u in upgrades {
-		fmt.printf("  %s: %s -> %s\n", u.name, u.installed_ver, u.new_ver)
+	print_upgrade_summary(upgrades[:])
+	if config.stats {
+		print_upgrade_stats(upgrades[:])
+	}
+
+	if !confirm_insecure_repos(upgrade_repo_urls(upgrades[:]), config) {
+		errors.log_info("Upgrade cancelled")
+		return errors.EXIT_ABORTED
 	}
-	fmt.println()
 
 	// Phase 2: Fetch templates (unless --yes)
 	confirmed := yes
 	if !yes {
 		errors.log_info("Fetching templates for review...")
 
-		for &u in upgrades {
-			new_tmpl, tmpl_ok := template.fetch_template(u.category, u.name)
+		reqs := make([]template.Template_Request, len(upgrades), context.temp_allocator)
+		for u, i in upgrades {
+			reqs[i] = {u.category, u.template_name}
+		}
+		fetched, fetched_ok := template.fetch_templates(reqs, source, max_concurrent(config))
+
+		for &u, i in upgrades {
+			new_tmpl, tmpl_ok := fetched[i], fetched_ok[i]
+			if !tmpl_ok {
+				// Failed parallel downloads get one more, sequential try
+				new_tmpl, tmpl_ok = template.fetch_template(u.category, u.template_name, source)
+			}
 			if !tmpl_ok {
 				errors.log_error("Failed to fetch template for %s", u.name)
-				return -1
+				return errors.EXIT_INDEX_FAILED
 			}
 			u.new_template = new_tmpl
 
@@ -226,22 +411,81 @@ xbps_upgrade_all :: proc(idx: ^index.Index, yes: bool) -> int {
 		}
 
 		// Phase 3: Show batch review
-		if !show_batch_review(upgrades[:]) {
+		if !show_batch_review(upgrades[:], !config.default_no, config.keep_diff_files) {
 			errors.log_info("Upgrade cancelled by user")
-			return 0
+			return errors.EXIT_ABORTED
 		}
 		confirmed = true
 	}
 
 	// Phase 4: Perform upgrades
-	upgraded := 0
-	err_count := 0
+	upgraded, err_count := perform_upgrades(
+		upgrades[:],
+		confirmed,
+		ignore_conf_repos = config.ignore_conf_repos,
+		download_first = config.download_first,
+		upgrade_official = upgrade_official,
+		yes = config.yes,
+	)
 
-	// Group upgrades by repo URL for batch execution
-	Upgrade_Group :: struct {
-		repo_url: string,
-		upgrades: [dynamic]^Upgrade_Info,
+	if upgraded > 0 {
+		errors.log_info("Upgraded %d package(s)", upgraded)
+	} else if err_count == 0 {
+		errors.log_info("All VUP packages are up to date")
+	}
+
+	if utils.command_interrupted() {
+		return errors.EXIT_ABORTED
 	}
+
+	if config.notify {
+		if err_count > 0 {
+			utils.desktop_notify(
+				"vuru: update finished with errors",
+				fmt.tprintf("%d package(s) updated, %d group(s) failed", upgraded, err_count),
+			)
+		} else {
+			utils.desktop_notify(fmt.tprintf("vuru: %d package(s) updated", upgraded), "")
+		}
+	}
+
+	return errors.EXIT_XBPS_FAILED if err_count > 0 else 0
+}
+
+// Repo URLs of a list of upgrades
+upgrade_repo_urls :: proc(upgrades: []Upgrade_Info) -> []string {
+	urls := make([]string, len(upgrades), context.temp_allocator)
+	for u, i in upgrades {
+		urls[i] = u.repo_url
+	}
+	return urls
+}
+
+// Upgrades of one repo, installed in one xbps transaction
+@(private)
+Upgrade_Group :: struct {
+	repo_url: string,
+	upgrades: [dynamic]^Upgrade_Info,
+}
+
+// Install upgrades, one xbps transaction per repo. With exact_versions the
+// planned new_ver is requested instead of whatever the repo has now. With
+// download_first every group is downloaded before anything is installed, so
+// a network failure can't leave the system half upgraded. upgrade_official
+// installs the official upgrades first (confirmed by yes like update does).
+perform_upgrades :: proc(
+	upgrades: []Upgrade_Info,
+	confirmed: bool,
+	exact_versions := false,
+	ignore_conf_repos := false,
+	download_first := false,
+	upgrade_official := false,
+	yes := false,
+) -> (
+	upgraded: int,
+	err_count: int,
+) {
+	// Group upgrades by repo URL for batch execution
 	groups := make([dynamic]Upgrade_Group, context.temp_allocator)
 
 	for &u in upgrades {
@@ -262,22 +506,68 @@ xbps_upgrade_all :: proc(idx: ^index.Index, yes: bool) -> int {
 		}
 	}
 
-	for group in groups {
-		pkg_names := make([dynamic]string, context.temp_allocator)
-		for u in group.upgrades {
-			append(&pkg_names, u.name)
+	if download_first && len(groups) > 0 {
+		downloaded := 0
+		for group in groups {
+			pkg_names := upgrade_group_names(group, exact_versions)
+			errors.log_info("Downloading %d package(s) from VUP...", len(pkg_names))
+
+			if xbps.download_packages_from_repo(
+				   group.repo_url,
+				   pkg_names,
+				   confirmed,
+				   utils.run_command,
+				   ignore_conf_repos = ignore_conf_repos,
+			   ) !=
+			   0 {
+				if utils.command_interrupted() {
+					errors.log_error("Download interrupted")
+				} else {
+					errors.log_error("Failed to download %d package(s) from %s", len(pkg_names), group.repo_url)
+				}
+				errors.log_info("Nothing was installed")
+				return 0, 1
+			}
+			downloaded += len(pkg_names)
 		}
+		errors.log_info("All downloads done (%d VUP package(s)), installing", downloaded)
+	}
+
+	if upgrade_official {
+		if !update_official(yes) {
+			return 0, 1
+		}
+	}
+
+	for group in groups {
+		pkg_names := upgrade_group_names(group, exact_versions)
 
 		errors.log_info("Upgrading %d package(s) from VUP...", len(pkg_names))
 
-		if xbps.upgrade_packages_from_repo(group.repo_url, pkg_names[:], confirmed, utils.run_command) != 0 {
-			errors.log_error("Failed to upgrade %d package(s)", len(pkg_names))
+		if xbps.upgrade_packages_from_repo(
+			   group.repo_url,
+			   pkg_names,
+			   confirmed,
+			   utils.run_command,
+			   ignore_conf_repos = ignore_conf_repos,
+		   ) !=
+		   0 {
 			err_count += 1
+			for u in group.upgrades {
+				audit.audit_record("upgrade", u.name, u.new_ver, false)
+			}
+			// Don't start on the next repo after a Ctrl-C
+			if utils.command_interrupted() {
+				errors.log_error("Upgrade interrupted")
+				break
+			}
+			errors.log_error("Failed to upgrade %d package(s)", len(pkg_names))
 		} else {
 			for u in group.upgrades {
 				new_ver, ver_ok := get_installed_version(u.name, context.temp_allocator)
 				if ver_ok && new_ver != u.installed_ver {
 					upgraded += 1
+					audit.audit_record("upgrade", u.name, new_ver, true)
 					if len(u.new_template) > 0 {
 						template.cache_save_template(u.name, u.new_template)
 					}
@@ -286,11 +576,23 @@ xbps_upgrade_all :: proc(idx: ^index.Index, yes: bool) -> int {
 		}
 	}
 
-	if upgraded > 0 {
-		errors.log_info("Upgraded %d package(s)", upgraded)
-	} else if err_count == 0 {
-		errors.log_info("All VUP packages are up to date")
-	}
+	return upgraded, err_count
+}
 
-	return -1 if err_count > 0 else 0
+// Package arguments for xbps-install of one upgrade group
+@(private)
+upgrade_group_names :: proc(group: Upgrade_Group, exact_versions: bool) -> []string {
+	pkg_names := make([dynamic]string, context.temp_allocator)
+	for u in group.upgrades {
+		// A pkgver makes xbps install exactly that version, not the newest.
+		// xbps only takes a pkgver with its revision, so without one the
+		// name has to do.
+		_, has_revision := xbps.version_revision(u.new_ver)
+		if exact_versions && has_revision {
+			append(&pkg_names, fmt.tprintf("%s-%s", u.name, u.new_ver))
+		} else {
+			append(&pkg_names, u.name)
+		}
+	}
+	return pkg_names[:]
 }
@@ -0,0 +1,225 @@
+package commands
+
+import "core:encoding/json"
+import "core:fmt"
+import "core:slice"
+import "core:time"
+
+import cfg "../core/config"
+import errors "../core/errors"
+import index "../core/index"
+import pins "../core/pins"
+import xbps "../core/xbps"
+import utils "../utils"
+
+// Update plans - `update --simulate --out plan.json` saves the pending VUP
+// upgrades, `update --apply plan.json` installs exactly those later.
+PLAN_FORMAT_VERSION :: 1
+
+Upgrade_Plan_Entry :: struct {
+	name:              string `json:"name"`,
+	installed_version: string `json:"installed_version"`,
+	new_version:       string `json:"new_version"`,
+	repo_url:          string `json:"repo_url"`,
+	category:          string `json:"category"`,
+}
+
+Upgrade_Plan :: struct {
+	format:   int `json:"format"`,
+	created:  string `json:"created"`,
+	arch:     string `json:"arch"`,
+	upgrades: []Upgrade_Plan_Entry `json:"upgrades"`,
+}
+
+// Write pending upgrades as a plan to --out (stdout without it)
+update_write_plan :: proc(idx: ^index.Index, config: ^Config) -> int {
+	upgrades, ok := collect_upgrades(idx)
+	if !ok {
+		return 1
+	}
+
+	arch, arch_ok := cfg.get_arch()
+	if !arch_ok {
+		errors.print_error(errors.make_error(.Arch_Detection_Failed))
+		return 1
+	}
+
+	entries := make([]Upgrade_Plan_Entry, len(upgrades), context.temp_allocator)
+	for u, i in upgrades {
+		entries[i] = Upgrade_Plan_Entry {
+			name = u.name,
+			installed_version = u.installed_ver,
+			new_version = u.new_ver,
+			repo_url = u.repo_url,
+			category = u.category,
+		}
+	}
+
+	plan := Upgrade_Plan {
+		format = PLAN_FORMAT_VERSION,
+		created = utils.format_timestamp(time.now(), context.temp_allocator),
+		arch = arch,
+		upgrades = entries,
+	}
+
+	data, err := json.marshal(plan, {pretty = true}, context.temp_allocator)
+	if err != nil {
+		errors.log_error("Failed to encode update plan")
+		return 1
+	}
+
+	if len(config.output_path) == 0 {
+		fmt.println(string(data))
+		return 0
+	}
+
+	if !utils.write_file(config.output_path, string(data)) {
+		errors.log_error("Failed to write %s", config.output_path)
+		return 1
+	}
+
+	errors.log_info("Wrote plan with %d upgrade(s) to %s", len(entries), config.output_path)
+	return 0
+}
+
+// Read and check a plan written by update_write_plan
+update_read_plan :: proc(path: string) -> (Upgrade_Plan, bool) {
+	plan: Upgrade_Plan
+
+	content, ok := utils.read_file(path, context.temp_allocator)
+	if !ok {
+		errors.log_error("Failed to read plan: %s", path)
+		return plan, false
+	}
+
+	if json.unmarshal(transmute([]u8)content, &plan, allocator = context.temp_allocator) != nil {
+		errors.log_error("Invalid plan file: %s", path)
+		return plan, false
+	}
+
+	if plan.format != PLAN_FORMAT_VERSION {
+		errors.log_error("Unsupported plan format %d (expected %d)", plan.format, PLAN_FORMAT_VERSION)
+		return plan, false
+	}
+
+	for e in plan.upgrades {
+		if !utils.is_valid_package_name(e.name) ||
+		   !index.is_valid_url(e.repo_url) ||
+		   len(e.installed_version) == 0 ||
+		   len(e.new_version) == 0 {
+			errors.log_error("Invalid plan entry: %q", e.name)
+			return plan, false
+		}
+	}
+
+	return plan, true
+}
+
+// Install exactly the upgrades of a saved plan. Entries whose installed
+// version changed since the plan was made are skipped.
+update_apply_plan :: proc(path: string, config: ^Config) -> int {
+	plan, ok := update_read_plan(path)
+	if !ok {
+		return 1
+	}
+
+	arch, arch_ok := cfg.get_arch()
+	if !arch_ok {
+		errors.print_error(errors.make_error(.Arch_Detection_Failed))
+		return 1
+	}
+	if plan.arch != arch {
+		errors.log_error("Plan was made for %s, this system is %s", plan.arch, arch)
+		return 1
+	}
+
+	// A package pinned since the plan was made stays held back
+	pinned, pins_ok := pins.pins_load(context.temp_allocator)
+	if !pins_ok {
+		return 1
+	}
+
+	upgrades := make([dynamic]Upgrade_Info, context.temp_allocator)
+	for e in plan.upgrades {
+		if slice.contains(pinned, e.name) {
+			errors.log_warning("Skipping %s: pinned (vuru unpin %s to upgrade it)", e.name, e.name)
+			continue
+		}
+
+		installed, installed_ok := get_installed_version(e.name, context.temp_allocator)
+		switch {
+		case !installed_ok:
+			errors.log_warning("Skipping %s: no longer installed", e.name)
+			continue
+		case installed == e.new_version:
+			continue
+		case installed != e.installed_version:
+			errors.log_warning(
+				"Skipping %s: installed version is %s, plan expected %s",
+				e.name,
+				installed,
+				e.installed_version,
+			)
+			continue
+		case xbps.version_compare(e.new_version, installed) <= 0:
+			// collect_upgrades never plans these, but a plan file can be
+			// edited or written by hand: never "upgrade" to an older version
+			errors.log_warning(
+				"Skipping %s: planned version %s is not newer than installed %s",
+				e.name,
+				e.new_version,
+				installed,
+			)
+			continue
+		}
+
+		append(
+			&upgrades,
+			Upgrade_Info {
+				name = e.name,
+				installed_ver = e.installed_version,
+				new_ver = e.new_version,
+				repo_url = e.repo_url,
+				category = e.category,
+			},
+		)
+	}
+
+	if len(upgrades) == 0 {
+		errors.log_info("Nothing left to apply from %s (planned %s)", path, plan.created)
+		return 0
+	}
+
+	print_upgrade_summary(upgrades[:])
+	if config.dry_run {
+		return 0
+	}
+
+	if !confirm_insecure_repos(upgrade_repo_urls(upgrades[:]), config) {
+		errors.log_info("Upgrade cancelled")
+		return errors.EXIT_ABORTED
+	}
+
+	if !config.yes && !utils.prompt_yes_no("Apply this plan?", !config.default_no) {
+		errors.log_info("Upgrade cancelled by user")
+		return errors.EXIT_ABORTED
+	}
+
+	upgraded, err_count := perform_upgrades(
+		upgrades[:],
+		true,
+		exact_versions = true,
+		ignore_conf_repos = config.ignore_conf_repos,
+		download_first = config.download_first,
+	)
+	errors.log_info("Upgraded %d of %d planned package(s)", upgraded, len(upgrades))
+	if utils.command_interrupted() {
+		return errors.EXIT_ABORTED
+	}
+
+	if config.notify {
+		utils.desktop_notify(fmt.tprintf("vuru: %d package(s) updated from plan", upgraded), path)
+	}
+
+	return errors.EXIT_XBPS_FAILED if err_count > 0 else 0
+}
@@ -0,0 +1,37 @@
+package commands
+
+import "core:fmt"
+
+import index "../core/index"
+import utils "../utils"
+
+VERSION :: "0.6.0"
+
+// Version command - prints version, plus index details with -v
+version_run :: proc(args: []string, config: ^Config) -> int {
+	fmt.printf("vuru %s\n", VERSION)
+
+	if !config.verbose {
+		return 0
+	}
+
+	// key: value lines, easy to paste into bug reports or parse in scripts
+	fmt.printf("index_url: %s\n", config.index_url)
+
+	info, ok := index.index_cache_info(context.temp_allocator)
+	if !ok {
+		fmt.println("index_cache: unknown")
+		return 0
+	}
+
+	fmt.printf("index_cache: %s\n", info.path)
+	if !info.exists {
+		fmt.println("index_packages: 0")
+		fmt.println("index_synced: never")
+		return 0
+	}
+
+	fmt.printf("index_packages: %d\n", info.packages)
+	fmt.printf("index_synced: %s\n", utils.format_timestamp(info.synced_at, context.temp_allocator))
+	return 0
+}
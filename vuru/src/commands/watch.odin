@@ -0,0 +1,119 @@
+package commands
+
+import "core:c/libc"
+import "core:fmt"
+import "core:slice"
+import "core:strings"
+import "core:time"
+
+import audit "../core/audit"
+import errors "../core/errors"
+import index "../core/index"
+import utils "../utils"
+
+// Update watcher for tray indicators and the like, only built with `make WATCH=1`
+VURU_WATCH :: #config(VURU_WATCH, false)
+
+// Time between checks when `vuru watch` gets no interval
+WATCH_DEFAULT_INTERVAL :: "6h"
+
+// Watch command - check for VUP updates every interval and show a desktop
+// notification when new ones appear. Nothing is installed. Runs in the
+// foreground until SIGTERM or Ctrl-C.
+watch_run :: proc(args: []string, config: ^Config) -> int {
+	when !VURU_WATCH {
+		errors.log_error("This vuru was built without the watcher (rebuild with 'make WATCH=1')")
+		return 1
+	} else {
+		return watch_main(args, config)
+	}
+}
+
+when VURU_WATCH {
+	@(private)
+	watch_stop: bool
+
+	@(private)
+	watch_signal_handler :: proc "c" (sig: i32) {
+		watch_stop = true
+	}
+
+	@(private)
+	watch_main :: proc(args: []string, config: ^Config) -> int {
+		interval_arg := args[0] if len(args) > 0 else WATCH_DEFAULT_INTERVAL
+		interval, interval_ok := audit.parse_duration(interval_arg)
+		if !interval_ok || interval < 60 {
+			errors.log_error("Invalid interval: %s (expected at least a minute, e.g. 30m, 6h, 1d)", interval_arg)
+			return 1
+		}
+
+		errors.log_info("Checking for VUP updates every %s", interval_arg)
+
+		// In place of the global handler: a signal stops after the current round
+		libc.signal(libc.SIGTERM, watch_signal_handler)
+		libc.signal(libc.SIGINT, watch_signal_handler)
+
+		// Only notify when the set of pending updates changes
+		last_summary := ""
+		for !watch_stop {
+			summary, count, ok := watch_check(config)
+			if !ok {
+				errors.log_warning("Update check failed, retrying in %s", interval_arg)
+			} else if count == 0 {
+				errors.log_info("All VUP packages are up to date")
+			} else if summary != last_summary {
+				errors.log_info("%d update(s) available: %s", count, summary)
+				utils.desktop_notify(fmt.tprintf("vuru: %d update(s) available", count), summary)
+			}
+			if ok {
+				delete(last_summary)
+				last_summary = strings.clone(summary)
+			}
+
+			// The index and everything of this round live in the temp allocator
+			index.index_unload()
+			free_all(context.temp_allocator)
+
+			for _ in 0 ..< interval {
+				if watch_stop {
+					break
+				}
+				time.sleep(time.Second)
+			}
+		}
+
+		errors.log_info("Stopped watching")
+		return 0
+	}
+
+	// One check: refresh the index if the remote one changed, then list the
+	// pending upgrades as "name version, ...". Allocates in the temp allocator.
+	@(private)
+	watch_check :: proc(config: ^Config) -> (summary: string, count: int, ok: bool) {
+		context.allocator = context.temp_allocator
+
+		available, check_ok := index.index_check_for_update(config.index_url)
+		if !check_ok {
+			errors.log_warning("Failed to check for index updates, using the cached index")
+		}
+
+		idx, idx_ok := index.index_load_or_fetch(config.index_url, check_ok && available)
+		if !idx_ok {
+			errors.log_error("Failed to load package index")
+			return "", 0, false
+		}
+
+		upgrades, upgrades_ok := collect_upgrades(&idx)
+		if !upgrades_ok {
+			return "", 0, false
+		}
+
+		entries := make([]string, len(upgrades))
+		for u, i in upgrades {
+			entries[i] = fmt.tprintf("%s %s", u.name, u.new_ver)
+		}
+		slice.sort(entries)
+
+		return strings.join(entries, ", "), len(entries), true
+	}
+}
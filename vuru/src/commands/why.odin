@@ -0,0 +1,79 @@
+package commands
+
+import "core:fmt"
+import "core:strings"
+
+import errors "../core/errors"
+import xbps "../core/xbps"
+import utils "../utils"
+
+// Why command - explain why each argument is installed: explicitly or as a
+// dependency, and which installed packages still depend on it
+why_run :: proc(args: []string, config: ^Config) -> int {
+	if len(args) == 0 {
+		fmt.println("Usage: vuru why <package> [packages...]")
+		return 1
+	}
+
+	for pkg_name, i in args {
+		if !utils.is_valid_package_name(pkg_name) {
+			errors.log_error("Invalid package name: %s", pkg_name)
+			return 1
+		}
+
+		version, installed := xbps.get_installed_version(
+			pkg_name,
+			utils.run_command_output,
+			context.temp_allocator,
+		)
+		if !installed {
+			errors.log_error("%s is not installed", pkg_name)
+			return errors.EXIT_NOT_FOUND
+		}
+
+		automatic, auto_ok := xbps.is_automatic_install(pkg_name, utils.run_command_output)
+		revdeps, revdeps_ok := xbps.get_reverse_deps(
+			pkg_name,
+			utils.run_command_output,
+			context.temp_allocator,
+		)
+		if !auto_ok || !revdeps_ok {
+			errors.log_error("Failed to query %s", pkg_name)
+			return errors.EXIT_XBPS_FAILED
+		}
+
+		if config.porcelain {
+			fmt.printf(
+				"%s\t%s\t%s\t%s\n",
+				pkg_name,
+				version,
+				"auto" if automatic else "manual",
+				strings.join(revdeps, ",", context.temp_allocator),
+			)
+			continue
+		}
+
+		if i > 0 {fmt.println()}
+		if automatic {
+			fmt.printf("%s-%s was installed as a dependency\n", pkg_name, version)
+		} else {
+			fmt.printf("%s-%s was installed explicitly\n", pkg_name, version)
+		}
+
+		if len(revdeps) == 0 {
+			if automatic {
+				fmt.println("Nothing depends on it anymore (orphan, see 'vuru remove -o')")
+			} else {
+				fmt.println("No installed package depends on it")
+			}
+			continue
+		}
+
+		fmt.printf("Required by %d package(s):\n", len(revdeps))
+		for r in revdeps {
+			fmt.printf("  %s\n", r)
+		}
+	}
+
+	return 0
+}
@@ -0,0 +1,181 @@
+package audit
+
+import "core:fmt"
+import "core:os"
+import "core:strconv"
+import "core:strings"
+import "core:time"
+
+import "../../utils"
+import config "../config"
+import errors "../errors"
+
+// Audit log of what vuru changed on the system: ~/.local/state/vup/history.log
+// (older versions kept it in ~/.cache/vup and it is moved on first use),
+// one tab-separated line per package and operation:
+//   <unix time> <action> <package> <version> <result>
+// Read back by `vuru history`.
+
+// Once the log grows past AUDIT_TRIM_BYTES it is cut back to the newest
+// AUDIT_MAX_LINES lines, so most records are a plain append
+AUDIT_MAX_LINES :: 5000
+AUDIT_TRIM_BYTES :: 1024 * 1024
+
+Audit_Entry :: struct {
+	timestamp: i64, // Unix seconds
+	action:    string, // install, build, remove, upgrade
+	pkg:       string,
+	version:   string, // Empty when unknown (e.g. removals)
+	result:    string, // ok, failed
+}
+
+// Path of the audit log
+audit_log_path :: proc(allocator := context.allocator) -> (string, bool) {
+	state_dir, ok := config.get_state_dir(context.temp_allocator)
+	if !ok {
+		return "", false
+	}
+	path := utils.path_join(state_dir, "history.log", allocator = allocator)
+	migrate_legacy_log(state_dir, path)
+	return path, true
+}
+
+// Move a history log from the cache directory, where older versions kept it
+@(private)
+migrate_legacy_log :: proc(state_dir: string, path: string) {
+	cache_dir, ok := config.get_cache_dir(context.temp_allocator)
+	if !ok {
+		return
+	}
+	legacy := utils.path_join(cache_dir, "history.log", allocator = context.temp_allocator)
+	if !os.exists(legacy) || os.exists(path) || !utils.mkdir_p(state_dir) {
+		return
+	}
+
+	// rename fails across file systems, copy then
+	if os.rename(legacy, path) == os.ERROR_NONE {
+		return
+	}
+	if content, read_ok := utils.read_file(legacy, context.temp_allocator); read_ok && utils.write_file(path, content) {
+		os.remove(legacy)
+	} else {
+		errors.log_warning("Failed to move history log %s to %s", legacy, path)
+	}
+}
+
+// Append an operation to the audit log. Failing to log never fails the operation.
+audit_record :: proc(action: string, pkg: string, version: string, ok: bool) {
+	path, path_ok := audit_log_path(context.temp_allocator)
+	if !path_ok {
+		return
+	}
+
+	line := fmt.tprintf(
+		"%d\t%s\t%s\t%s\t%s\n",
+		time.to_unix_seconds(time.now()),
+		action,
+		pkg,
+		version,
+		"ok" if ok else "failed",
+	)
+
+	if slash := strings.last_index_byte(path, '/'); slash > 0 {
+		utils.mkdir_p(path[:slash])
+	}
+	if !utils.append_file(path, line) {
+		errors.log_warning("Failed to write history log %s", path)
+		return
+	}
+
+	if fi, err := os.stat(path, context.temp_allocator); err == nil && fi.size > AUDIT_TRIM_BYTES {
+		audit_trim(path)
+	}
+}
+
+// Keep the newest AUDIT_MAX_LINES lines. The rewrite goes through a temp file
+// and a rename; a record appended by another vuru run in between is lost,
+// which is why this only happens once the log has grown well past the limit.
+@(private)
+audit_trim :: proc(path: string) {
+	content, ok := utils.read_file(path, context.temp_allocator)
+	if !ok {
+		return
+	}
+
+	lines := strings.split_lines(strings.trim_right(content, "\n"), context.temp_allocator)
+	if len(lines) <= AUDIT_MAX_LINES {
+		return
+	}
+
+	kept := strings.join(lines[len(lines) - AUDIT_MAX_LINES:], "\n", context.temp_allocator)
+	if !utils.write_file_atomic(path, strings.concatenate({kept, "\n"}, context.temp_allocator)) {
+		errors.log_warning("Failed to trim history log %s", path)
+	}
+}
+
+// Read the audit log, oldest entry first. Malformed lines are skipped.
+// A missing log is an empty history.
+audit_read :: proc(allocator := context.allocator) -> ([]Audit_Entry, bool) {
+	path, path_ok := audit_log_path(context.temp_allocator)
+	if !path_ok {
+		return nil, false
+	}
+
+	entries := make([dynamic]Audit_Entry, allocator)
+
+	content, ok := utils.read_file(path, context.temp_allocator)
+	if !ok {
+		return entries[:], true
+	}
+
+	for line in strings.split_lines_iterator(&content) {
+		fields := strings.split(line, "\t", context.temp_allocator)
+		if len(fields) != 5 {
+			continue
+		}
+
+		ts, ts_ok := strconv.parse_i64(fields[0])
+		if !ts_ok {
+			continue
+		}
+
+		append(
+			&entries,
+			Audit_Entry {
+				timestamp = ts,
+				action = strings.clone(fields[1], allocator),
+				pkg = strings.clone(fields[2], allocator),
+				version = strings.clone(fields[3], allocator),
+				result = strings.clone(fields[4], allocator),
+			},
+		)
+	}
+
+	return entries[:], true
+}
+
+// Parse a duration like "30m", "12h", "7d" or "2w" into seconds
+parse_duration :: proc(s: string) -> (seconds: i64, ok: bool) {
+	if len(s) < 2 {
+		return 0, false
+	}
+
+	n, n_ok := strconv.parse_i64(s[:len(s) - 1])
+	if !n_ok || n < 0 {
+		return 0, false
+	}
+
+	switch s[len(s) - 1] {
+	case 's':
+		return n, true
+	case 'm':
+		return n * 60, true
+	case 'h':
+		return n * 3600, true
+	case 'd':
+		return n * 86400, true
+	case 'w':
+		return n * 7 * 86400, true
+	}
+	return 0, false
+}
@@ -90,7 +90,7 @@ xbps_src_bootstrap :: proc(cfg: ^Build_Config) -> bool {
 
 // Build a package using xbps-src
 build_package :: proc(cfg: ^Build_Config, pkg_name: string, category: string) -> bool {
-	if !utils.is_valid_identifier(pkg_name) || !utils.is_valid_identifier(category) {
+	if !utils.is_valid_package_name(pkg_name) || !utils.is_valid_identifier(category) {
 		errors.log_error("Invalid package name or category")
 		return false
 	}
@@ -4,8 +4,49 @@ import "core:os"
 import "core:strings"
 import "core:sys/linux"
 
-// Get the current system architecture name
+// Architectures Void Linux publishes packages for
+XBPS_ARCHS :: []string {
+	"x86_64",
+	"x86_64-musl",
+	"i686",
+	"aarch64",
+	"aarch64-musl",
+	"armv7l",
+	"armv7l-musl",
+	"armv6l",
+	"armv6l-musl",
+}
+
+// Check an architecture name against XBPS_ARCHS
+is_known_arch :: proc(arch: string) -> bool {
+	for a in XBPS_ARCHS {
+		if a == arch {
+			return true
+		}
+	}
+	return false
+}
+
+// Target architecture from --arch, see set_target_arch
+@(private)
+target_arch: string
+
+// Make get_arch() report arch (--arch). It is not exported to the
+// environment, where curl, git, the hooks and xbps-src would inherit it.
+set_target_arch :: proc(arch: string) {
+	target_arch = arch
+}
+
+// Get the current system architecture name. --arch takes precedence, then
+// XBPS_TARGET_ARCH from the environment, as it does for xbps.
 get_arch :: proc() -> (string, bool) {
+	if len(target_arch) > 0 {
+		return strings.clone(target_arch), true
+	}
+	if target := os.get_env("XBPS_TARGET_ARCH", context.temp_allocator); is_known_arch(target) {
+		return strings.clone(target), true
+	}
+
 	uts: linux.UTS_Name
 	if linux.uname(&uts) != nil {
 		return "", false
@@ -47,6 +88,37 @@ get_cache_dir :: proc(allocator := context.allocator) -> (string, bool) {
 	return strings.concatenate({home, "/.cache/vup"}, allocator), true
 }
 
+// Get config directory path ($XDG_CONFIG_HOME/vup or ~/.config/vup)
+get_config_dir :: proc(allocator := context.allocator) -> (string, bool) {
+	xdg_config := os.get_env("XDG_CONFIG_HOME", context.temp_allocator)
+	if len(xdg_config) > 0 && xdg_config[0] == '/' {
+		return strings.concatenate({xdg_config, "/vup"}, allocator), true
+	}
+
+	home := os.get_env("HOME", context.temp_allocator)
+	if len(home) == 0 || home[0] != '/' {
+		return "", false
+	}
+
+	return strings.concatenate({home, "/.config/vup"}, allocator), true
+}
+
+// Get state directory path ($XDG_STATE_HOME/vup or ~/.local/state/vup), for
+// data vuru writes that should survive clearing the cache (history)
+get_state_dir :: proc(allocator := context.allocator) -> (string, bool) {
+	xdg_state := os.get_env("XDG_STATE_HOME", context.temp_allocator)
+	if len(xdg_state) > 0 && xdg_state[0] == '/' {
+		return strings.concatenate({xdg_state, "/vup"}, allocator), true
+	}
+
+	home := os.get_env("HOME", context.temp_allocator)
+	if len(home) == 0 || home[0] != '/' {
+		return "", false
+	}
+
+	return strings.concatenate({home, "/.local/state/vup"}, allocator), true
+}
+
 // Get temporary directory path
 get_tmpdir :: proc() -> string {
 	tmpdir := os.get_env("TMPDIR", context.temp_allocator)
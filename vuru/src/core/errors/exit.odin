@@ -0,0 +1,33 @@
+package errors
+
+// Process exit codes. Scripts branch on these, so keep them stable
+// (documented in the README under "Exit codes").
+EXIT_SUCCESS :: 0
+EXIT_FAILURE :: 1 // Usage errors and anything not listed below
+EXIT_NOT_FOUND :: 2 // Package not found
+EXIT_XBPS_FAILED :: 3 // xbps-install/xbps-remove/xbps-src failed
+EXIT_INDEX_FAILED :: 4 // Network or package index failure
+EXIT_ABORTED :: 5 // Declined at a prompt, vetoed by a hook or interrupted with Ctrl-C
+
+// Exit code for an error kind
+exit_code :: proc(kind: Error_Kind) -> int {
+	#partial switch kind {
+	case .Package_Not_Found,
+	     .Package_Not_In_VUP,
+	     .Package_Not_In_Repos,
+	     .Package_Arch_Unavailable,
+	     .Package_Not_Installed,
+	     .Dependency_Not_Found,
+	     .Template_Not_Found:
+		return EXIT_NOT_FOUND
+	case .Build_Failed, .Command_Failed:
+		return EXIT_XBPS_FAILED
+	case .Index_Fetch_Failed,
+	     .Index_Parse_Failed,
+	     .Index_Cache_Failed,
+	     .Network_Unavailable,
+	     .Download_Failed:
+		return EXIT_INDEX_FAILED
+	}
+	return EXIT_FAILURE
+}
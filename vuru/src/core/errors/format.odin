@@ -2,25 +2,81 @@ package errors
 
 import "core:fmt"
 
-// ANSI color codes (centralized here for all of vuru)
-COLOR_RESET :: "\033[0m"
-COLOR_BOLD :: "\033[1m"
-COLOR_RED :: "\033[31m"
-COLOR_GREEN :: "\033[32m"
-COLOR_YELLOW :: "\033[33m"
-COLOR_BLUE :: "\033[34m"
-COLOR_MAGENTA :: "\033[35m"
-COLOR_CYAN :: "\033[36m"
-COLOR_WHITE :: "\033[37m"
+// ANSI color codes (centralized here for all of vuru). Variables so that
+// set_colors can blank them all for --color never.
+COLOR_RESET := "\033[0m"
+COLOR_BOLD := "\033[1m"
+COLOR_RED := "\033[31m"
+COLOR_GREEN := "\033[32m"
+COLOR_YELLOW := "\033[33m"
+COLOR_BLUE := "\033[34m"
+COLOR_MAGENTA := "\033[35m"
+COLOR_CYAN := "\033[36m"
+COLOR_WHITE := "\033[37m"
 
 // Detailed codes
-COLOR_ERROR_BG :: "\033[41;37;1m" // White on Red Background for heavy errors? Maybe too much.
+COLOR_ERROR_BG := "\033[41;37;1m" // White on Red Background for heavy errors? Maybe too much.
 // Let's stick to text colors but use BOLD for emphasis.
-COLOR_ERROR :: "\033[1;31m" // Bold Red
-COLOR_WARNING :: "\033[1;33m" // Bold Yellow
-COLOR_SUCCESS :: "\033[1;32m" // Bold Green
-COLOR_INFO :: "\033[1;36m" // Bold Cyan
-COLOR_DIM :: "\033[2m"
+COLOR_ERROR := "\033[1;31m" // Bold Red
+COLOR_WARNING := "\033[1;33m" // Bold Yellow
+COLOR_SUCCESS := "\033[1;32m" // Bold Green
+COLOR_INFO := "\033[1;36m" // Bold Cyan
+COLOR_DIM := "\033[2m"
+
+// --color <when>, resolved once at startup (see set_colors)
+Color_Mode :: enum {
+	Auto, // Color when stdout is a terminal and NO_COLOR is unset
+	Always, // Even when piped, e.g. into less -R
+	Never,
+}
+
+// Parse a --color value: auto, always or never
+parse_color_mode :: proc(s: string) -> (Color_Mode, bool) {
+	switch s {
+	case "auto":
+		return .Auto, true
+	case "always":
+		return .Always, true
+	case "never":
+		return .Never, true
+	}
+	return .Auto, false
+}
+
+@(private)
+colors_on := true
+
+// Turn colored output on or off for the rest of the run
+set_colors :: proc(enabled: bool) {
+	colors_on = enabled
+	if enabled {
+		return
+	}
+	for c in ([]^string {
+			&COLOR_RESET,
+			&COLOR_BOLD,
+			&COLOR_RED,
+			&COLOR_GREEN,
+			&COLOR_YELLOW,
+			&COLOR_BLUE,
+			&COLOR_MAGENTA,
+			&COLOR_CYAN,
+			&COLOR_WHITE,
+			&COLOR_ERROR_BG,
+			&COLOR_ERROR,
+			&COLOR_WARNING,
+			&COLOR_SUCCESS,
+			&COLOR_INFO,
+			&COLOR_DIM,
+		}) {
+		c^ = ""
+	}
+}
+
+// Whether output is colored, also for tools vuru runs (diff)
+colors_enabled :: proc() -> bool {
+	return colors_on
+}
 
 // Print error with full formatting
 print_error :: proc(err: Error) {
@@ -102,8 +158,14 @@ log_error :: proc(format: string, args: ..any) {
 	fmt.eprintln()
 }
 
+// Set by -q/--quiet: info and success messages are dropped
+quiet: bool
+
 // Simple info logging
 log_info :: proc(format: string, args: ..any) {
+	if quiet {
+		return
+	}
 	fmt.eprintf("%s[INFO]%s  ", COLOR_INFO, COLOR_RESET)
 	fmt.eprintf(format, ..args)
 	fmt.eprintln()
@@ -111,6 +173,9 @@ log_info :: proc(format: string, args: ..any) {
 
 // Success logging (New!)
 log_success :: proc(format: string, args: ..any) {
+	if quiet {
+		return
+	}
 	fmt.eprintf("%s[OK]%s    ", COLOR_SUCCESS, COLOR_RESET)
 	fmt.eprintf(format, ..args)
 	fmt.eprintln()
@@ -0,0 +1,119 @@
+package hooks
+
+import "core:fmt"
+import "core:os"
+import "core:slice"
+import "core:strings"
+
+import "../../utils"
+import config "../config"
+import errors "../errors"
+
+// User hooks: executables in ~/.config/vup/hooks/<stage>.d/, run in file name
+// order with "<pkg> <version>" as arguments. The environment of the hook (and
+// only the hook, vuru's own stays as it is) additionally has VURU_HOOK
+// (stage), VURU_PKG, VURU_VERSION, VURU_SOURCE (vup, official or build) and
+// VURU_ROOTDIR (empty for /). Hooks may be symlinks to the executables.
+
+Hook_Stage :: enum {
+	Pre_Install,
+	Post_Install,
+}
+
+// Package a hook is run for
+Hook_Target :: struct {
+	name:    string,
+	version: string,
+	source:  string, // "vup", "official" or "build"
+}
+
+// Stage name as used for the directory and VURU_HOOK
+hook_stage_name :: proc(stage: Hook_Stage) -> string {
+	switch stage {
+	case .Pre_Install:
+		return "pre-install"
+	case .Post_Install:
+		return "post-install"
+	}
+	return ""
+}
+
+// List executable hooks for a stage, sorted by file name
+hooks_list :: proc(stage: Hook_Stage, allocator := context.allocator) -> []string {
+	config_dir, ok := config.get_config_dir(context.temp_allocator)
+	if !ok {
+		return nil
+	}
+
+	dir_path := utils.path_join(
+		config_dir,
+		"hooks",
+		strings.concatenate({hook_stage_name(stage), ".d"}, context.temp_allocator),
+		allocator = context.temp_allocator,
+	)
+	if !os.exists(dir_path) {
+		return nil
+	}
+
+	d, err := os.open(dir_path)
+	if err != os.ERROR_NONE {
+		errors.log_warning("Cannot read hook directory: %s", dir_path)
+		return nil
+	}
+	defer os.close(d)
+
+	file_infos, _ := os.read_dir(d, -1, context.temp_allocator)
+
+	result := make([dynamic]string, allocator)
+	for fi in file_infos {
+		if strings.has_prefix(fi.name, ".") {
+			continue
+		}
+
+		// stat follows symlinks, the usual way hook directories are filled
+		path := utils.path_join(dir_path, fi.name, allocator = context.temp_allocator)
+		target, stat_err := os.stat(path, context.temp_allocator)
+		if stat_err != nil || target.type != .Regular || !utils.is_executable(path) {
+			continue
+		}
+		append(&result, strings.clone(path, allocator))
+	}
+
+	slice.sort(result[:])
+	return result[:]
+}
+
+// Run all hooks of a stage for each target. Stops at the first failing hook
+// and returns false, so a failing pre-install hook can abort the install.
+hooks_run :: proc(stage: Hook_Stage, targets: []Hook_Target, rootdir: string) -> bool {
+	hook_paths := hooks_list(stage, context.temp_allocator)
+	if len(hook_paths) == 0 {
+		return true
+	}
+
+	stage_name := hook_stage_name(stage)
+
+	for target in targets {
+		// Through env(1), so the variables reach the hook and nothing else
+		env := []string {
+			"env",
+			fmt.tprintf("VURU_HOOK=%s", stage_name),
+			fmt.tprintf("VURU_PKG=%s", target.name),
+			fmt.tprintf("VURU_VERSION=%s", target.version),
+			fmt.tprintf("VURU_SOURCE=%s", target.source),
+			fmt.tprintf("VURU_ROOTDIR=%s", rootdir),
+		}
+
+		for hook in hook_paths {
+			args := make([dynamic]string, 0, len(env) + 3, context.temp_allocator)
+			append(&args, ..env)
+			append(&args, hook, target.name, target.version)
+			if utils.run_command(args[:]) != 0 {
+				errors.log_error("%s hook %s failed for %s", stage_name, hook, target.name)
+				return false
+			}
+		}
+	}
+
+	return true
+}
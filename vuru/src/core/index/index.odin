@@ -1,9 +1,12 @@
 package index
 
+import "core:encoding/cbor"
 import "core:encoding/json"
 import "core:fmt"
 import "core:os"
+import "core:slice"
 import "core:strings"
+import "core:time"
 
 import "../../utils"
 import config "../config"
@@ -49,9 +52,14 @@ parse_index :: proc(content: string, allocator := context.allocator) -> (Index,
 		return {}, false
 	}
 
-	// Get the "packages" object from root
+	// Get the "packages" object from root. `{}` is an empty index, which
+	// callers warn about (see accept_fetched_index); any other object without
+	// "packages" is not an index at all (a wrong URL, an API error body).
 	packages_val, has_packages := root["packages"]
 	if !has_packages {
+		if len(root) == 0 {
+			return idx, true
+		}
 		errors.log_error("Index missing 'packages' field")
 		index_free(&idx)
 		return {}, false
@@ -94,6 +102,40 @@ parse_index :: proc(content: string, allocator := context.allocator) -> (Index,
 			}
 		}
 
+		// Parse source_pkg (optional, set for subpackages)
+		if v, has := pkg_obj["source_pkg"]; has {
+			if s, is_str := v.(json.String); is_str {
+				pkg.source_pkg = strings.clone(s, allocator)
+			}
+		}
+
+		// Parse updated_at (optional, not every index carries dates)
+		if v, has := pkg_obj["updated_at"]; has {
+			if s, is_str := v.(json.String); is_str {
+				pkg.updated_at = strings.clone(s, allocator)
+			}
+		}
+
+		// Parse license (optional)
+		if v, has := pkg_obj["license"]; has {
+			if s, is_str := v.(json.String); is_str {
+				pkg.license = strings.clone(s, allocator)
+			}
+		}
+
+		// Parse suggests (optional, older indexes don't have it)
+		if v, has := pkg_obj["suggests"]; has {
+			if arr, is_arr := v.(json.Array); is_arr {
+				suggests := make([dynamic]string, 0, len(arr), allocator)
+				for item in arr {
+					if s, is_str := item.(json.String); is_str {
+						append(&suggests, strings.clone(s, allocator))
+					}
+				}
+				pkg.suggests = suggests[:]
+			}
+		}
+
 		// Parse repo_urls map
 		if v, has := pkg_obj["repo_urls"]; has {
 			if urls_obj, is_urls_obj := v.(json.Object); is_urls_obj {
@@ -117,77 +159,437 @@ parse_index :: proc(content: string, allocator := context.allocator) -> (Index,
 
 // Load index from file
 load_index_from_file :: proc(path: string, allocator := context.allocator) -> (Index, bool) {
+	bin_path := binary_cache_path(path)
+	if idx, ok := load_index_binary(path, bin_path, allocator); ok {
+		return idx, true
+	}
+
 	content, ok := utils.read_file(path, context.temp_allocator)
 	if !ok {
 		return {}, false
 	}
 
-	return parse_index(content, allocator)
+	idx, parse_ok := parse_index(content, allocator)
+	if parse_ok {
+		write_index_binary(bin_path, &idx)
+	}
+	return idx, parse_ok
+}
+
+// Parsing the JSON index is the slow part of every load, so the parsed
+// packages are also kept as CBOR next to it (<index>.bin). Bump the schema
+// whenever Package_Info changes so older sidecars are ignored.
+INDEX_BINARY_SCHEMA :: 3
+
+@(private)
+Index_Binary :: struct {
+	schema:   int,
+	packages: map[string]Package_Info,
+}
+
+@(private)
+Index_Binary_Header :: struct {
+	schema: int,
+}
+
+@(private)
+binary_cache_path :: proc(index_path: string) -> string {
+	return fmt.tprintf("%s.bin", index_path)
+}
+
+// Load the binary sidecar if it is at least as new as the JSON index and
+// has the current schema
+@(private)
+load_index_binary :: proc(
+	json_path: string,
+	bin_path: string,
+	allocator := context.allocator,
+) -> (
+	Index,
+	bool,
+) {
+	json_fi, json_err := os.stat(json_path, context.temp_allocator)
+	bin_fi, bin_err := os.stat(bin_path, context.temp_allocator)
+	if json_err != nil || bin_err != nil {
+		return {}, false
+	}
+	if time.diff(json_fi.modification_time, bin_fi.modification_time) < 0 {
+		return {}, false
+	}
+
+	content, ok := utils.read_file(bin_path, context.temp_allocator)
+	if !ok {
+		return {}, false
+	}
+
+	// Check the schema before decoding packages with a possibly different layout
+	header: Index_Binary_Header
+	if cbor.unmarshal(content, &header, allocator = context.temp_allocator) != nil ||
+	   header.schema != INDEX_BINARY_SCHEMA {
+		return {}, false
+	}
+
+	cached: Index_Binary
+	if cbor.unmarshal(content, &cached, allocator = allocator) != nil {
+		return {}, false
+	}
+
+	return Index{packages = cached.packages, allocator = allocator}, true
+}
+
+// Write the binary sidecar. Failing only costs the speedup on the next load.
+@(private)
+write_index_binary :: proc(bin_path: string, idx: ^Index) {
+	data, err := cbor.marshal(
+		Index_Binary{schema = INDEX_BINARY_SCHEMA, packages = idx.packages},
+		allocator = context.temp_allocator,
+	)
+	if err != nil {
+		return
+	}
+	utils.write_file_atomic(bin_path, string(data))
 }
 
 // Get cache paths for index files
 @(private)
 Cache_Paths :: struct {
-	dir:   string,
-	index: string,
-	etag:  string,
-	temp:  string,
+	dir:        string,
+	index:      string,
+	validators: string, // Sidecar holding ETag / Last-Modified of the cached index
+	headers:    string, // Response headers of the last fetch (temporary)
+	temp:       string,
 }
 
+// Paths of the main index (repo_name empty) or of an overlay repo's index
 @(private)
-get_cache_paths :: proc() -> (Cache_Paths, bool) {
+get_cache_paths :: proc(repo_name := "") -> (Cache_Paths, bool) {
 	cache_dir, ok := config.get_cache_dir(context.temp_allocator)
 	if !ok {
 		return {}, false
 	}
 
+	base := "index.json"
+	if len(repo_name) > 0 {
+		base = fmt.tprintf("repo-%s.json", repo_name)
+	}
+
 	return Cache_Paths {
 			dir = cache_dir,
-			index = utils.path_join(cache_dir, "index.json", allocator = context.temp_allocator),
-			etag = utils.path_join(
+			index = utils.path_join(cache_dir, base, allocator = context.temp_allocator),
+			validators = utils.path_join(
+				cache_dir,
+				fmt.tprintf("%s.etag", base),
+				allocator = context.temp_allocator,
+			),
+			headers = utils.path_join(
 				cache_dir,
-				"index.json.etag",
+				fmt.tprintf("%s.headers", base),
 				allocator = context.temp_allocator,
 			),
 			temp = utils.path_join(
 				cache_dir,
-				"index.json.tmp",
+				fmt.tprintf("%s.tmp", base),
 				allocator = context.temp_allocator,
 			),
 		},
 		true
 }
 
+// Cache validators for conditional index requests
+@(private)
+Validators :: struct {
+	etag:          string,
+	last_modified: string,
+}
+
+// Read validators from the sidecar file ("etag: ..." / "last-modified: ..." lines).
+// A bare line is accepted as an ETag, which is what older versions wrote.
+@(private)
+read_validators :: proc(path: string) -> Validators {
+	content, ok := utils.read_file(path, context.temp_allocator)
+	if !ok {
+		return {}
+	}
+
+	v: Validators
+	it := content
+	for line in strings.split_lines_iterator(&it) {
+		l := strings.trim_space(line)
+		if len(l) == 0 {
+			continue
+		}
+
+		if strings.has_prefix(l, "etag:") {
+			v.etag = strings.trim_space(l[len("etag:"):])
+		} else if strings.has_prefix(l, "last-modified:") {
+			v.last_modified = strings.trim_space(l[len("last-modified:"):])
+		} else if len(v.etag) == 0 {
+			v.etag = l
+		}
+	}
+	return v
+}
+
+// Write validators to the sidecar file, removing it when there are none
+@(private)
+write_validators :: proc(path: string, v: Validators) {
+	tx: utils.File_Transaction
+	stage_validators(&tx, path, v)
+	if !utils.file_tx_commit(&tx) {
+		errors.log_warning("Failed to save index validators")
+	}
+}
+
+// Stage the validators sidecar (or its removal) in a file transaction
+@(private)
+stage_validators :: proc(tx: ^utils.File_Transaction, path: string, v: Validators) {
+	if len(v.etag) == 0 && len(v.last_modified) == 0 {
+		utils.file_tx_remove(tx, path)
+		return
+	}
+
+	b := strings.builder_make(context.temp_allocator)
+	if len(v.etag) > 0 {
+		fmt.sbprintf(&b, "etag: %s\n", v.etag)
+	}
+	if len(v.last_modified) > 0 {
+		fmt.sbprintf(&b, "last-modified: %s\n", v.last_modified)
+	}
+	utils.file_tx_write(tx, path, strings.to_string(b))
+}
+
+// Extract validators from a curl header dump. With -L every redirect hop
+// adds a header block, so only the last block counts.
+@(private)
+parse_response_validators :: proc(headers: string) -> Validators {
+	v: Validators
+	it := headers
+	for line in strings.split_lines_iterator(&it) {
+		l := strings.trim_space(line)
+
+		if strings.has_prefix(l, "HTTP/") {
+			v = {}
+			continue
+		}
+
+		colon := strings.index_byte(l, ':')
+		if colon <= 0 {
+			continue
+		}
+
+		name := strings.to_lower(l[:colon], context.temp_allocator)
+		value := strings.trim_space(l[colon + 1:])
+
+		switch name {
+		case "etag":
+			v.etag = value
+		case "last-modified":
+			v.last_modified = value
+		}
+	}
+	return v
+}
+
+// Remove the cached files of the main index (repo_name empty) or of an
+// overlay repo, so the next load does a clean, unconditional fetch
+index_cache_remove :: proc(repo_name := "") {
+	paths, ok := get_cache_paths(repo_name)
+	if !ok {
+		return
+	}
+	os.remove(paths.index)
+	os.remove(binary_cache_path(paths.index))
+	os.remove(paths.validators)
+	os.remove(paths.headers)
+	os.remove(paths.temp)
+	remove_shard_cache(paths.index)
+}
+
+// Bytes taken by the cached files of the main index and every overlay repo
+index_cache_usage :: proc() -> i64 {
+	total := index_cache_size()
+
+	repos, _ := repos_load(context.temp_allocator)
+	for repo in repos {
+		total += index_cache_size(repo.name)
+	}
+	return total
+}
+
+// Bytes taken by the cached files of one index (see index_cache_remove)
+@(private)
+index_cache_size :: proc(repo_name := "") -> i64 {
+	paths, ok := get_cache_paths(repo_name)
+	if !ok {
+		return 0
+	}
+
+	total: i64 = 0
+	files := []string {
+		paths.index,
+		binary_cache_path(paths.index),
+		paths.validators,
+		paths.headers,
+		paths.temp,
+	}
+	for path in files {
+		if fi, err := os.stat(path, context.temp_allocator); err == nil {
+			total += fi.size
+		}
+	}
+	return total + shard_cache_size(paths.index)
+}
+
+// Remove the cached files of the main index and every overlay repo (--force-cache-rebuild)
+index_cache_remove_all :: proc() {
+	index_cache_remove()
+
+	repos, _ := repos_load(context.temp_allocator)
+	for repo in repos {
+		index_cache_remove(repo.name)
+	}
+
+	loaded_index = {}
+}
+
+// Package names, one per line, read by the shell completion scripts so that
+// completing never has to run vuru or load the index
+completion_names_path :: proc(allocator := context.allocator) -> (string, bool) {
+	cache_dir, ok := config.get_cache_dir(context.temp_allocator)
+	if !ok {
+		return "", false
+	}
+	return utils.path_join(cache_dir, "package-names", allocator = allocator), true
+}
+
+// Write the names of all packages in idx to the completion names file
+write_completion_names :: proc(idx: ^Index) -> bool {
+	path, ok := completion_names_path(context.temp_allocator)
+	if !ok || !utils.mkdir_p(path[:strings.last_index_byte(path, '/')]) {
+		return false
+	}
+
+	names := make([dynamic]string, 0, len(idx.packages), context.temp_allocator)
+	for name in idx.packages {
+		append(&names, name)
+	}
+	slice.sort(names[:])
+
+	b := strings.builder_make(context.temp_allocator)
+	for name in names {
+		strings.write_string(&b, name)
+		strings.write_byte(&b, '\n')
+	}
+	return utils.write_file_atomic(path, strings.to_string(b))
+}
+
+// Cache paths under TMPDIR, used when the cache directory can't be written.
+// The names are random and the two files curl writes (headers, download) are
+// created exclusively up front: curl follows symlinks, so in a shared /tmp a
+// predictable name would let someone else decide where the download goes.
+@(private)
+temp_cache_paths :: proc(repo_name := "") -> (Cache_Paths, bool) {
+	for _ in 0 ..< 8 {
+		prefix := fmt.tprintf("%s/vuru-index-%s", config.get_tmpdir(), utils.rand_string(8, context.temp_allocator))
+		if len(repo_name) > 0 {
+			prefix = fmt.tprintf("%s-%s", prefix, repo_name)
+		}
+
+		paths := Cache_Paths {
+			dir = config.get_tmpdir(),
+			index = fmt.tprintf("%s.json", prefix),
+			validators = fmt.tprintf("%s.json.etag", prefix),
+			headers = fmt.tprintf("%s.json.headers", prefix),
+			temp = fmt.tprintf("%s.json.tmp", prefix),
+		}
+		if !utils.write_file_exclusive(paths.temp, "") {
+			continue
+		}
+		if !utils.write_file_exclusive(paths.headers, "") {
+			os.remove(paths.temp)
+			continue
+		}
+		return paths, true
+	}
+
+	errors.log_error("Failed to create temporary index files in %s", config.get_tmpdir())
+	return {}, false
+}
+
 // Fetch index from URL, returns HTTP status code
 @(private)
 fetch_index_from_url :: proc(
 	url: string,
 	paths: Cache_Paths,
-	old_etag: string,
+	old: Validators,
 ) -> (
 	status: string,
 	ok: bool,
 ) {
+	limit_args, limit_ok := utils.download_curl_args(url, context.temp_allocator)
+	if !limit_ok {
+		return "", false
+	}
+
 	curl_args := make([dynamic]string, context.temp_allocator)
 
-	append(&curl_args, "curl", "-s", "-L", "-w", "%{http_code}")
+	append(&curl_args, "curl", "-s", "-L", "-w", "%{http_code}", "-D", paths.headers)
+	append(&curl_args, ..limit_args)
 
-	// Use conditional request if we have an etag
-	if len(old_etag) > 0 {
-		append(&curl_args, "-H", fmt.tprintf("If-None-Match: %s", old_etag))
+	// Use conditional request with whichever validators we have
+	if len(old.etag) > 0 {
+		append(&curl_args, "-H", fmt.tprintf("If-None-Match: %s", old.etag))
+	}
+	if len(old.last_modified) > 0 {
+		append(&curl_args, "-H", fmt.tprintf("If-Modified-Since: %s", old.last_modified))
 	}
 
 	append(&curl_args, "-o", paths.temp, url)
 
 	output, cmd_ok := utils.run_command_output(curl_args[:], context.temp_allocator)
 	if !cmd_ok {
+		utils.download_budget_hint()
 		return "", false
 	}
 
+	utils.download_record_file(paths.temp)
 	return strings.trim_space(output), true
 }
 
-// Load or fetch index - main entry point
+// The index loaded by this process, reused by later loads of the same URL
+// (e.g. build loading it again for xbps-src dependencies) instead of reparsing
+@(private)
+Loaded_Index :: struct {
+	idx:   Index,
+	url:   string,
+	fresh: bool, // Fetched with force_update, so it also satisfies forced loads
+	valid: bool,
+}
+
+@(private)
+loaded_index: Loaded_Index
+
+// Set when a fetch replaced a cached index (main or overlay) in this process
+@(private)
+index_fetched_update: bool
+
+// Whether this process downloaded a changed index, as opposed to reading the
+// cache or getting "not modified"
+index_was_updated :: proc() -> bool {
+	return index_fetched_update
+}
+
+// Forget the index kept by index_load_or_fetch, so the next load reads the
+// cache or network again. For long-running commands; freeing it is up to
+// the allocator it was loaded with.
+index_unload :: proc() {
+	loaded_index = {}
+}
+
+// Load or fetch index - main entry point. Overlay repos (see repos.odin) are
+// loaded the same way and merged in; name clashes go to the repo with the
+// highest priority.
+// The result is shared for the rest of the process, callers must not free it.
 index_load_or_fetch :: proc(
 	url: string,
 	force_update: bool,
@@ -195,6 +597,69 @@ index_load_or_fetch :: proc(
 ) -> (
 	Index,
 	bool,
+) {
+	if loaded_index.valid && loaded_index.url == url && (!force_update || loaded_index.fresh) {
+		return loaded_index.idx, true
+	}
+
+	// Merged highest priority first, so the first repo to have a name keeps it
+	idx: Index
+	merged := false
+	for repo in repos_by_priority(url, context.temp_allocator) {
+		is_main := repo.name == MAIN_REPO_NAME
+		source, source_ok := load_or_fetch_source(
+			repo.url,
+			"" if is_main else repo.name,
+			force_update,
+			allocator,
+		)
+		if !source_ok {
+			if is_main {
+				if merged {
+					index_free(&idx)
+				}
+				return {}, false
+			}
+			errors.log_warning("Skipping repo %s: index unavailable", repo.name)
+			continue
+		}
+
+		if !is_main {
+			index_tag_source_repo(&source, repo.name)
+		}
+		if !merged {
+			idx = source
+			merged = true
+			continue
+		}
+		if shadowed := index_merge(&idx, &source, .Keep_Existing); shadowed > 0 {
+			errors.log_warning(
+				"%d package(s) of repo %s are ignored, a higher-priority repo has them (see 'vuru repo conflicts')",
+				shadowed,
+				repo.name,
+			)
+		}
+	}
+
+	loaded_index = Loaded_Index {
+		idx   = idx,
+		url   = strings.clone(url, allocator),
+		fresh = force_update,
+		valid = true,
+	}
+	return idx, true
+}
+
+// Load or fetch a single index: the main one (repo_name empty) or an overlay
+@(private)
+load_or_fetch_source :: proc(
+	url: string,
+	repo_name: string,
+	force_update: bool,
+	allocator := context.allocator,
+) -> (
+	Index,
+	bool,
 ) {
 	// Validate URL first
 	if !is_valid_url(url) {
@@ -203,62 +668,136 @@ index_load_or_fetch :: proc(
 	}
 
 	// Get cache paths
-	paths, paths_ok := get_cache_paths()
+	paths, paths_ok := get_cache_paths(repo_name)
 	if !paths_ok {
 		errors.log_error("Could not determine cache directory")
 		return {}, false
 	}
 
-	// Ensure cache directory exists
-	if !utils.mkdir_p(paths.dir) {
-		errors.log_error("Failed to create cache directory: %s", paths.dir)
-		return {}, false
-	}
-
 	// Try to load from cache if not forced
 	if !force_update && os.exists(paths.index) {
 		if idx, ok := load_index_from_file(paths.index, allocator); ok {
 			return idx, true
 		}
+
+		// Drop the validators with the broken file: a conditional request
+		// would get a 304 and hand the same corrupt cache back
+		errors.log_warning("Cached index is unreadable, fetching a fresh copy")
+		index_cache_remove(repo_name)
 	}
 
-	// Read existing ETag for conditional request
-	old_etag := ""
-	if !force_update && os.exists(paths.etag) {
-		if content, ok := utils.read_file(paths.etag, context.temp_allocator); ok {
-			old_etag = strings.trim_space(content)
+	// Without a writable cache directory (read-only home, kiosks) fetch into
+	// TMPDIR and drop the files afterwards instead of failing
+	persistent := utils.dir_is_writable(paths.dir)
+	if !persistent {
+		errors.log_warning("Cache directory %s is not writable, index will not be cached", paths.dir)
+		temp_paths, temp_ok := temp_cache_paths(repo_name)
+		if !temp_ok {
+			return {}, false
 		}
+		paths = temp_paths
+	}
+	defer if !persistent {
+		os.remove(paths.index)
+		os.remove(binary_cache_path(paths.index))
+		os.remove(paths.validators)
+		remove_shard_cache(paths.index)
 	}
 
-	errors.log_info("Fetching index...")
+	// Read existing validators for conditional request (only useful with a cached index)
+	old: Validators
+	if !force_update && os.exists(paths.index) {
+		old = read_validators(paths.validators)
+	}
+
+	if len(repo_name) > 0 {
+		errors.log_info("Fetching index of repo %s...", repo_name)
+	} else {
+		errors.log_info("Fetching index...")
+	}
 
 	// Fetch from URL
-	status, fetch_ok := fetch_index_from_url(url, paths, old_etag)
+	status, fetch_ok := fetch_index_from_url(url, paths, old)
+	headers, _ := utils.read_file(paths.headers, context.temp_allocator)
+	os.remove(paths.headers)
 	if !fetch_ok {
 		errors.log_error("Failed to fetch index")
 		os.remove(paths.temp)
 		return try_fallback_to_cache(paths.index, allocator)
 	}
 
+	received := parse_response_validators(headers)
+
 	// Handle response based on status
 	switch status {
 	case "304":
 		// Not modified - use cache
 		errors.log_info("Index not modified (cached)")
 		os.remove(paths.temp)
-		return load_index_from_file(paths.index, allocator)
+
+		if idx, ok := load_index_from_file(paths.index, allocator); ok {
+			// A 304 may carry refreshed validators; keep the old ones otherwise
+			if len(received.etag) > 0 || len(received.last_modified) > 0 {
+				write_validators(paths.validators, received)
+			}
+			return idx, true
+		}
+
+		// "Not modified" but the cache is gone or unreadable: the validators
+		// are stale, so drop them and fetch the whole index once more. Without
+		// validators the 304 came from a broken server, and retrying won't help.
+		os.remove(paths.validators)
+		if len(old.etag) == 0 && len(old.last_modified) == 0 {
+			errors.log_error("Server answered 304 to an unconditional request and there is no cached index")
+			return {}, false
+		}
+		errors.log_warning("Index not modified, but the cached copy is unusable; fetching it again")
+		return load_or_fetch_source(url, repo_name, true, allocator)
 
 	case "200":
-		// Success - move temp file to index
-		errors.log_info("Index updated")
-		os.remove(paths.index)
+		// Never let an HTML page replace the cached index
+		if content_type := utils.response_content_type(headers); utils.is_html_content_type(content_type) {
+			errors.log_error(
+				"Expected JSON index but got %s from %s, possibly behind a captive portal",
+				content_type,
+				url,
+			)
+			os.remove(paths.temp)
+			return try_fallback_to_cache(paths.index, allocator)
+		}
 
-		if os.rename(paths.temp, paths.index) != os.ERROR_NONE {
+		// A shard manifest becomes the merged index of its shards (see shards.odin)
+		if !merge_index_shards(url, paths) {
+			os.remove(paths.temp)
+			return try_fallback_to_cache(paths.index, allocator)
+		}
+
+		// An empty index is more likely a broken publish than a repo without
+		// packages, so it doesn't get to silently replace a good cache, and
+		// something that isn't an index never does. The validators stay as
+		// they were, so the next sync fetches again.
+		if !accept_fetched_index(paths) {
+			os.remove(paths.temp)
+			return load_index_from_file(paths.index, allocator)
+		}
+
+		// Success - the stale binary sidecar goes first, then the index, then
+		// its validators. An interrupted save can leave the new index with the
+		// old ETag (costing one full fetch), never the old index with the new
+		// ETag, which would be answered with 304s until the index changes again.
+		tx: utils.File_Transaction
+		utils.file_tx_stage(&tx, paths.temp, paths.index)
+		stage_validators(&tx, paths.validators, received)
+		utils.file_tx_remove(&tx, binary_cache_path(paths.index))
+		if !utils.file_tx_commit(&tx) {
 			errors.log_error("Failed to save index")
 			os.remove(paths.temp)
-			return {}, false
+			return try_fallback_to_cache(paths.index, allocator)
 		}
 
+		errors.log_info("Index updated")
+		index_fetched_update = true
+
 		return load_index_from_file(paths.index, allocator)
 
 	case:
@@ -269,6 +808,142 @@ index_load_or_fetch :: proc(
 	}
 }
 
+// Whether the index just fetched to paths.temp may replace the cached one.
+// One without packages (`{}` or an empty "packages") is refused in favour of
+// a cached index that has some: after asking, or right away when nobody can
+// answer. Content that isn't an index at all is always refused.
+@(private)
+accept_fetched_index :: proc(paths: Cache_Paths) -> bool {
+	content, ok := utils.read_file(paths.temp, context.temp_allocator)
+	if !ok {
+		return true
+	}
+	switch index_content_kind(content) {
+	case .Packages:
+		return true
+	case .Invalid:
+		errors.log_error("The fetched file is not a package index (wrong URL or a server error?)")
+		return false
+	case .Empty:
+	}
+
+	errors.log_warning("The fetched index lists no packages, which may mean a broken publish")
+	if !os.exists(paths.index) {
+		return true
+	}
+	cached, cached_ok := load_index_from_file(paths.index, context.temp_allocator)
+	if !cached_ok || len(cached.packages) == 0 {
+		return true
+	}
+
+	if _, non_interactive := utils.non_interactive_reason(); non_interactive {
+		errors.log_info("Keeping the cached index (%d packages)", len(cached.packages))
+		return false
+	}
+	return !utils.prompt_yes_no(
+		fmt.tprintf("Keep the cached index (%d packages) instead?", len(cached.packages)),
+		true,
+	)
+}
+
+// What fetched index JSON holds, judged from its top level
+@(private)
+Index_Content :: enum {
+	Invalid, // Not JSON, or an object that isn't an index
+	Empty, // `{}` or an empty "packages" object
+	Packages,
+}
+
+@(private)
+index_content_kind :: proc(content: string) -> Index_Content {
+	parsed, err := json.parse(transmute([]u8)content, allocator = context.temp_allocator)
+	if err != .None {
+		return .Invalid
+	}
+	root, is_obj := parsed.(json.Object)
+	if !is_obj {
+		return .Invalid
+	}
+	packages, has_packages := root["packages"]
+	if !has_packages {
+		return .Empty if len(root) == 0 else .Invalid
+	}
+	packages_obj, is_packages_obj := packages.(json.Object)
+	if !is_packages_obj {
+		return .Invalid
+	}
+	return .Empty if len(packages_obj) == 0 else .Packages
+}
+
+// Seconds allowed for the HEAD request of index_check_for_update
+INDEX_HEAD_TIMEOUT :: "10"
+
+// Cheaply check whether the remote index differs from the cached one (HEAD
+// request with the stored validators, nothing is downloaded or written).
+// Without a cached index an update is always reported as available.
+index_check_for_update :: proc(url: string) -> (available: bool, ok: bool) {
+	if !is_valid_url(url) {
+		errors.log_error("Invalid or unsafe URL provided")
+		return false, false
+	}
+
+	paths, paths_ok := get_cache_paths()
+	if !paths_ok {
+		errors.log_error("Could not determine cache directory")
+		return false, false
+	}
+
+	if !os.exists(paths.index) {
+		return true, true
+	}
+	old := read_validators(paths.validators)
+
+	curl_args := make([dynamic]string, context.temp_allocator)
+	append(&curl_args, "curl", "-s", "-I", "-L", "--max-time", INDEX_HEAD_TIMEOUT)
+	append(&curl_args, "-w", "%{http_code}")
+	if len(old.etag) > 0 {
+		append(&curl_args, "-H", fmt.tprintf("If-None-Match: %s", old.etag))
+	}
+	if len(old.last_modified) > 0 {
+		append(&curl_args, "-H", fmt.tprintf("If-Modified-Since: %s", old.last_modified))
+	}
+	append(&curl_args, url)
+
+	output, cmd_ok := utils.run_command_output(curl_args[:], context.temp_allocator)
+	if !cmd_ok {
+		return false, false
+	}
+
+	// Headers come first, the status code from -w is appended after them
+	output = strings.trim_right_space(output)
+	status := output
+	headers := ""
+	if nl := strings.last_index_byte(output, '\n'); nl >= 0 {
+		status = strings.trim_space(output[nl + 1:])
+		headers = output[:nl]
+	}
+
+	switch status {
+	case "304":
+		return false, true
+	case "200":
+		// Servers that ignore conditional HEADs: compare validators ourselves
+		received := parse_response_validators(headers)
+		if len(old.etag) > 0 && received.etag == old.etag {
+			return false, true
+		}
+		if len(old.etag) == 0 &&
+		   len(old.last_modified) > 0 &&
+		   received.last_modified == old.last_modified {
+			return false, true
+		}
+		return true, true
+	case:
+		errors.log_error("Unexpected HTTP status: %s", status)
+		return false, false
+	}
+}
+
 // Try to load from cache as fallback
 @(private)
 try_fallback_to_cache :: proc(
@@ -284,3 +959,37 @@ try_fallback_to_cache :: proc(
 	}
 	return {}, false
 }
+
+// Information about the cached index, for diagnostics
+Cache_Info :: struct {
+	path:      string,
+	exists:    bool,
+	packages:  int,
+	synced_at: time.Time, // Modification time of the cached index file
+}
+
+// Describe the cached index without touching the network
+index_cache_info :: proc(allocator := context.allocator) -> (Cache_Info, bool) {
+	paths, paths_ok := get_cache_paths()
+	if !paths_ok {
+		return {}, false
+	}
+
+	info := Cache_Info {
+		path = strings.clone(paths.index, allocator),
+	}
+
+	fi, stat_err := os.stat(paths.index, context.temp_allocator)
+	if stat_err != nil {
+		return info, true
+	}
+
+	info.exists = true
+	info.synced_at = fi.modification_time
+
+	if idx, ok := load_index_from_file(paths.index, context.temp_allocator); ok {
+		info.packages = index_count(&idx)
+	}
+
+	return info, true
+}
@@ -0,0 +1,300 @@
+package index
+
+import "core:fmt"
+import "core:os"
+import "core:slice"
+import "core:strings"
+
+import "../../utils"
+import config "../config"
+import errors "../errors"
+
+// Overlay repos are extra VUP-style indexes (personal or third-party repos)
+// listed in ~/.config/vup/repos, one "<name> <index-url> [priority]" per line.
+//
+// When several repos have a package of the same name, the repo with the
+// highest priority wins. The main index has priority 0, as do overlays
+// without one; on a tie the main index wins, then overlays in the order they
+// were added. The winner is recorded in Package_Info.source_repo;
+// `vuru repo conflicts` lists the rest.
+
+// Name under which the main VUP index is referred to
+MAIN_REPO_NAME :: "vup"
+
+// Priority of the main index (overlays need a higher one to override it)
+MAIN_REPO_PRIORITY :: 0
+
+// A user-registered overlay repo
+Overlay_Repo :: struct {
+	name:     string,
+	url:      string,
+	priority: int, // Higher wins on package name clashes
+}
+
+// Path of the repos config file
+repos_config_path :: proc(allocator := context.allocator) -> (string, bool) {
+	config_dir, ok := config.get_config_dir(context.temp_allocator)
+	if !ok {
+		return "", false
+	}
+	return utils.path_join(config_dir, "repos", allocator = allocator), true
+}
+
+// Check a repo name (used in cache file names, and "vup" is the main index)
+is_valid_repo_name :: proc(name: string) -> bool {
+	return utils.is_valid_identifier(name) && name != MAIN_REPO_NAME
+}
+
+// Check a repo priority: a non-negative number
+is_valid_repo_priority :: proc(s: string) -> bool {
+	if len(s) == 0 || len(s) > 9 {
+		return false
+	}
+	for c in s {
+		if c < '0' || c > '9' {
+			return false
+		}
+	}
+	return true
+}
+
+// Load registered overlay repos. A missing config file means no repos.
+repos_load :: proc(allocator := context.allocator) -> ([]Overlay_Repo, bool) {
+	path, path_ok := repos_config_path(context.temp_allocator)
+	if !path_ok {
+		return nil, false
+	}
+	if !os.exists(path) {
+		return nil, true
+	}
+
+	content, ok := utils.read_file(path, context.temp_allocator)
+	if !ok {
+		errors.log_error("Failed to read %s", path)
+		return nil, false
+	}
+
+	repos := make([dynamic]Overlay_Repo, allocator)
+	it := content
+	for line in strings.split_lines_iterator(&it) {
+		l := strings.trim_space(line)
+		if len(l) == 0 || l[0] == '#' {
+			continue
+		}
+
+		fields := strings.fields(l, context.temp_allocator)
+		if len(fields) < 2 ||
+		   len(fields) > 3 ||
+		   !is_valid_repo_name(fields[0]) ||
+		   !is_valid_url(fields[1]) ||
+		   (len(fields) == 3 && !is_valid_repo_priority(fields[2])) {
+			errors.log_warning("Ignoring invalid repo entry: %s", l)
+			continue
+		}
+
+		append(
+			&repos,
+			Overlay_Repo {
+				name = strings.clone(fields[0], allocator),
+				url = strings.clone(fields[1], allocator),
+				priority = utils.parse_int(fields[2]) if len(fields) == 3 else 0,
+			},
+		)
+	}
+
+	return repos[:], true
+}
+
+// Write the overlay repo list
+repos_save :: proc(repos: []Overlay_Repo) -> bool {
+	path, path_ok := repos_config_path(context.temp_allocator)
+	if !path_ok {
+		return false
+	}
+
+	config_dir, _ := config.get_config_dir(context.temp_allocator)
+	if !utils.mkdir_p(config_dir) {
+		errors.log_error("Failed to create config directory: %s", config_dir)
+		return false
+	}
+
+	b := strings.builder_make(context.temp_allocator)
+	strings.write_string(&b, "# vuru overlay repos: <name> <index-url> [priority]\n")
+	for r in repos {
+		if r.priority != 0 {
+			fmt.sbprintf(&b, "%s %s %d\n", r.name, r.url, r.priority)
+		} else {
+			fmt.sbprintf(&b, "%s %s\n", r.name, r.url)
+		}
+	}
+
+	return utils.write_file_atomic(path, strings.to_string(b))
+}
+
+// Download an index URL and check that it parses, without touching the
+// cache. Returns the number of packages it lists.
+index_probe :: proc(url: string) -> (packages: int, ok: bool) {
+	if !is_valid_url(url) {
+		errors.log_error("Invalid or unsafe URL: %s", url)
+		return 0, false
+	}
+
+	paths, paths_ok := temp_cache_paths("probe")
+	if !paths_ok {
+		return 0, false
+	}
+	defer {
+		os.remove(paths.headers)
+		os.remove(paths.temp)
+	}
+
+	status, fetch_ok := fetch_index_from_url(url, paths, {})
+	if !fetch_ok || len(status) == 0 || status == "000" {
+		errors.log_error("Could not reach %s", url)
+		return 0, false
+	}
+	if status != "200" {
+		errors.log_error("%s answered with HTTP status %s", url, status)
+		return 0, false
+	}
+
+	headers, _ := utils.read_file(paths.headers, context.temp_allocator)
+	if content_type := utils.response_content_type(headers); utils.is_html_content_type(content_type) {
+		errors.log_error("Expected a JSON index but got %s from %s", content_type, url)
+		return 0, false
+	}
+
+	content, read_ok := utils.read_file(paths.temp, context.temp_allocator)
+	if !read_ok {
+		errors.log_error("Failed to read the index downloaded from %s", url)
+		return 0, false
+	}
+
+	idx, parse_ok := parse_index(content, context.temp_allocator)
+	if !parse_ok {
+		errors.log_error("%s does not serve a valid VUP index", url)
+		return 0, false
+	}
+	return index_count(&idx), true
+}
+
+// The main index and the overlay repos, highest priority first (the order
+// in which they win name clashes). The main index is named MAIN_REPO_NAME.
+repos_by_priority :: proc(main_url: string, allocator := context.allocator) -> []Overlay_Repo {
+	repos, _ := repos_load(allocator)
+
+	sources := make([dynamic]Overlay_Repo, 0, len(repos) + 1, allocator)
+	append(&sources, Overlay_Repo{name = MAIN_REPO_NAME, url = main_url, priority = MAIN_REPO_PRIORITY})
+	append(&sources, ..repos)
+
+	// Stable, so ties keep the main index first and overlays in added order
+	slice.stable_sort_by(sources[:], proc(a, b: Overlay_Repo) -> bool {
+		return a.priority > b.priority
+	})
+	return sources[:]
+}
+
+// Remove the cached index files of an overlay repo
+repo_cache_remove :: proc(name: string) {
+	index_cache_remove(name)
+}
+
+// A package name defined by more than one repo
+Repo_Conflict :: struct {
+	name:           string,
+	winner:         string, // Repo whose package is used
+	winner_version: string,
+	loser:          string, // Repo whose package is ignored
+	loser_version:  string,
+}
+
+// Which package index_merge keeps when both indexes have the same name
+Merge_Precedence :: enum {
+	Keep_Existing, // dst's package stays (overlay repos, see above)
+	Prefer_Other, // other's package replaces it (e.g. a mirror known to be newer)
+}
+
+// Move all packages of other into dst, resolving name clashes by precedence.
+// other is consumed and the losing package of each clash is freed. Returns
+// the number of clashes.
+index_merge :: proc(dst: ^Index, other: ^Index, precedence: Merge_Precedence) -> (conflicts: int) {
+	for name, &pkg in other.packages {
+		if name not_in dst.packages {
+			dst.packages[name] = pkg
+			continue
+		}
+
+		conflicts += 1
+		switch precedence {
+		case .Keep_Existing:
+			package_info_free(&pkg, other.allocator)
+			delete(name, other.allocator)
+		case .Prefer_Other:
+			old_name, old_pkg := delete_key(&dst.packages, name)
+			package_info_free(&old_pkg, dst.allocator)
+			delete(old_name, dst.allocator)
+			dst.packages[name] = pkg
+		}
+	}
+	delete(other.packages)
+	other.packages = nil
+	return
+}
+
+// Record the overlay repo every package of idx came from
+index_tag_source_repo :: proc(idx: ^Index, repo_name: string) {
+	for _, &pkg in idx.packages {
+		pkg.source_repo = strings.clone(repo_name, idx.allocator)
+	}
+}
+
+// Package names defined by more than one of the main index and the overlay
+// repos (cached copies, fetched if missing), sorted by name
+index_repo_conflicts :: proc(url: string, allocator := context.allocator) -> ([]Repo_Conflict, bool) {
+	// Repo and version each name resolves to so far
+	owners := make(map[string][2]string, context.temp_allocator)
+
+	conflicts := make([dynamic]Repo_Conflict, allocator)
+	for repo in repos_by_priority(url, context.temp_allocator) {
+		is_main := repo.name == MAIN_REPO_NAME
+		overlay, overlay_ok := load_or_fetch_source(
+			repo.url,
+			"" if is_main else repo.name,
+			false,
+			context.temp_allocator,
+		)
+		if !overlay_ok {
+			if is_main {
+				return nil, false
+			}
+			errors.log_warning("Skipping repo %s: index unavailable", repo.name)
+			continue
+		}
+
+		for name, pkg in overlay.packages {
+			owner, taken := owners[name]
+			if !taken {
+				owners[name] = {repo.name, pkg.version}
+				continue
+			}
+			append(
+				&conflicts,
+				Repo_Conflict {
+					name = strings.clone(name, allocator),
+					winner = strings.clone(owner[0], allocator),
+					winner_version = strings.clone(owner[1], allocator),
+					loser = strings.clone(repo.name, allocator),
+					loser_version = strings.clone(pkg.version, allocator),
+				},
+			)
+		}
+	}
+
+	slice.sort_by(conflicts[:], proc(a, b: Repo_Conflict) -> bool {
+		if a.name != b.name {
+			return a.name < b.name
+		}
+		return a.loser < b.loser
+	})
+	return conflicts[:], true
+}
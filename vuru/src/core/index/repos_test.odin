@@ -0,0 +1,63 @@
+package index
+
+import "core:testing"
+
+// An index of name -> version entries
+@(private = "file")
+test_index :: proc(t: ^testing.T, content: string) -> Index {
+	idx, ok := parse_index(content)
+	testing.expect(t, ok, "test index parses")
+	return idx
+}
+
+@(test)
+test_index_merge_disjoint :: proc(t: ^testing.T) {
+	// Without clashes the precedence makes no difference
+	for precedence in Merge_Precedence {
+		dst := test_index(t, `{"packages": {"a": {"version": "1.0_1"}}}`)
+		other := test_index(t, `{"packages": {"b": {"version": "2.0_1"}, "c": {"version": "3.0_1"}}}`)
+
+		testing.expect_value(t, index_merge(&dst, &other, precedence), 0)
+		testing.expect(t, other.packages == nil)
+		testing.expect_value(t, len(dst.packages), 3)
+		testing.expect_value(t, dst.packages["a"].version, "1.0_1")
+		testing.expect_value(t, dst.packages["b"].version, "2.0_1")
+		testing.expect_value(t, dst.packages["c"].version, "3.0_1")
+		index_free(&dst)
+	}
+}
+
+@(test)
+test_index_merge_keep_existing :: proc(t: ^testing.T) {
+	dst := test_index(t, `{"packages": {"a": {"version": "1.0_1"}, "b": {"version": "1.0_1"}}}`)
+	defer index_free(&dst)
+	other := test_index(t, `{"packages": {"b": {"version": "2.0_1"}, "c": {"version": "2.0_1"}}}`)
+
+	testing.expect_value(t, index_merge(&dst, &other, .Keep_Existing), 1)
+	testing.expect_value(t, len(dst.packages), 3)
+	testing.expect_value(t, dst.packages["b"].version, "1.0_1")
+	testing.expect_value(t, dst.packages["c"].version, "2.0_1")
+}
+
+@(test)
+test_index_merge_prefer_other :: proc(t: ^testing.T) {
+	dst := test_index(t, `{"packages": {"a": {"version": "1.0_1"}, "b": {"version": "1.0_1"}}}`)
+	defer index_free(&dst)
+	other := test_index(t, `{"packages": {"b": {"version": "2.0_1"}, "c": {"version": "2.0_1"}}}`)
+
+	testing.expect_value(t, index_merge(&dst, &other, .Prefer_Other), 1)
+	testing.expect_value(t, len(dst.packages), 3)
+	testing.expect_value(t, dst.packages["a"].version, "1.0_1")
+	testing.expect_value(t, dst.packages["b"].version, "2.0_1")
+	testing.expect_value(t, dst.packages["c"].version, "2.0_1")
+}
+
+@(test)
+test_index_merge_empty :: proc(t: ^testing.T) {
+	dst := test_index(t, `{"packages": {}}`)
+	defer index_free(&dst)
+	other := test_index(t, `{"packages": {"a": {"version": "1.0_1"}}}`)
+
+	testing.expect_value(t, index_merge(&dst, &other, .Keep_Existing), 0)
+	testing.expect_value(t, dst.packages["a"].version, "1.0_1")
+}
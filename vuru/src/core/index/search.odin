@@ -0,0 +1,66 @@
+package index
+
+import "core:strings"
+
+// Split a search query into lowercased, space-separated terms
+search_terms :: proc(query: string, allocator := context.allocator) -> []string {
+	terms := make([dynamic]string, allocator)
+	rest := query
+	for field in strings.fields_iterator(&rest) {
+		append(&terms, strings.to_lower(field, allocator))
+	}
+	return terms[:]
+}
+
+// Check lowercased text against terms: all must appear, or any with match_any
+search_terms_match :: proc(text_lower: string, terms: []string, match_any: bool) -> bool {
+	if len(terms) == 0 {
+		return false
+	}
+	for term in terms {
+		found := strings.contains(text_lower, term)
+		if match_any && found {
+			return true
+		}
+		if !match_any && !found {
+			return false
+		}
+	}
+	return !match_any
+}
+
+// Check a license field against a --license filter: case-insensitive
+// substring, so "gpl" finds GPL-2.0-only as well as LGPL-2.1-or-later.
+// Packages without a license never match.
+license_matches :: proc(license: string, filter: string) -> bool {
+	if len(license) == 0 {
+		return false
+	}
+	return strings.contains(
+		strings.to_lower(license, context.temp_allocator),
+		strings.to_lower(filter, context.temp_allocator),
+	)
+}
+
+// Check a package against query terms. A single term matches the name
+// (and the description with include_desc); several terms are matched
+// across name and description together.
+search_matches :: proc(
+	name: string,
+	desc: string,
+	terms: []string,
+	match_any: bool,
+	include_desc: bool,
+) -> bool {
+	name_lower := strings.to_lower(name, context.temp_allocator)
+	if search_terms_match(name_lower, terms, match_any) {
+		return true
+	}
+
+	if !include_desc && len(terms) < 2 {
+		return false
+	}
+
+	text := strings.concatenate({name, " ", desc}, context.temp_allocator)
+	return search_terms_match(strings.to_lower(text, context.temp_allocator), terms, match_any)
+}
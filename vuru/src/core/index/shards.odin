@@ -0,0 +1,305 @@
+package index
+
+import "core:encoding/json"
+import "core:fmt"
+import "core:os"
+import "core:strconv"
+import "core:strings"
+
+import "../../utils"
+import errors "../errors"
+
+// Sharded indexes: for very large package sets the index URL may serve a
+// manifest naming shards, each a regular index of its own:
+//
+//     {"shards": [
+//         {"url": "index-a.json", "etag": "\"5f2c1e\""},
+//         {"url": "https://mirror.example.org/index-b.json"}
+//     ]}
+//
+// Relative shard URLs are resolved against the manifest URL. The shards are
+// fetched concurrently and merged into one index, which is cached like a
+// plain one; the cache validators then belong to the manifest. Shards are
+// kept next to the cached index, and one whose manifest ETag matches the
+// kept copy is not downloaded again.
+
+// Parallel shard downloads
+@(private)
+SHARD_MAX_CONCURRENT :: 4
+
+@(private)
+Index_Shard :: struct {
+	url:  string,
+	etag: string,
+}
+
+// The merged index as written to the cache, in the format parse_index reads
+@(private)
+Index_Json :: struct {
+	packages: map[string]Package_Info,
+}
+
+// Shards listed by index JSON. is_manifest is false for a plain index;
+// shards is empty when a manifest has invalid entries (already reported).
+@(private)
+parse_shard_manifest :: proc(content: string) -> (shards: []Index_Shard, is_manifest: bool) {
+	parsed, err := json.parse(transmute([]u8)content, allocator = context.temp_allocator)
+	if err != .None {
+		return nil, false
+	}
+	root, is_obj := parsed.(json.Object)
+	if !is_obj {
+		return nil, false
+	}
+	shards_val, has_shards := root["shards"]
+	if !has_shards {
+		return nil, false
+	}
+
+	arr, is_arr := shards_val.(json.Array)
+	if !is_arr {
+		errors.log_error("Index shard manifest: 'shards' is not an array")
+		return nil, true
+	}
+
+	result := make([dynamic]Index_Shard, 0, len(arr), context.temp_allocator)
+	for entry, i in arr {
+		obj, _ := entry.(json.Object)
+		url, url_ok := obj["url"].(json.String)
+		if !url_ok || len(url) == 0 {
+			errors.log_error("Index shard manifest: shard %d has no url", i + 1)
+			return nil, true
+		}
+		etag, _ := obj["etag"].(json.String)
+		append(&result, Index_Shard{url = url, etag = etag})
+	}
+	return result[:], true
+}
+
+// When paths.temp holds a shard manifest fetched from url, replace it with
+// the merged index of its shards. A plain index is left alone. False when a
+// shard can't be fetched or isn't a valid index.
+@(private)
+merge_index_shards :: proc(url: string, paths: Cache_Paths) -> bool {
+	content, ok := utils.read_file(paths.temp, context.temp_allocator)
+	if !ok {
+		return true
+	}
+	shards, is_manifest := parse_shard_manifest(content)
+	if !is_manifest {
+		return true
+	}
+	if len(shards) == 0 {
+		errors.log_error("Index shard manifest at %s lists no usable shards", url)
+		return false
+	}
+
+	shard_dir := shard_cache_dir(paths.index)
+	if !utils.mkdir_p(shard_dir) {
+		errors.log_error("Failed to create directory: %s", shard_dir)
+		return false
+	}
+
+	shard_paths := make([]string, len(shards), context.temp_allocator)
+	shard_urls := make([]string, len(shards), context.temp_allocator)
+	stale := make([dynamic]int, context.temp_allocator)
+	for s, i in shards {
+		shard_urls[i] = resolve_shard_url(url, s.url)
+		if !is_valid_url(shard_urls[i]) {
+			errors.log_error("Invalid or unsafe shard URL: %s", shard_urls[i])
+			return false
+		}
+		shard_paths[i] = utils.path_join(shard_dir, fmt.tprintf("shard-%d.json", i), allocator = context.temp_allocator)
+
+		// Reuse the kept copy only for a known ETag of the same URL
+		stamp := fmt.tprintf("%s\n%s\n", shard_urls[i], s.etag)
+		kept, kept_ok := utils.read_file(shard_stamp_path(shard_paths[i]), context.temp_allocator)
+		if len(s.etag) == 0 || !kept_ok || kept != stamp || !os.exists(shard_paths[i]) {
+			append(&stale, i)
+		}
+	}
+
+	if len(stale) > 0 && !fetch_index_shards(shards, shard_urls, shard_paths, stale[:]) {
+		return false
+	}
+	remove_unlisted_shards(shard_dir, len(shards))
+
+	// Fold the shards together; a package listed by several shards keeps
+	// the entry of the first
+	merged := index_make(context.temp_allocator)
+	duplicates := 0
+	for path, i in shard_paths {
+		shard_content, read_ok := utils.read_file(path, context.temp_allocator)
+		shard: Index
+		shard_ok := false
+		if read_ok {
+			shard, shard_ok = parse_index(shard_content, context.temp_allocator)
+		}
+		if !shard_ok {
+			errors.log_error("Index shard %s is not a valid index", shard_urls[i])
+			os.remove(path)
+			return false
+		}
+		duplicates += index_merge(&merged, &shard, .Keep_Existing)
+	}
+	if duplicates > 0 {
+		errors.log_warning("%d package(s) appear in more than one index shard, using the first", duplicates)
+	}
+
+	data, err := json.marshal(Index_Json{packages = merged.packages}, allocator = context.temp_allocator)
+	if err != nil || !utils.write_file(paths.temp, string(data)) {
+		errors.log_error("Failed to write the merged index")
+		return false
+	}
+
+	errors.log_info("Merged %d index shard(s), %d packages", len(shards), len(merged.packages))
+	return true
+}
+
+// Download the shards at positions todo with one parallel curl run. Only
+// transfers curl reports as complete are kept: a shard cut off part-way (a
+// reset connection, --max-filesize) is deleted, never stamped as current.
+@(private)
+fetch_index_shards :: proc(shards: []Index_Shard, urls: []string, paths: []string, todo: []int) -> bool {
+	limit_args, limit_ok := utils.download_curl_args("index shards", context.temp_allocator, transfers = len(todo))
+	if !limit_ok {
+		return false
+	}
+
+	errors.log_info("Fetching %d of %d index shard(s)...", len(todo), len(shards))
+
+	args := make([dynamic]string, context.temp_allocator)
+	append(&args, "curl", "--parallel", "--parallel-max", fmt.tprintf("%d", SHARD_MAX_CONCURRENT))
+	for i, n in todo {
+		// Options after --next apply to the next transfer only
+		if n > 0 {
+			append(&args, "--next")
+		}
+		append(&args, "-s", "-f", "-L", "-o", fmt.tprintf("%s.tmp", paths[i]))
+		// One "<position> <curl exit code>" line per finished transfer
+		append(&args, "-w", fmt.tprintf("%d %%{exitcode}\n", i))
+		append(&args, ..limit_args)
+		append(&args, urls[i])
+	}
+
+	// curl's own exit code only tells about one transfer, the -w lines tell
+	// about each
+	output, _ := utils.run_command_output(args[:], context.temp_allocator)
+	completed := make(map[int]bool, context.temp_allocator)
+	for line in strings.split_lines(output, context.temp_allocator) {
+		fields := strings.fields(line, context.temp_allocator)
+		if len(fields) != 2 || fields[1] != "0" {
+			continue
+		}
+		if i, i_ok := strconv.parse_int(fields[0]); i_ok {
+			completed[i] = true
+		}
+	}
+
+	ok := true
+	for i in todo {
+		tmp := fmt.tprintf("%s.tmp", paths[i])
+		if os.exists(tmp) {
+			utils.download_record_file(tmp)
+		}
+		if !completed[i] || !os.exists(tmp) {
+			errors.log_error("Failed to fetch index shard %s", urls[i])
+			os.remove(tmp)
+			ok = false
+			continue
+		}
+		if os.rename(tmp, paths[i]) != os.ERROR_NONE {
+			errors.log_error("Failed to save index shard %s", urls[i])
+			os.remove(tmp)
+			ok = false
+			continue
+		}
+		utils.write_file(shard_stamp_path(paths[i]), fmt.tprintf("%s\n%s\n", urls[i], shards[i].etag))
+	}
+	if !ok {
+		utils.download_budget_hint()
+	}
+	return ok
+}
+
+// Absolute URL of a shard: as given, or relative to the manifest URL
+@(private)
+resolve_shard_url :: proc(manifest_url: string, shard_url: string) -> string {
+	if strings.contains(shard_url, "://") {
+		return shard_url
+	}
+
+	// "/path" is relative to the host, anything else to the manifest's directory
+	if strings.has_prefix(shard_url, "/") {
+		scheme_end := strings.index(manifest_url, "://") + 3
+		if host_end := strings.index_byte(manifest_url[scheme_end:], '/'); host_end >= 0 {
+			return strings.concatenate({manifest_url[:scheme_end + host_end], shard_url}, context.temp_allocator)
+		}
+		return strings.concatenate({manifest_url, shard_url}, context.temp_allocator)
+	}
+	dir := manifest_url[:strings.last_index_byte(manifest_url, '/') + 1]
+	return strings.concatenate({dir, shard_url}, context.temp_allocator)
+}
+
+// Directory holding the shards of a cached index
+@(private)
+shard_cache_dir :: proc(index_path: string) -> string {
+	return fmt.tprintf("%s.shards", index_path)
+}
+
+// Sidecar recording the URL and ETag a kept shard was fetched with
+@(private)
+shard_stamp_path :: proc(shard_path: string) -> string {
+	return fmt.tprintf("%s.stamp", shard_path)
+}
+
+// Remove kept shards at positions the manifest no longer has
+@(private)
+remove_unlisted_shards :: proc(shard_dir: string, count: int) {
+	for fi in shard_cache_files(shard_dir) {
+		keep := false
+		for i in 0 ..< count {
+			name := fmt.tprintf("shard-%d.json", i)
+			if fi.name == name || fi.name == fmt.tprintf("%s.stamp", name) {
+				keep = true
+				break
+			}
+		}
+		if !keep {
+			os.remove(fi.fullpath)
+		}
+	}
+}
+
+// Files in the shard directory of a cached index
+@(private)
+shard_cache_files :: proc(shard_dir: string) -> []os.File_Info {
+	d, err := os.open(shard_dir)
+	if err != os.ERROR_NONE {
+		return nil
+	}
+	defer os.close(d)
+
+	file_infos, _ := os.read_dir(d, -1, context.temp_allocator)
+	return file_infos
+}
+
+// Remove the kept shards of a cached index
+@(private)
+remove_shard_cache :: proc(index_path: string) {
+	dir := shard_cache_dir(index_path)
+	for fi in shard_cache_files(dir) {
+		os.remove(fi.fullpath)
+	}
+	os.remove(dir)
+}
+
+// Bytes taken by the kept shards of a cached index
+@(private)
+shard_cache_size :: proc(index_path: string) -> i64 {
+	total: i64 = 0
+	for fi in shard_cache_files(shard_cache_dir(index_path)) {
+		total += fi.size
+	}
+	return total
+}
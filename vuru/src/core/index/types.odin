@@ -1,12 +1,18 @@
 package index
 
 import "core:mem"
+import "core:strings"
 
 // Package metadata from index
 Package_Info :: struct {
-	version:    string,
+	version:    string, // "<version>_<revision>" as in an xbps pkgver ("1.2.3_1")
 	category:   string,
 	short_desc: string,
+	source_pkg: string, // srcpkgs directory holding the template, if not the package name
+	source_repo: string, // Overlay repo the package came from (empty = main VUP index)
+	suggests:   []string, // Optional companion packages offered after install (may be empty)
+	updated_at: string, // Date the package was added or last changed (YYYY-MM-DD or RFC 3339), empty if unknown
+	license:    string, // Template license field (e.g. "GPL-3.0-or-later"), empty if the index has none
 	repo_urls:  map[string]string,
 }
 
@@ -23,6 +29,12 @@ package_info_free :: proc(pkg: ^Package_Info, allocator: mem.Allocator) {
 	if len(pkg.version) > 0 do delete(pkg.version, allocator)
 	if len(pkg.category) > 0 do delete(pkg.category, allocator)
 	if len(pkg.short_desc) > 0 do delete(pkg.short_desc, allocator)
+	if len(pkg.source_pkg) > 0 do delete(pkg.source_pkg, allocator)
+	if len(pkg.source_repo) > 0 do delete(pkg.source_repo, allocator)
+	if len(pkg.updated_at) > 0 do delete(pkg.updated_at, allocator)
+	if len(pkg.license) > 0 do delete(pkg.license, allocator)
+	for s in pkg.suggests do delete(s, allocator)
+	if len(pkg.suggests) > 0 do delete(pkg.suggests, allocator)
 	
 	// Free repo_urls map entries
 	for arch, url in pkg.repo_urls {
@@ -57,6 +69,37 @@ index_get_package :: proc(idx: ^Index, name: string) -> (Package_Info, bool) {
 	return pkg, ok
 }
 
+// Get the srcpkgs directory name for a package's template
+// (subpackages share the template of their source package)
+package_template_name :: proc(pkg: Package_Info, name: string) -> string {
+	return pkg.source_pkg if len(pkg.source_pkg) > 0 else name
+}
+
+// Name of the repo a package came from (MAIN_REPO_NAME for the main index)
+package_repo_name :: proc(pkg: Package_Info) -> string {
+	return pkg.source_repo if len(pkg.source_repo) > 0 else MAIN_REPO_NAME
+}
+
+// Whether a package was added or changed on or after a YYYY-MM-DD date.
+// Packages without updated_at are unknown and never match.
+package_updated_since :: proc(pkg: Package_Info, date: string) -> bool {
+	if len(pkg.updated_at) < 10 {
+		return false
+	}
+	return pkg.updated_at[:10] >= date
+}
+
+// Names in the index equal to name ignoring case, for when an exact lookup misses
+index_find_package_ci :: proc(idx: ^Index, name: string, allocator := context.allocator) -> []string {
+	matches := make([dynamic]string, allocator)
+	for key in idx.packages {
+		if strings.equal_fold(key, name) {
+			append(&matches, key)
+		}
+	}
+	return matches[:]
+}
+
 // Check if package exists in index
 index_has_package :: proc(idx: ^Index, name: string) -> bool {
 	return name in idx.packages
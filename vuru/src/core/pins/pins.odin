@@ -0,0 +1,78 @@
+package pins
+
+import "core:os"
+import "core:slice"
+import "core:strings"
+
+import "../../utils"
+import config "../config"
+import errors "../errors"
+
+// Pinned packages are held back by `vuru update`. They are listed in
+// ~/.config/vup/pins, one package name per line.
+
+// Path of the pins file
+pins_path :: proc(allocator := context.allocator) -> (string, bool) {
+	config_dir, ok := config.get_config_dir(context.temp_allocator)
+	if !ok {
+		return "", false
+	}
+	return utils.path_join(config_dir, "pins", allocator = allocator), true
+}
+
+// Load pinned package names, sorted. A missing file means no pins.
+pins_load :: proc(allocator := context.allocator) -> ([]string, bool) {
+	path, path_ok := pins_path(context.temp_allocator)
+	if !path_ok {
+		return nil, false
+	}
+	if !os.exists(path) {
+		return nil, true
+	}
+
+	content, ok := utils.read_file(path, context.temp_allocator)
+	if !ok {
+		errors.log_error("Failed to read %s", path)
+		return nil, false
+	}
+
+	names := make([dynamic]string, allocator)
+	it := content
+	for line in strings.split_lines_iterator(&it) {
+		l := strings.trim_space(line)
+		if len(l) == 0 || l[0] == '#' {
+			continue
+		}
+		if !utils.is_valid_package_name(l) {
+			errors.log_warning("Ignoring invalid pin: %s", l)
+			continue
+		}
+		append(&names, strings.clone(l, allocator))
+	}
+
+	slice.sort(names[:])
+	return names[:], true
+}
+
+// Write the pin list
+pins_save :: proc(names: []string) -> bool {
+	path, path_ok := pins_path(context.temp_allocator)
+	if !path_ok {
+		return false
+	}
+
+	config_dir, _ := config.get_config_dir(context.temp_allocator)
+	if !utils.mkdir_p(config_dir) {
+		errors.log_error("Failed to create config directory: %s", config_dir)
+		return false
+	}
+
+	b := strings.builder_make(context.temp_allocator)
+	strings.write_string(&b, "# vuru pinned packages, held back by 'vuru update'\n")
+	for name in names {
+		strings.write_string(&b, name)
+		strings.write_byte(&b, '\n')
+	}
+
+	return utils.write_file_atomic(path, strings.to_string(b))
+}
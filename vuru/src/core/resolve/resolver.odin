@@ -27,7 +27,7 @@ get_installed_version :: proc(name: string) -> (version: string, ok: bool) {
 	for line in strings.split_lines_iterator(&output) {
 		if strings.has_prefix(line, "pkgver:") {
 			value := strings.trim_space(line[7:])
-			// Use xbps-uhelper for correct parsing of packages with dashes in names
+			// The version follows the last dash; names may contain dashes too
 			_, version, parse_ok := xbps.parse_pkgver(value)
 			if parse_ok {
 				return version, true
@@ -49,7 +49,7 @@ is_in_official_repos :: proc(name: string) -> (version: string, ok: bool) {
 	for line in strings.split_lines_iterator(&output) {
 		if strings.has_prefix(line, "pkgver:") {
 			value := strings.trim_space(line[7:])
-			// Use xbps-uhelper for correct parsing of packages with dashes in names
+			// The version follows the last dash; names may contain dashes too
 			_, version, parse_ok := xbps.parse_pkgver(value)
 			if parse_ok {
 				return version, true
@@ -80,7 +80,7 @@ resolve_package :: proc(
 		if url, url_ok := vup_pkg.repo_urls[arch]; url_ok {
 			// If installed, check if VUP has a newer version
 			if is_installed {
-				if xbps.version_greater_than(vup_pkg.version, installed_ver, utils.run_command) {
+				if xbps.version_greater_than(vup_pkg.version, installed_ver) {
 					// VUP has a newer version - mark for upgrade
 					return Resolved_Package {
 							name = strings.clone(name, allocator),
@@ -139,11 +139,15 @@ resolve_package :: proc(
 	return {}, false
 }
 
-// Resolve dependencies for one or more target packages
+// Resolve dependencies for one or more target packages. Without
+// follow_templates VUP packages aren't expanded through their templates:
+// nothing is downloaded, and xbps pulls in their dependencies from the repo.
 resolve_deps :: proc(
 	targets: []string,
 	idx: ^index.Index,
 	include_makedeps: bool,
+	source := template.Template_Source{},
+	follow_templates := true,
 	allocator := context.allocator,
 ) -> (
 	Resolution,
@@ -217,10 +221,25 @@ resolve_deps :: proc(
 
 		case .VUP:
 			append(&res.to_install, pkg)
+			if !follow_templates {
+				continue
+			}
 
-			// Resolve VUP package dependencies from template
-			if tmpl, tmpl_ok := fetch_and_parse_template(pkg.category, item.name, allocator);
-			   tmpl_ok {
+			// Resolve VUP package dependencies from the template, keeping the
+			// raw template for callers that archive what was used
+			vup_pkg, _ := index.index_get_package(idx, item.name)
+			tmpl: template.Template
+			tmpl_ok := false
+			if content, content_ok := template.fetch_template(
+				pkg.category,
+				index.package_template_name(vup_pkg, item.name),
+				source,
+				context.temp_allocator,
+			); content_ok {
+				res.templates[strings.clone(item.name, allocator)] = strings.clone(content, allocator)
+				tmpl, tmpl_ok = template.template_parse(content, allocator)
+			}
+			if tmpl_ok {
 				// Queue runtime dependencies
 				for dep in tmpl.depends {
 					if dep not_in visited {
@@ -272,12 +291,13 @@ resolve_deps :: proc(
 fetch_and_parse_template :: proc(
 	category: string,
 	pkg_name: string,
+	source := template.Template_Source{},
 	allocator := context.allocator,
 ) -> (
 	template.Template,
 	bool,
 ) {
-	content, ok := template.fetch_template(category, pkg_name, context.temp_allocator)
+	content, ok := template.fetch_template(category, pkg_name, source, context.temp_allocator)
 	if !ok {
 		return {}, false
 	}
@@ -44,6 +44,9 @@ Resolution :: struct {
 
 	// Detailed errors for each failure
 	errors:     [dynamic]errors.Error,
+
+	// Raw template of each VUP package fetched while resolving, by name
+	templates:  map[string]string,
 	allocator:  mem.Allocator,
 }
 
@@ -101,6 +104,12 @@ resolution_free :: proc(r: ^Resolution) {
 
 	// Errors are temp-based views, ctx is not owned - just free the array
 	delete(r.errors)
+
+	for name, content in r.templates {
+		delete(name, r.allocator)
+		delete(content, r.allocator)
+	}
+	delete(r.templates)
 }
 
 // Create a new empty Resolution
@@ -112,6 +121,7 @@ resolution_make :: proc(target: string = "", allocator := context.allocator) ->
 		satisfied = make([dynamic]string, allocator),
 		missing = make([dynamic]string, allocator),
 		errors = make([dynamic]errors.Error, allocator),
+		templates = make(map[string]string, allocator = allocator),
 		allocator = allocator,
 	}
 }
@@ -7,9 +7,44 @@ import errors "../errors"
 import "core:os"
 import "core:strings"
 
+// Whether the template cache can be written, probed on first save
+@(private)
+Cache_State :: enum {
+	Unknown,
+	Writable,
+	Disabled, // Not writable: templates are not persisted (warned once)
+}
+
+@(private)
+cache_state: Cache_State
+
+// Probe the template cache directory once. When it can't be written, warn
+// and carry on without persisting templates instead of failing installs.
+cache_is_writable :: proc() -> bool {
+	switch cache_state {
+	case .Writable:
+		return true
+	case .Disabled:
+		return false
+	case .Unknown:
+	}
+
+	cache_dir, ok := config.get_cache_dir(context.temp_allocator)
+	dir_path := utils.path_join(cache_dir, "templates", allocator = context.temp_allocator)
+
+	if ok && utils.dir_is_writable(dir_path) {
+		cache_state = .Writable
+		return true
+	}
+
+	errors.log_warning("Template cache %s is not writable, continuing without cache", dir_path)
+	cache_state = .Disabled
+	return false
+}
+
 // Retrieve a cached package template
 cache_get_template :: proc(pkg_name: string, allocator := context.allocator) -> (string, bool) {
-	if !utils.is_valid_identifier(pkg_name) {
+	if !utils.is_valid_package_name(pkg_name) {
 		errors.log_error("Invalid package name: %s", pkg_name)
 		return "", false
 	}
@@ -26,7 +61,12 @@ cache_get_template :: proc(pkg_name: string, allocator := context.allocator) ->
 
 // Save a package template to the cache
 cache_save_template :: proc(pkg_name: string, content: string) -> bool {
-	if !utils.is_valid_identifier(pkg_name) || len(content) == 0 {
+	if !utils.is_valid_package_name(pkg_name) || len(content) == 0 {
+		return false
+	}
+
+	// Degraded no-cache mode (already warned)
+	if !cache_is_writable() {
 		return false
 	}
 
@@ -37,17 +77,128 @@ cache_save_template :: proc(pkg_name: string, content: string) -> bool {
 
 	dir_path := utils.path_join(cache_dir, "templates", allocator = context.temp_allocator)
 
-	if !utils.mkdir_p(dir_path) {
-		errors.log_error("Failed to create cache directory: %s", dir_path)
-		return false
-	}
-
 	file_path := utils.path_join(dir_path, pkg_name, allocator = context.temp_allocator)
 
-	if !utils.write_file(file_path, content) {
+	if !utils.write_file_atomic(file_path, content) {
 		errors.log_error("Failed to save template")
 		return false
 	}
 
 	return true
 }
+
+// What an entry of the template cache directory turned out to be. Only
+// .Template entries are templates vuru wrote; the rest are skipped by cache
+// maintenance and reported.
+Cache_Entry_Kind :: enum {
+	Template,
+	Bad_Name, // Regular file whose name is not a package name (editor backups, leftovers)
+	Unreadable,
+	Symlink,
+	Broken_Symlink,
+	Directory,
+	Other, // Sockets, fifos, devices
+}
+
+// An entry of the template cache directory
+Cache_Entry :: struct {
+	name: string, // Package name for templates, else the file name
+	path: string,
+	size: i64,
+	kind: Cache_Entry_Kind,
+}
+
+// Human readable description of an entry kind, for reports
+cache_entry_kind_string :: proc(kind: Cache_Entry_Kind) -> string {
+	switch kind {
+	case .Template:
+		return "template"
+	case .Bad_Name:
+		return "not a package name"
+	case .Unreadable:
+		return "unreadable"
+	case .Symlink:
+		return "symlink"
+	case .Broken_Symlink:
+		return "broken symlink"
+	case .Directory:
+		return "directory"
+	case .Other:
+		return "not a regular file"
+	}
+	return "unknown"
+}
+
+// List every entry of the template cache directory, classified. Nothing is
+// followed or opened beyond what classifying needs.
+cache_entries :: proc(allocator := context.allocator) -> ([]Cache_Entry, bool) {
+	cache_dir, ok := config.get_cache_dir(context.temp_allocator)
+	if !ok {
+		return nil, false
+	}
+
+	dir_path := utils.path_join(cache_dir, "templates", allocator = context.temp_allocator)
+	if !os.exists(dir_path) {
+		return nil, true
+	}
+
+	d, err := os.open(dir_path)
+	if err != os.ERROR_NONE {
+		errors.log_error("Cannot read template cache: %s", dir_path)
+		return nil, false
+	}
+	defer os.close(d)
+
+	file_infos, _ := os.read_dir(d, -1, context.temp_allocator)
+
+	entries := make([dynamic]Cache_Entry, allocator)
+	for fi in file_infos {
+		path := utils.path_join(dir_path, fi.name, allocator = allocator)
+
+		kind: Cache_Entry_Kind
+		#partial switch fi.type {
+		case .Regular:
+			switch {
+			case !utils.is_valid_package_name(fi.name):
+				kind = .Bad_Name
+			case !utils.is_readable(path):
+				kind = .Unreadable
+			case:
+				kind = .Template
+			}
+		case .Symlink:
+			kind = .Symlink if os.exists(path) else .Broken_Symlink
+		case .Directory:
+			kind = .Directory
+		case:
+			kind = .Other
+		}
+
+		append(
+			&entries,
+			Cache_Entry {
+				name = strings.clone(fi.name, allocator),
+				path = path,
+				size = fi.size,
+				kind = kind,
+			},
+		)
+	}
+
+	return entries[:], true
+}
+
+// Remove a package template from the cache
+cache_remove_template :: proc(pkg_name: string) -> bool {
+	if !utils.is_valid_package_name(pkg_name) {
+		return false
+	}
+
+	cache_dir, ok := config.get_cache_dir(context.temp_allocator)
+	if !ok {
+		return false
+	}
+
+	path := utils.path_join(cache_dir, "templates", pkg_name, allocator = context.temp_allocator)
+	return os.remove(path) == nil
+}
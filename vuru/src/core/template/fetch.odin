@@ -6,37 +6,194 @@ import errors "../errors"
 
 import "core:fmt"
 import "core:os"
+import "core:strings"
 import "core:sys/linux"
 
-// Base URL for templates
-TEMPLATE_URL_BASE :: "https://raw.githubusercontent.com/VUP-Linux/vup/main/vup/srcpkgs"
+// Raw file host for the VUP repository. Templates live at
+// <base>/<ref>/vup/srcpkgs/<category>/<pkg>/template
+TEMPLATE_REPO_BASE :: "https://raw.githubusercontent.com/VUP-Linux/vup"
+
+// Branch templates are fetched from unless overridden
+DEFAULT_TEMPLATE_REF :: "main"
+
+// Where package templates are fetched from
+Template_Source :: struct {
+	git_ref:  string, // Branch, tag or commit of the VUP repo (empty = main)
+	base_url: string, // Raw file base of a fork or mirror (empty = TEMPLATE_REPO_BASE)
+}
+
+// Raw URL of a package template
+template_url :: proc(category: string, pkg_name: string, source := Template_Source{}) -> (string, bool) {
+	if !utils.is_valid_identifier(category) || !utils.is_valid_package_name(pkg_name) {
+		errors.log_error("Invalid category or package name")
+		return "", false
+	}
+
+	git_ref := source.git_ref if len(source.git_ref) > 0 else DEFAULT_TEMPLATE_REF
+	if !utils.is_valid_git_ref(git_ref) {
+		errors.log_error("Invalid template ref: %s", git_ref)
+		return "", false
+	}
+
+	base := TEMPLATE_REPO_BASE
+	if len(source.base_url) > 0 {
+		base = strings.trim_right(source.base_url, "/")
+	}
+
+	return fmt.tprintf("%s/%s/vup/srcpkgs/%s/%s/template", base, git_ref, category, pkg_name), true
+}
 
 // Fetch the template for a package
 fetch_template :: proc(
 	category: string,
 	pkg_name: string,
+	source := Template_Source{},
 	allocator := context.allocator,
 ) -> (
 	string,
 	bool,
 ) {
-	if !utils.is_valid_identifier(category) || !utils.is_valid_identifier(pkg_name) {
-		errors.log_error("Invalid category or package name")
+	url, url_ok := template_url(category, pkg_name, source)
+	if !url_ok {
 		return "", false
 	}
 
-	url := fmt.tprintf("%s/%s/%s/template", TEMPLATE_URL_BASE, category, pkg_name)
-
 	tmpdir := config.get_tmpdir()
 	tmp_path := fmt.tprintf("%s/vuru_tmpl_%s_%d", tmpdir, pkg_name, linux.getpid())
-	defer os.remove(tmp_path)
+	headers_path := fmt.tprintf("%s.headers", tmp_path)
+	utils.temp_file_register(tmp_path)
+	defer utils.temp_file_remove(tmp_path)
+	defer os.remove(headers_path)
+
+	limit_args, limit_ok := utils.download_curl_args(url, context.temp_allocator)
+	if !limit_ok {
+		return "", false
+	}
 
 	// curl to fetch
-	if utils.run_command({"curl", "-s", "-f", "-L", "-o", tmp_path, url}) != 0 {
+	args := make([dynamic]string, context.temp_allocator)
+	append(&args, "curl", "-s", "-f", "-L", "-D", headers_path, "-o", tmp_path)
+	append(&args, ..limit_args)
+	append(&args, url)
+	if utils.run_command(args[:]) != 0 {
 		errors.log_error("Failed to fetch template from %s", url)
+		utils.download_budget_hint()
+		return "", false
+	}
+	utils.download_record_file(tmp_path)
+
+	return read_fetched_template(tmp_path, headers_path, url, allocator)
+}
+
+// Parallel template downloads allowed by default (--max-concurrent). Kept
+// low: GitHub starts rate limiting raw downloads with many connections.
+DEFAULT_MAX_CONCURRENT :: 4
+
+// A template to fetch with fetch_templates
+Template_Request :: struct {
+	category: string,
+	pkg_name: string,
+}
+
+// Fetch several templates in one curl run with at most max_concurrent
+// downloads at a time. Results are in request order. Failed downloads are not
+// retried here; callers refetch them one at a time with fetch_template.
+fetch_templates :: proc(
+	reqs: []Template_Request,
+	source := Template_Source{},
+	max_concurrent := DEFAULT_MAX_CONCURRENT,
+	allocator := context.allocator,
+) -> (
+	contents: []string,
+	ok: []bool,
+) {
+	contents = make([]string, len(reqs), allocator)
+	ok = make([]bool, len(reqs), allocator)
+
+	// The transfers share what is left of the budget; the next run of
+	// downloads sees what these actually took
+	limit_args, limit_ok := utils.download_curl_args("templates", context.temp_allocator, transfers = len(reqs))
+	if !limit_ok {
+		return
+	}
+
+	tmpdir := config.get_tmpdir()
+	paths := make([]string, len(reqs), context.temp_allocator)
+	urls := make([]string, len(reqs), context.temp_allocator)
+
+	args := make([dynamic]string, context.temp_allocator)
+	append(&args, "curl", "--parallel", "--parallel-max", fmt.tprintf("%d", max(max_concurrent, 1)))
+
+	transfers := 0
+	for r, i in reqs {
+		url, url_ok := template_url(r.category, r.pkg_name, source)
+		if !url_ok {
+			continue
+		}
+
+		urls[i] = url
+		paths[i] = fmt.tprintf("%s/vuru_tmpl_%s_%d_%d", tmpdir, r.pkg_name, linux.getpid(), i)
+		utils.temp_file_register(paths[i])
+		utils.temp_file_register(fmt.tprintf("%s.headers", paths[i]))
+
+		// Options after --next apply to the next transfer only
+		if transfers > 0 {
+			append(&args, "--next")
+		}
+		append(&args, "-s", "-f", "-L", "-D", fmt.tprintf("%s.headers", paths[i]), "-o", paths[i])
+		append(&args, ..limit_args)
+		append(&args, url)
+		transfers += 1
+	}
+
+	if transfers == 0 {
+		return
+	}
+
+	// The exit code only tells about one transfer, so check each file instead
+	utils.run_command(args[:])
+
+	for path, i in paths {
+		if len(path) == 0 {
+			continue
+		}
+		headers_path := fmt.tprintf("%s.headers", path)
+		if os.exists(path) {
+			utils.download_record_file(path)
+			contents[i], ok[i] = read_fetched_template(path, headers_path, urls[i], allocator)
+		}
+		utils.temp_file_remove(path)
+		utils.temp_file_remove(headers_path)
+	}
+
+	return
+}
+
+// Read a downloaded template, rejecting HTML and empty responses
+@(private)
+read_fetched_template :: proc(
+	path: string,
+	headers_path: string,
+	url: string,
+	allocator := context.allocator,
+) -> (
+	string,
+	bool,
+) {
+	// Templates are plain text; HTML means something answered in place of the host
+	headers, _ := utils.read_file(headers_path, context.temp_allocator)
+	if content_type := utils.response_content_type(headers); utils.is_html_content_type(content_type) {
+		errors.log_error(
+			"Expected a plain text template but got %s from %s, possibly behind a captive portal",
+			content_type,
+			url,
+		)
 		return "", false
 	}
 
-	content, ok := utils.read_file(tmp_path, allocator)
-	return content, ok
+	content, ok := utils.read_file(path, allocator)
+	if !ok || len(content) == 0 {
+		return "", false
+	}
+	return content, true
 }
@@ -1,7 +1,6 @@
 package transaction
 
 import "core:fmt"
-import "core:os"
 import "core:strings"
 
 import builder "../../core/builder"
@@ -103,43 +102,24 @@ transaction_print :: proc(t: ^Transaction) {
 }
 
 // Execute a transaction
-transaction_execute :: proc(t: ^Transaction, cfg: ^builder.Build_Config, yes: bool) -> bool {
+transaction_execute :: proc(t: ^Transaction, cfg: ^builder.Build_Config, opts: Execute_Options) -> bool {
 	if transaction_is_empty(t) {
 		return true
 	}
 
 	// Group packages by operation type for batch execution
-	VUP_Group :: struct {
-		repo_url: string,
-		pkgs:     [dynamic]string,
-	}
-
 	official_pkgs := make([dynamic]string, context.temp_allocator)
-	vup_groups := make([dynamic]VUP_Group, context.temp_allocator)
+	vup_groups := group_by_repo(t.items[:])
 	remove_pkgs := make([dynamic]string, context.temp_allocator)
 	builds := make([dynamic]^Transaction_Item, context.temp_allocator)
 
 	for &item in t.items {
 		switch item.op {
 		case .Install_Official:
-			append(&official_pkgs, item.name)
+			append(&official_pkgs, item_install_arg(item))
 
 		case .Install_VUP:
-			found := false
-			for &group in vup_groups {
-				if group.repo_url == item.repo_url {
-					append(&group.pkgs, item.name)
-					found = true
-					break
-				}
-			}
-			if !found {
-				append(&vup_groups, VUP_Group{
-					repo_url = item.repo_url,
-					pkgs     = make([dynamic]string, context.temp_allocator),
-				})
-				append(&vup_groups[len(vup_groups) - 1].pkgs, item.name)
-			}
+		// Grouped by repo above
 
 		case .Remove:
 			append(&remove_pkgs, item.name)
@@ -151,13 +131,38 @@ transaction_execute :: proc(t: ^Transaction, cfg: ^builder.Build_Config, yes: bo
 		}
 	}
 
+	// Each batch and build is one step of the progress count
+	total := len(vup_groups) + len(builds)
+	if len(official_pkgs) > 0 {total += 1}
+	if len(remove_pkgs) > 0 {total += 1}
+	step := 0
+
+	// Sync every repo once up front; the installs below then skip -S
+	if len(official_pkgs) > 0 || len(vup_groups) > 0 {
+		total += 1
+		errors.log_info("%sSyncing repositories...", step_prefix(&step, total, opts.progress))
+
+		repo_urls := make([]string, len(vup_groups), context.temp_allocator)
+		for group, i in vup_groups {
+			repo_urls[i] = group.repo_url
+		}
+		if xbps.sync_repos_with(repo_urls, utils.run_command) != 0 {
+			log_step_failure("Failed to sync repositories")
+			return false
+		}
+	}
+
 	// Execute official installs in one batch
 	if len(official_pkgs) > 0 {
-		errors.log_info("Installing %d package(s) from official repos...", len(official_pkgs))
+		errors.log_info(
+			"%sInstalling %s from official repos...",
+			step_prefix(&step, total, opts.progress),
+			strings.join(official_pkgs[:], ", ", context.temp_allocator),
+		)
 
 		args: [dynamic; 64]string
-		append(&args, "sudo", "xbps-install", "-S")
-		if yes {
+		append(&args, "sudo", "xbps-install")
+		if opts.yes {
 			append(&args, "-y")
 		}
 		for pkg in official_pkgs {
@@ -165,28 +170,44 @@ transaction_execute :: proc(t: ^Transaction, cfg: ^builder.Build_Config, yes: bo
 		}
 
 		if utils.run_command(args[:]) != 0 {
-			errors.log_error("Failed to install official packages")
+			log_step_failure("Failed to install official packages")
 			return false
 		}
 	}
 
 	// Execute VUP installs grouped by repo
 	for group in vup_groups {
-		errors.log_info("Installing %d package(s) from VUP...", len(group.pkgs))
-
-		if xbps.install_packages_from_repo(group.repo_url, group.pkgs[:], yes, utils.run_command) != 0 {
-			errors.log_error("Failed to install VUP packages")
+		errors.log_info(
+			"%sInstalling %s from VUP...",
+			step_prefix(&step, total, opts.progress),
+			strings.join(group.pkgs[:], ", ", context.temp_allocator),
+		)
+
+		if xbps.install_packages_from_repo(
+			   group.repo_url,
+			   group.pkgs[:],
+			   opts.yes,
+			   utils.run_command,
+			   ignore_conf_repos = opts.ignore_conf_repos,
+			   sync = false,
+		   ) !=
+		   0 {
+			log_step_failure("Failed to install VUP packages")
 			return false
 		}
 	}
 
 	// Execute removes in one batch
 	if len(remove_pkgs) > 0 {
-		errors.log_info("Removing %d package(s)...", len(remove_pkgs))
+		errors.log_info(
+			"%sRemoving %s...",
+			step_prefix(&step, total, opts.progress),
+			strings.join(remove_pkgs[:], ", ", context.temp_allocator),
+		)
 
 		args: [dynamic; 64]string
 		append(&args, "sudo", "xbps-remove", "-R")
-		if yes {
+		if opts.yes {
 			append(&args, "-y")
 		}
 		for pkg in remove_pkgs {
@@ -194,14 +215,14 @@ transaction_execute :: proc(t: ^Transaction, cfg: ^builder.Build_Config, yes: bo
 		}
 
 		if utils.run_command(args[:]) != 0 {
-			errors.log_error("Failed to remove packages")
+			log_step_failure("Failed to remove packages")
 			return false
 		}
 	}
 
 	// Execute builds individually
 	for item in builds {
-		if !execute_build_install(item, cfg, yes) {
+		if !execute_build_install(item, cfg, opts.yes, step_prefix(&step, total, opts.progress)) {
 			return false
 		}
 	}
@@ -209,9 +230,67 @@ transaction_execute :: proc(t: ^Transaction, cfg: ^builder.Build_Config, yes: bo
 	return true
 }
 
+// VUP install items grouped by repo URL, each repo once, in first-seen order
+@(private)
+group_by_repo :: proc(items: []Transaction_Item) -> []Repo_Group {
+	groups := make([dynamic]Repo_Group, context.temp_allocator)
+	outer: for item in items {
+		if item.op != .Install_VUP {
+			continue
+		}
+		for &group in groups {
+			if group.repo_url == item.repo_url {
+				append(&group.pkgs, item_install_arg(item))
+				continue outer
+			}
+		}
+		group := Repo_Group {
+			repo_url = item.repo_url,
+			pkgs     = make([dynamic]string, context.temp_allocator),
+		}
+		append(&group.pkgs, item_install_arg(item))
+		append(&groups, group)
+	}
+	return groups[:]
+}
+
+// Package argument for xbps-install: the pkgver for exact items, else the name
+@(private)
+item_install_arg :: proc(item: Transaction_Item) -> string {
+	if item.exact {
+		return fmt.tprintf("%s-%s", item.name, item.new_version)
+	}
+	return item.name
+}
+
+// Report a failed xbps step, telling a Ctrl-C apart from an xbps failure
 @(private)
-execute_build_install :: proc(item: ^Transaction_Item, cfg: ^builder.Build_Config, yes: bool) -> bool {
-	errors.log_info("Building %s...", item.name)
+log_step_failure :: proc(message: string) {
+	if utils.command_interrupted() {
+		errors.log_error("Installation interrupted")
+	} else {
+		errors.log_error(message)
+	}
+}
+
+// "[step/total] " for the next step, empty for single-step transactions
+@(private)
+step_prefix :: proc(step: ^int, total: int, enabled: bool) -> string {
+	step^ += 1
+	if !enabled || total < 2 {
+		return ""
+	}
+	return fmt.tprintf("[%d/%d] ", step^, total)
+}
+
+@(private)
+execute_build_install :: proc(
+	item: ^Transaction_Item,
+	cfg: ^builder.Build_Config,
+	yes: bool,
+	prefix: string,
+) -> bool {
+	errors.log_info("%sBuilding %s...", prefix, item.name)
 	
 	if !builder.build_package(cfg, item.name, item.category) {
 		errors.log_error("Failed to build %s", item.name)
@@ -226,22 +305,10 @@ execute_build_install :: proc(item: ^Transaction_Item, cfg: ^builder.Build_Confi
 }
 
 // Confirm transaction with user
-transaction_confirm :: proc(t: ^Transaction) -> bool {
+transaction_confirm :: proc(t: ^Transaction, default_yes := true) -> bool {
 	if transaction_is_empty(t) {
 		return true
 	}
 
-	fmt.print("Proceed? [Y/n] ")
-
-	buf: [100]u8
-	n, _ := os.read(os.stdin, buf[:])
-
-	if n <= 0 {
-		return false
-	}
-
-	input := strings.trim_space(string(buf[:n]))
-	input_lower := strings.to_lower(input, context.temp_allocator)
-
-	return len(input) == 0 || input_lower == "y" || input_lower == "yes"
+	return utils.prompt_yes_no("Proceed?", default_yes)
 }
@@ -20,6 +20,7 @@ Transaction_Item :: struct {
 	repo_url:    string, // For VUP binary installs
 	category:    string, // For VUP packages
 	reason:      string, // "explicit" or "dependency"
+	exact:       bool, // Install exactly new_version (as a pkgver), not the repo's newest
 }
 
 // Complete transaction plan
@@ -28,6 +29,19 @@ Transaction :: struct {
 	allocator: mem.Allocator,
 }
 
+// VUP installs that come from the same repo, installed in one xbps call
+Repo_Group :: struct {
+	repo_url: string,
+	pkgs:     [dynamic]string,
+}
+
+// How transaction_execute runs
+Execute_Options :: struct {
+	yes:               bool, // Pass -y to xbps
+	progress:          bool, // "[2/5]" step prefixes on status messages
+	ignore_conf_repos: bool, // VUP installs consult only the package's repo (xbps-install -i)
+}
+
 // Free all resources in a Transaction_Item
 transaction_item_free :: proc(item: ^Transaction_Item, allocator: mem.Allocator) {
 	if item == nil do return
@@ -1,6 +1,11 @@
 package xbps
 
-// Package installation using xbps-install
+import "core:strings"
+
+import "../../utils"
+
+// Package installation using xbps-install. With ignore_conf_repos (-i) only
+// the given repository is consulted, not the ones in xbps.d.
 
 // Install a package from a specific repository
 install_from_repo :: proc(
@@ -8,24 +13,48 @@ install_from_repo :: proc(
 	pkg_name: string,
 	yes: bool,
 	run_cmd: Command_Runner,
+	ignore_conf_repos := false,
 ) -> int {
 	args := build_args_with_yes(yes, "sudo", "xbps-install", "-R", repo_url, "-S")
-
-
+	if ignore_conf_repos {
+		append(&args, "-i")
+	}
 	append(&args, pkg_name)
 	return run_cmd(args[:])
 }
 
-// Install multiple packages from a specific repository in a single transaction
+// Install multiple packages from a specific repository in a single
+// transaction. Without sync the repo must have been synced already (see
+// sync_repos_with).
 install_packages_from_repo :: proc(
 	repo_url: string,
 	pkg_names: []string,
 	yes: bool,
 	run_cmd: Command_Runner,
+	ignore_conf_repos := false,
+	sync := true,
 ) -> int {
-	args := build_args_with_yes(yes, "sudo", "xbps-install", "-R", repo_url, "-S")
-
+	args := build_args_with_yes(yes, "sudo", "xbps-install", "-R", repo_url)
+	if sync {
+		append(&args, "-S")
+	}
+	if ignore_conf_repos {
+		append(&args, "-i")
+	}
+	for name in pkg_names {
+		append(&args, name)
+	}
+	return run_cmd(args[:])
+}
 
+// Mark installed packages as automatically installed, so they count as
+// orphans once nothing depends on them (xbps-pkgdb -m auto). With rootdir the
+// package database under it is changed, not the host's.
+mark_automatic :: proc(pkg_names: []string, rootdir: string, run_cmd: Command_Runner) -> int {
+	args := build_args_with_yes(false, "sudo", "xbps-pkgdb", "-m", "auto", allocator = context.temp_allocator)
+	if len(rootdir) > 0 {
+		append(&args, "-r", rootdir)
+	}
 	for name in pkg_names {
 		append(&args, name)
 	}
@@ -36,3 +65,77 @@ install_packages_from_repo :: proc(
 sync_repos :: proc(run_cmd: Command_Runner) -> int {
 	return run_cmd({"sudo", "xbps-install", "-S"})
 }
+
+// Sync the configured repos and extra ones in one go, so later installs
+// from them can skip -S
+sync_repos_with :: proc(repo_urls: []string, run_cmd: Command_Runner) -> int {
+	args := make([dynamic]string, 0, 3 + 2 * len(repo_urls), context.temp_allocator)
+	append(&args, "sudo", "xbps-install", "-S")
+	for url in repo_urls {
+		append(&args, "-R", url)
+	}
+	return run_cmd(args[:])
+}
+
+// One package action from an xbps-install dry run (-n)
+Dry_Run_Entry :: struct {
+	pkgver:         string,
+	action:         string, // install, update, configure, remove, hold...
+	arch:           string,
+	repository:     string,
+	installed_size: i64,
+	download_size:  i64,
+}
+
+// Run xbps-install -n and parse the planned transaction.
+// Output lines look like: "<pkgver> <action> <arch> <repository> <installed-size> <download-size>"
+install_dry_run :: proc(
+	repo_url: string,
+	pkg_names: []string,
+	rootdir: string,
+	run_cmd: Command_Runner_Output,
+	allocator := context.allocator,
+) -> (
+	[]Dry_Run_Entry,
+	bool,
+) {
+	args := make([dynamic]string, context.temp_allocator)
+	append(&args, "xbps-install", "-n")
+	if len(repo_url) > 0 {
+		append(&args, "-R", repo_url)
+	}
+	if len(rootdir) > 0 {
+		append(&args, "-r", rootdir)
+	}
+	for name in pkg_names {
+		append(&args, name)
+	}
+
+	output, ok := run_cmd(args[:], context.temp_allocator)
+	if !ok {
+		return nil, false
+	}
+
+	entries := make([dynamic]Dry_Run_Entry, allocator)
+	output_iter := output
+
+	for line in strings.split_lines_iterator(&output_iter) {
+		fields := strings.fields(line, context.temp_allocator)
+		if len(fields) < 2 {
+			continue
+		}
+
+		entry := Dry_Run_Entry {
+			pkgver = strings.clone(fields[0], allocator),
+			action = strings.clone(fields[1], allocator),
+		}
+		if len(fields) > 2 {entry.arch = strings.clone(fields[2], allocator)}
+		if len(fields) > 3 {entry.repository = strings.clone(fields[3], allocator)}
+		if len(fields) > 4 {entry.installed_size = i64(utils.parse_int(fields[4]))}
+		if len(fields) > 5 {entry.download_size = i64(utils.parse_int(fields[5]))}
+
+		append(&entries, entry)
+	}
+
+	return entries[:], true
+}
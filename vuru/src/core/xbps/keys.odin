@@ -0,0 +1,64 @@
+package xbps
+
+import "core:fmt"
+import "core:os"
+import "core:strings"
+
+// Repo signing keys. xbps asks before importing the key of a repo it hasn't
+// seen, and that question can't be answered under -y or without a terminal.
+
+// Where xbps keeps imported repo keys, as <fingerprint>.plist
+XBPS_KEYS_DIR :: "/var/db/xbps/keys"
+
+// Signing key fingerprint of a repo from its locally synced repodata
+// (xbps-query -L). Empty when the repo hasn't been synced yet or is unsigned.
+repo_key_fingerprint :: proc(
+	repo_url: string,
+	run_cmd: Command_Runner_Output,
+	allocator := context.allocator,
+) -> (
+	string,
+	bool,
+) {
+	output, ok := run_cmd(
+		{"xbps-query", "-i", fmt.tprintf("--repository=%s", repo_url), "-L"},
+		context.temp_allocator,
+	)
+	if !ok {
+		return "", false
+	}
+
+	// " 1234 <url> (RSA signed)" followed by indented "Signed-by: ..." and
+	// "<bits> <aa:bb:...>" lines
+	in_repo := false
+	it := output
+	for line in strings.split_lines_iterator(&it) {
+		fields := strings.fields(line, context.temp_allocator)
+		if len(fields) < 2 {
+			continue
+		}
+
+		if strings.trim_right(fields[1], "/") == strings.trim_right(repo_url, "/") {
+			in_repo = true
+			continue
+		}
+		if in_repo && len(fields) == 2 && strings.count(fields[1], ":") >= 15 {
+			return strings.clone(fields[1], allocator), true
+		}
+	}
+
+	return "", true
+}
+
+// Whether the key with this fingerprint has been imported
+repo_key_trusted :: proc(fingerprint: string) -> bool {
+	return os.exists(fmt.tprintf("%s/%s.plist", XBPS_KEYS_DIR, fingerprint))
+}
+
+// Sync a repo, answering yes to importing its signing key
+repo_import_key :: proc(repo_url: string, run_cmd: Command_Runner_Input) -> int {
+	return run_cmd(
+		{"sudo", "xbps-install", "-S", fmt.tprintf("--repository=%s", repo_url)},
+		"y\n",
+	)
+}
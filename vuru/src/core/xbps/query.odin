@@ -1,7 +1,10 @@
 package xbps
 
+import "core:fmt"
 import "core:mem"
+import "core:slice"
 import "core:strings"
+import "core:unicode/utf8"
 
 // Type alias for command runner functions
 
@@ -37,22 +40,109 @@ get_installed_version :: proc(
 	return "", false
 }
 
-// List all installed packages as (name, version) pairs
-list_installed :: proc(
+// Installed packages that depend on pkg_name (xbps-query -X), as pkgvers
+get_reverse_deps :: proc(
+	pkg_name: string,
 	run_cmd: Command_Runner_Output,
 	allocator := context.allocator,
 ) -> (
-	[][2]string,
+	[]string,
 	bool,
 ) {
-	output, ok := run_cmd({"xbps-query", "-l"}, context.temp_allocator)
+	output, ok := run_cmd({"xbps-query", "-X", pkg_name}, context.temp_allocator)
 	if !ok {
 		return nil, false
 	}
 
-	result := make([dynamic][2]string, allocator)
+	result := make([dynamic]string, allocator)
+	output_iter := output
+
+	for line in strings.split_lines_iterator(&output_iter) {
+		pkgver := strings.trim_space(line)
+		if len(pkgver) > 0 {
+			append(&result, strings.clone(pkgver, allocator))
+		}
+	}
+
+	return result[:], true
+}
+
+// Run-time dependency names of a repository package (xbps-query -R -x),
+// version constraints stripped. repo_url adds a repository to the system ones;
+// rootdir (if set) reads the repository configuration of that root.
+get_repo_deps :: proc(
+	pkg: string,
+	repo_url: string,
+	rootdir: string,
+	run_cmd: Command_Runner_Output,
+	allocator := context.allocator,
+) -> (
+	[]string,
+	bool,
+) {
+	args := make([dynamic]string, context.temp_allocator)
+	append(&args, "xbps-query", "-R")
+	if len(repo_url) > 0 {
+		append(&args, fmt.tprintf("--repository=%s", repo_url))
+	}
+	if len(rootdir) > 0 {
+		append(&args, "-r", rootdir)
+	}
+	append(&args, "-x", pkg)
+
+	output, ok := run_cmd(args[:], context.temp_allocator)
+	if !ok {
+		return nil, false
+	}
+
+	result := make([dynamic]string, allocator)
+	output_iter := output
+
+	for line in strings.split_lines_iterator(&output_iter) {
+		dep := strings.trim_space(line)
+		if len(dep) == 0 {
+			continue
+		}
+
+		// "foo>=1.0_1", "foo<2" or "foo-1.0_1"
+		if op := strings.index_any(dep, "<>="); op > 0 {
+			dep = dep[:op]
+		} else if name, _, parse_ok := parse_pkgver_simple(dep); parse_ok && strings.contains(dep, "_") {
+			dep = name
+		}
+		append(&result, strings.clone(dep, allocator))
+	}
+
+	return result[:], true
+}
+
+// Versions of a package the repositories offer (xbps-query -R -s), newest
+// first. repo_url adds a repository to the system ones.
+get_repo_versions :: proc(
+	pkg: string,
+	repo_url: string,
+	run_cmd: Command_Runner_Output,
+	allocator := context.allocator,
+) -> (
+	[]string,
+	bool,
+) {
+	args := make([dynamic]string, context.temp_allocator)
+	append(&args, "xbps-query", "-R")
+	if len(repo_url) > 0 {
+		append(&args, fmt.tprintf("--repository=%s", repo_url))
+	}
+	append(&args, "-s", pkg)
+
+	output, ok := run_cmd(args[:], context.temp_allocator)
+	if !ok {
+		return nil, false
+	}
+
+	result := make([dynamic]string, allocator)
 	output_iter := output
 
+	// "[-] foo-1.0_1    Short description", the search also matches other names
 	for line in strings.split_lines_iterator(&output_iter) {
 		parts := strings.fields(line, context.temp_allocator)
 		if len(parts) < 2 {
@@ -60,6 +150,76 @@ list_installed :: proc(
 		}
 
 		name, version, parse_ok := parse_pkgver(parts[1])
+		if !parse_ok || name != pkg || slice.contains(result[:], version) {
+			continue
+		}
+		append(&result, strings.clone(version, allocator))
+	}
+
+	slice.sort_by(result[:], proc(a, b: string) -> bool {
+		return version_compare(a, b) > 0
+	})
+	return result[:], true
+}
+
+// Version of a package in one repository only (xbps-query -i -R -p pkgver),
+// skipping the system repos. False when the repo doesn't have the package or
+// can't be reached.
+get_repo_version :: proc(
+	pkg: string,
+	repo_url: string,
+	run_cmd: Command_Runner_Output,
+	allocator := context.allocator,
+) -> (
+	string,
+	bool,
+) {
+	repository := fmt.tprintf("--repository=%s", repo_url)
+	output, ok := run_cmd({"xbps-query", "-i", "-R", repository, "-p", "pkgver", pkg}, context.temp_allocator)
+	if !ok {
+		return "", false
+	}
+
+	name, version, parse_ok := parse_pkgver(strings.trim_space(output))
+	if !parse_ok || name != pkg {
+		return "", false
+	}
+	return strings.clone(version, allocator), true
+}
+
+// Whether an installed package was pulled in as a dependency rather than
+// installed explicitly (xbps-query -p automatic-install prints "yes")
+is_automatic_install :: proc(pkg_name: string, run_cmd: Command_Runner_Output) -> (bool, bool) {
+	output, ok := run_cmd({"xbps-query", "-p", "automatic-install", pkg_name}, context.temp_allocator)
+	if !ok {
+		return false, false
+	}
+	return strings.trim_space(output) == "yes", true
+}
+
+// Check whether a package is installed
+is_installed :: proc(pkg_name: string, run_cmd: Command_Runner) -> bool {
+	return run_cmd({"xbps-query", pkg_name}) == 0
+}
+
+// List all installed packages as (name, version) pairs
+list_installed :: proc(
+	run_cmd: Command_Runner_Output,
+	allocator := context.allocator,
+) -> (
+	[][2]string,
+	bool,
+) {
+	output, ok := run_cmd({"xbps-query", "-l"}, context.temp_allocator)
+	if !ok {
+		return nil, false
+	}
+
+	result := make([dynamic][2]string, allocator)
+	output_iter := output
+
+	for line in strings.split_lines_iterator(&output_iter) {
+		name, version, parse_ok := parse_installed_line(line)
 		if parse_ok {
 			append(
 				&result,
@@ -70,3 +230,20 @@ list_installed :: proc(
 
 	return result[:], true
 }
+
+// Name and version from an xbps-query -l line ("ii foo-1.0_1  Short desc").
+// False for malformed lines and for pkgvers that aren't readable text (see
+// pkgver_is_readable), so a mangled name never reaches a version comparison.
+parse_installed_line :: proc(line: string) -> (name: string, version: string, ok: bool) {
+	parts := strings.fields(line, context.temp_allocator)
+	if len(parts) < 2 || !pkgver_is_readable(parts[1]) {
+		return "", "", false
+	}
+	return parse_pkgver(parts[1])
+}
+
+// Whether xbps output is valid UTF-8 without U+FFFD replacement characters,
+// which stand in for bytes a locale or terminal couldn't decode
+pkgver_is_readable :: proc(s: string) -> bool {
+	return utf8.valid_string(s) && strings.index_rune(s, utf8.RUNE_ERROR) < 0
+}
@@ -0,0 +1,49 @@
+package xbps
+
+import "core:testing"
+
+@(test)
+test_parse_installed_line_valid :: proc(t: ^testing.T) {
+	name, version, ok := parse_installed_line("ii vlang-0.4.10_1                  Simple, fast, safe, compiled language")
+	testing.expect(t, ok)
+	testing.expect_value(t, name, "vlang")
+	testing.expect_value(t, version, "0.4.10_1")
+
+	// Dashes in the name
+	name, version, ok = parse_installed_line("ii visual-studio-code-insiders-1.102.0.20250116_1 Code editor")
+	testing.expect(t, ok)
+	testing.expect_value(t, name, "visual-studio-code-insiders")
+	testing.expect_value(t, version, "1.102.0.20250116_1")
+}
+
+@(test)
+test_parse_installed_line_short :: proc(t: ^testing.T) {
+	for line in ([]string{"", "ii", "   ", "ii nodash"}) {
+		_, _, ok := parse_installed_line(line)
+		testing.expectf(t, !ok, "%q should not parse", line)
+	}
+}
+
+@(test)
+test_parse_installed_line_invalid_utf8 :: proc(t: ^testing.T) {
+	line := []u8{'i', 'i', ' ', 'f', 0xff, 'o', '-', '1', '.', '0', '_', '1', ' ', 'd'}
+	_, _, ok := parse_installed_line(string(line))
+	testing.expect(t, !ok)
+	testing.expect(t, !pkgver_is_readable(string(line[3:12])))
+}
+
+@(test)
+test_parse_installed_line_replacement_char :: proc(t: ^testing.T) {
+	// U+FFFD, what a decoder puts in place of bytes it couldn't read
+	line := []u8{'i', 'i', ' ', 'f', 0xef, 0xbf, 0xbd, 'o', '-', '1', '.', '0', '_', '1'}
+	_, _, ok := parse_installed_line(string(line))
+	testing.expect(t, !ok)
+	testing.expect(t, !pkgver_is_readable(string(line[3:])))
+}
+
+@(test)
+test_pkgver_is_readable :: proc(t: ^testing.T) {
+	testing.expect(t, pkgver_is_readable("foo-1.0_1"))
+	testing.expect(t, pkgver_is_readable("gtk+3-3.24.43_1"))
+	testing.expect(t, pkgver_is_readable(""))
+}
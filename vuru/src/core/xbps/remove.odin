@@ -1,14 +1,82 @@
 package xbps
 
+import "core:os"
+import "core:strings"
+
+import "../../utils"
+
 // Package removal using xbps-remove
 
-// Remove a package and its dependencies
-remove_package :: proc(pkg_name: string, yes: bool, run_cmd: Command_Runner) -> int {
-	args := build_args_with_yes(yes, "sudo", "xbps-remove", "-R")
+// List the pkgvers xbps-remove would remove (xbps-remove -n), without root
+remove_dry_run :: proc(
+	pkg_names: []string,
+	clean_deps: bool,
+	rootdir: string,
+	run_cmd: Command_Runner_Output,
+	allocator := context.allocator,
+) -> (
+	[]string,
+	bool,
+) {
+	args := make([dynamic]string, context.temp_allocator)
+	append(&args, "xbps-remove", "-n")
+	if clean_deps {
+		append(&args, "-R")
+	}
+	if len(rootdir) > 0 {
+		append(&args, "-r", rootdir)
+	}
+	for name in pkg_names {
+		append(&args, name)
+	}
 
+	output, ok := run_cmd(args[:], context.temp_allocator)
+	if !ok {
+		return nil, false
+	}
 
-	append(&args, pkg_name)
-	return run_cmd(args[:])
+	pkgvers := make([dynamic]string, allocator)
+	output_iter := output
+
+	for line in strings.split_lines_iterator(&output_iter) {
+		fields := strings.fields(line, context.temp_allocator)
+		if len(fields) < 2 || fields[1] != "remove" {
+			continue
+		}
+		append(&pkgvers, strings.clone(fields[0], allocator))
+	}
+
+	return pkgvers[:], true
+}
+
+// List the pkgvers of orphaned packages (xbps-query -O), without root
+list_orphans :: proc(
+	rootdir: string,
+	run_cmd: Command_Runner_Output,
+	allocator := context.allocator,
+) -> (
+	[]string,
+	bool,
+) {
+	args := make([dynamic]string, context.temp_allocator)
+	append(&args, "xbps-query", "-O")
+	if len(rootdir) > 0 {
+		append(&args, "-r", rootdir)
+	}
+
+	output, ok := run_cmd(args[:], context.temp_allocator)
+	if !ok {
+		return nil, false
+	}
+
+	pkgvers := make([dynamic]string, allocator)
+	output_iter := output
+	for line in strings.split_lines_iterator(&output_iter) {
+		if l := strings.trim_space(line); len(l) > 0 {
+			append(&pkgvers, strings.clone(l, allocator))
+		}
+	}
+	return pkgvers[:], true
 }
 
 // Remove orphaned packages
@@ -23,3 +91,40 @@ remove_orphans :: proc(yes: bool, run_cmd: Command_Runner) -> int {
 clean_cache :: proc(run_cmd: Command_Runner) -> int {
 	return run_cmd({"sudo", "xbps-remove", "-O"})
 }
+
+// Default xbps binary package cache (cachedir in xbps.d)
+XBPS_CACHE_DIR :: "/var/cache/xbps"
+
+// Location of the binpkg cache, inside rootdir if one is given
+cache_dir_path :: proc(rootdir: string, allocator := context.allocator) -> string {
+	if len(rootdir) == 0 {
+		return strings.clone(XBPS_CACHE_DIR, allocator)
+	}
+	return utils.path_join(
+		strings.trim_right(rootdir, "/"),
+		XBPS_CACHE_DIR[1:],
+		allocator = allocator,
+	)
+}
+
+// Number and total size of cached binpkgs (.xbps files, signatures included in size)
+cache_usage :: proc(cache_dir: string) -> (files: int, size: i64, ok: bool) {
+	d, err := os.open(cache_dir)
+	if err != os.ERROR_NONE {
+		return 0, 0, false
+	}
+	defer os.close(d)
+
+	file_infos, _ := os.read_dir(d, -1, context.temp_allocator)
+	for fi in file_infos {
+		if fi.type != .Regular {
+			continue
+		}
+		if strings.has_suffix(fi.name, ".xbps") {
+			files += 1
+		}
+		size += fi.size
+	}
+
+	return files, size, true
+}
@@ -1,6 +1,6 @@
 package xbps
 
-// Package upgrade using xbps-install
+// Package upgrade using xbps-install (ignore_conf_repos: see install.odin)
 
 // Upgrade a specific package from a repository
 upgrade_from_repo :: proc(
@@ -8,10 +8,12 @@ upgrade_from_repo :: proc(
 	pkg_name: string,
 	yes: bool,
 	run_cmd: Command_Runner,
+	ignore_conf_repos := false,
 ) -> int {
 	args := build_args_with_yes(yes, "sudo", "xbps-install", "-R", repo_url, "-Su")
-
-
+	if ignore_conf_repos {
+		append(&args, "-i")
+	}
 	append(&args, pkg_name)
 	return run_cmd(args[:])
 }
@@ -22,16 +24,57 @@ upgrade_packages_from_repo :: proc(
 	pkg_names: []string,
 	yes: bool,
 	run_cmd: Command_Runner,
+	ignore_conf_repos := false,
 ) -> int {
 	args := build_args_with_yes(yes, "sudo", "xbps-install", "-R", repo_url, "-Su")
+	if ignore_conf_repos {
+		append(&args, "-i")
+	}
+	for name in pkg_names {
+		append(&args, name)
+	}
+	return run_cmd(args[:])
+}
 
+// Download the upgrades of multiple packages into the cache without
+// installing anything (xbps-install -D)
+download_packages_from_repo :: proc(
+	repo_url: string,
+	pkg_names: []string,
+	yes: bool,
+	run_cmd: Command_Runner,
+	ignore_conf_repos := false,
+) -> int {
+	args := build_args_with_yes(yes, "sudo", "xbps-install", "-R", repo_url, "-SuD")
+	if ignore_conf_repos {
+		append(&args, "-i")
+	}
+	for name in pkg_names {
+		append(&args, name)
+	}
+	return run_cmd(args[:])
+}
 
+// Upgrade some packages from the system repos; xbps leaves them alone when
+// they are already up to date
+upgrade_official_packages :: proc(pkg_names: []string, yes: bool, dry_run: bool, run_cmd: Command_Runner) -> int {
+	args := build_args_with_yes(yes, "sudo", "xbps-install", "-Su")
+	if dry_run {
+		append(&args, "-n")
+	}
 	for name in pkg_names {
 		append(&args, name)
 	}
 	return run_cmd(args[:])
 }
 
+// Download the upgrades of all packages from official repos into the cache
+// without installing anything (xbps-install -D)
+download_all_official :: proc(yes: bool, run_cmd: Command_Runner) -> int {
+	args := build_args_with_yes(yes, "sudo", "xbps-install", "-SuD")
+	return run_cmd(args[:])
+}
+
 // Upgrade all packages from official repos
 upgrade_all_official :: proc(yes: bool, run_cmd: Command_Runner) -> int {
 	args := build_args_with_yes(yes, "sudo", "xbps-install", "-Su")
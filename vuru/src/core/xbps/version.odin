@@ -1,35 +1,162 @@
 package xbps
 
-// Version comparison using xbps-uhelper
+// Version comparison - native port of the xbps dewey comparison
+// (lib/external/dewey.c), so no xbps-uhelper process is spawned per compare.
 
-// Compare two versions using xbps-uhelper cmpver
-// Returns true if v1 > v2
-version_greater_than :: proc(
-	v1: string,
-	v2: string,
-	run_cmd: proc([]string) -> int,
-) -> bool {
-	if len(v1) == 0 || len(v2) == 0 {
+// Modifier values; "pl", "." and letters separate components like dots do
+@(private)
+DEWEY_ALPHA :: -3
+@(private)
+DEWEY_BETA :: -2
+@(private)
+DEWEY_RC :: -1
+@(private)
+DEWEY_DOT :: 0
+
+@(private)
+Dewey_Modifier :: struct {
+	s:     string,
+	value: int,
+}
+
+@(private)
+DEWEY_MODIFIERS :: [?]Dewey_Modifier {
+	{"alpha", DEWEY_ALPHA},
+	{"beta", DEWEY_BETA},
+	{"pre", DEWEY_RC},
+	{"rc", DEWEY_RC},
+	{"pl", DEWEY_DOT},
+	{".", DEWEY_DOT},
+}
+
+// Parsed version: numeric components plus the "_N" revision, compared last
+@(private)
+Dewey_Version :: struct {
+	parts:    [dynamic]int,
+	revision: int,
+}
+
+@(private)
+dewey_is_digit :: proc(c: u8) -> bool {
+	return c >= '0' && c <= '9'
+}
+
+@(private)
+dewey_lower :: proc(c: u8) -> u8 {
+	return c + ('a' - 'A') if c >= 'A' && c <= 'Z' else c
+}
+
+// Case-insensitive prefix check (strncasecmp in dewey.c)
+@(private)
+dewey_has_prefix :: proc(s: string, prefix: string) -> bool {
+	if len(s) < len(prefix) {
 		return false
 	}
+	for i in 0 ..< len(prefix) {
+		if dewey_lower(s[i]) != prefix[i] {
+			return false
+		}
+	}
+	return true
+}
 
-	// xbps-uhelper cmpver returns:
-	//   0 if v1 == v2
-	//   1 if v1 > v2
-	//  -1 if v1 < v2
-	return run_cmd({"xbps-uhelper", "cmpver", v1, v2}) == 1
+// Read a run of digits starting at i, returns the value and the index after it
+@(private)
+dewey_read_number :: proc(v: string, i: int) -> (n: int, next: int) {
+	next = i
+	for next < len(v) && dewey_is_digit(v[next]) {
+		n = n * 10 + int(v[next] - '0')
+		next += 1
+	}
+	return n, next
 }
 
-// Compare two versions
+@(private)
+dewey_parse :: proc(v: string) -> Dewey_Version {
+	result := Dewey_Version {
+		parts = make([dynamic]int, context.temp_allocator),
+	}
+
+	i := 0
+	outer: for i < len(v) {
+		c := v[i]
+
+		if dewey_is_digit(c) {
+			n: int
+			n, i = dewey_read_number(v, i)
+			append(&result.parts, n)
+			continue
+		}
+
+		if c == '_' {
+			result.revision, i = dewey_read_number(v, i + 1)
+			continue
+		}
+
+		for m in DEWEY_MODIFIERS {
+			if dewey_has_prefix(v[i:], m.s) {
+				append(&result.parts, m.value)
+				i += len(m.s)
+				continue outer
+			}
+		}
+
+		// A lone letter is a dot followed by its position in the alphabet (1.2a = 1.2.0.1)
+		l := dewey_lower(c)
+		if l >= 'a' && l <= 'z' {
+			append(&result.parts, DEWEY_DOT)
+			append(&result.parts, int(l - 'a') + 1)
+		}
+		i += 1
+	}
+
+	return result
+}
+
+// Compare two versions ("1.2.3_1")
+// The revision only decides when the version parts are equal, so 1.2.3_2 > 1.2.3_1
+// and 1.2.4_1 > 1.2.3_9. A missing revision counts as 0, like in xbps.
 // Returns: -1 if v1 < v2, 0 if equal, 1 if v1 > v2
-version_compare :: proc(
-	v1: string,
-	v2: string,
-	run_cmd: proc([]string) -> int,
-) -> int {
-	if len(v1) == 0 || len(v2) == 0 {
-		return 0
+version_compare :: proc(v1: string, v2: string) -> int {
+	a := dewey_parse(v1)
+	b := dewey_parse(v2)
+
+	for i in 0 ..< max(len(a.parts), len(b.parts)) {
+		x := a.parts[i] if i < len(a.parts) else 0
+		y := b.parts[i] if i < len(b.parts) else 0
+		if x != y {
+			return 1 if x > y else -1
+		}
+	}
+
+	if a.revision != b.revision {
+		return 1 if a.revision > b.revision else -1
 	}
+	return 0
+}
+
+// Revision of a version ("1.2.3_2" -> 2), false if it has none
+version_revision :: proc(v: string) -> (int, bool) {
+	for i := len(v) - 1; i >= 0; i -= 1 {
+		if v[i] == '_' {
+			if i + 1 == len(v) {
+				return 0, false
+			}
+			rev, next := dewey_read_number(v, i + 1)
+			return rev, next == len(v)
+		}
+		if !dewey_is_digit(v[i]) {
+			break
+		}
+	}
+	return 0, false
+}
 
-	return run_cmd({"xbps-uhelper", "cmpver", v1, v2})
+// Compare two versions
+// Returns true if v1 > v2
+version_greater_than :: proc(v1: string, v2: string) -> bool {
+	if len(v1) == 0 || len(v2) == 0 {
+		return false
+	}
+	return version_compare(v1, v2) > 0
 }
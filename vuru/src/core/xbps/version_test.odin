@@ -0,0 +1,59 @@
+package xbps
+
+import "core:testing"
+
+@(test)
+test_version_compare_equal :: proc(t: ^testing.T) {
+	testing.expect_value(t, version_compare("1.2.3_1", "1.2.3_1"), 0)
+	testing.expect_value(t, version_compare("1.0", "1.0_0"), 0)
+	testing.expect_value(t, version_compare("1.0", "1.0.0"), 0)
+	testing.expect(t, !version_greater_than("1.2.3_1", "1.2.3_1"))
+}
+
+@(test)
+test_version_compare_newer :: proc(t: ^testing.T) {
+	testing.expect_value(t, version_compare("1.2.4_1", "1.2.3_9"), 1)
+	testing.expect_value(t, version_compare("1.10_1", "1.9_1"), 1)
+	testing.expect_value(t, version_compare("1.0_1", "1.0rc1_1"), 1)
+	testing.expect_value(t, version_compare("1.0rc1_1", "1.0beta2_1"), 1)
+	testing.expect(t, version_greater_than("2.0_1", "1.99_1"))
+}
+
+@(test)
+test_version_compare_regressed :: proc(t: ^testing.T) {
+	// An index that went back must never look like an update
+	testing.expect_value(t, version_compare("1.2.3_1", "1.2.4_1"), -1)
+	testing.expect_value(t, version_compare("1.9_1", "1.10_1"), -1)
+	testing.expect_value(t, version_compare("1.0rc1_1", "1.0_1"), -1)
+	testing.expect(t, !version_greater_than("1.2.3_1", "1.2.4_1"))
+	testing.expect(t, !version_greater_than("", "1.0_1"))
+	testing.expect(t, !version_greater_than("1.0_1", ""))
+}
+
+@(test)
+test_version_compare_revision_bump :: proc(t: ^testing.T) {
+	// Same version, new revision: a rebuild that is still an update
+	testing.expect_value(t, version_compare("1.0_2", "1.0_1"), 1)
+	testing.expect_value(t, version_compare("1.0_1", "1.0_2"), -1)
+	testing.expect_value(t, version_compare("1.0_10", "1.0_9"), 1)
+	testing.expect(t, version_greater_than("1.0_2", "1.0_1"))
+
+	// The version decides before the revision
+	testing.expect_value(t, version_compare("1.1_1", "1.0_2"), 1)
+}
+
+@(test)
+test_version_revision :: proc(t: ^testing.T) {
+	rev, ok := version_revision("1.0_1")
+	testing.expect(t, ok)
+	testing.expect_value(t, rev, 1)
+
+	rev, ok = version_revision("1.2.3_12")
+	testing.expect(t, ok)
+	testing.expect_value(t, rev, 12)
+
+	for v in ([]string{"1.0", "1.0_", "1.0_1a", ""}) {
+		_, ok = version_revision(v)
+		testing.expectf(t, !ok, "%q has no revision", v)
+	}
+}
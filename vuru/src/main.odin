@@ -6,15 +6,18 @@ import "core:os"
 import "core:strings"
 
 import commands "commands"
+import cfg "core/config"
 import errors "core/errors"
+import index "core/index"
+import utils "utils"
 
-VERSION :: "0.6.0"
 INDEX_URL :: "https://vup-linux.github.io/vup/index.json"
 
 // Arena size for command execution (4MB should be plenty)
 ARENA_SIZE :: 4 * 1024 * 1024
 
 main :: proc() {
+	utils.signals_install()
 	exit_code := run()
 	os.exit(exit_code)
 }
@@ -29,15 +32,23 @@ run :: proc() -> int {
 	args := os.args[1:]
 	config := commands.Config {
 		index_url = strings.clone(INDEX_URL),
+		max_download = -1,
 		allocator = context.allocator,
 	}
 	defer commands.config_free(&config)
 
+	// Environment defaults (flags below take precedence)
+	if base := os.get_env("VURU_TEMPLATE_BASE", context.temp_allocator); len(base) > 0 {
+		config.template_base = strings.clone(base)
+	}
+
 	command_name := ""
 	command_args: [dynamic]string
 	defer delete(command_args)
 
 	// Flag parsing loop
+	show_version := false
+	color_mode := errors.Color_Mode.Auto
 	skip_next := false
 	src_cmd_index := -1 // Track where 'src' command appears
 	for arg, i in args {
@@ -53,7 +64,8 @@ run :: proc() -> int {
 			break
 		}
 
-		if strings.has_prefix(arg, "-") {
+		// A bare "-" is an argument (install - reads names from stdin), not a flag
+		if strings.has_prefix(arg, "-") && arg != "-" {
 			if arg == "-h" || arg == "--help" {
 				if command_name == "" {
 					print_help()
@@ -62,28 +74,69 @@ run :: proc() -> int {
 				print_help()
 				return 0
 			} else if arg == "-V" || arg == "--version" {
-				fmt.printf("vuru %s\n", VERSION)
-				return 0
+				show_version = true
 			} else if arg == "-y" || arg == "--yes" {
 				config.yes = true
+			} else if arg == "--default-no" {
+				config.default_no = true
+			} else if arg == "--interactive" {
+				config.interactive = true
+			} else if arg == "--stdin" {
+				config.read_stdin = true
 			} else if arg == "-n" || arg == "--dry-run" {
 				config.dry_run = true
 			} else if arg == "-b" || arg == "--build" {
 				config.force_build = true
 			} else if arg == "--vup-only" {
 				config.vup_only = true
+			} else if arg == "--installed" {
+				config.installed_only = true
+			} else if arg == "--any" {
+				config.search_any = true
+			} else if arg == "--stats" {
+				config.stats = true
+			} else if arg == "--porcelain" {
+				config.porcelain = true
+			} else if arg == "--list-shells" {
+				config.list_shells = true
+			} else if arg == "--json" {
+				config.json = true
+			} else if arg == "--notify" {
+				config.notify = true
+			} else if arg == "--as-dependency" {
+				config.as_dependency = true
+			} else if arg == "--trust-repo" {
+				config.trust_repo = true
+			} else if arg == "--tree" {
+				config.tree = true
+			} else if arg == "--from-repo" {
+				config.ignore_conf_repos = true
+			} else if arg == "--preview" {
+				config.preview = true
+			} else if arg == "--no-review" {
+				config.no_review = true
+			} else if arg == "--confirm-each" {
+				config.confirm_each = true
+			} else if arg == "--keep-diff-files" {
+				config.keep_diff_files = true
 			} else if arg == "-d" || arg == "--desc" {
 				config.description_search = true
 			} else if arg == "-v" || arg == "--verbose" {
 				config.verbose = true
+			} else if arg == "-q" || arg == "--quiet" {
+				config.quiet = true
+			} else if arg == "--quiet-if-unchanged" {
+				config.quiet_unchanged = true
 			} else if arg == "-S" || arg == "--sync" {
 				config.sync = true
 			} else if arg == "-u" || arg == "--update" {
 				config.update_system = true
-			} else if arg == "-R" || arg == "--recursive" {
+			} else if arg == "-R" || arg == "--recursive" || arg == "--clean-deps" {
 				config.recursive = true
 			} else if arg == "-o" || arg == "--orphans" {
 				config.orphans = true
+			} else if arg == "--clean-orphans" {
+				config.clean_orphans = true
 			} else if arg == "-O" || arg == "--clean-cache" {
 				config.clean_cache = true
 			} else if arg == "-l" || arg == "--list" {
@@ -94,11 +147,191 @@ run :: proc() -> int {
 				config.show_deps = true
 			} else if arg == "--ownedby" {
 				config.ownedby = true
+			} else if arg == "--prune-cache" {
+				config.prune_cache = true
+			} else if arg == "--force-cache-rebuild" {
+				config.rebuild_cache = true
+			} else if arg == "--binpkgs" {
+				config.clean_binpkgs = true
+			} else if arg == "--all" {
+				config.clean_all = true
+			} else if arg == "--no-templates" {
+				config.no_templates = true
+			} else if arg == "--no-index" {
+				config.no_index = true
+			} else if arg == "--no-binpkgs" {
+				config.no_binpkgs = true
+			} else if arg == "--test" {
+				config.repo_test = true
 			} else if arg == "-r" || arg == "--rootdir" {
 				if i + 1 < len(args) {
 					config.rootdir = strings.clone(args[i + 1])
 					skip_next = true
 				}
+			} else if arg == "--template-ref" {
+				if i + 1 < len(args) {
+					if !utils.is_valid_git_ref(args[i + 1]) {
+						errors.log_error("Invalid template ref: %s", args[i + 1])
+						return 1
+					}
+					config.template_ref = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--arch" {
+				if i + 1 < len(args) {
+					if !cfg.is_known_arch(args[i + 1]) {
+						errors.log_error("Unknown architecture: %s", args[i + 1])
+						fmt.printf("Known architectures: %s\n", strings.join(cfg.XBPS_ARCHS, ", ", context.temp_allocator))
+						return 1
+					}
+					if len(config.arch) > 0 {
+						delete(config.arch)
+					}
+					config.arch = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--env" {
+				if i + 1 < len(args) {
+					if _, _, env_ok := utils.parse_env_assignment(args[i + 1]); !env_ok {
+						errors.log_error("Invalid --env: %s (expected KEY=VALUE)", args[i + 1])
+						return 1
+					}
+					append(&config.env_vars, strings.clone(args[i + 1]))
+					skip_next = true
+				}
+			} else if arg == "--repo-only" {
+				if i + 1 < len(args) {
+					config.repo_only = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--output" || arg == "--out" {
+				if i + 1 < len(args) {
+					config.output_path = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--from-file" {
+				if i + 1 < len(args) {
+					config.template_file = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--save-to" {
+				if i + 1 < len(args) {
+					config.save_to = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--apply" {
+				if i + 1 < len(args) {
+					config.apply_plan = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--max-concurrent" {
+				if i + 1 < len(args) {
+					n, n_ok := utils.parse_count(args[i + 1])
+					if !n_ok || n < 1 {
+						errors.log_error("Invalid --max-concurrent: %s (expected a positive number)", args[i + 1])
+						return 1
+					}
+					config.max_concurrent = n
+					skip_next = true
+				}
+			} else if arg == "--max-download" {
+				if i + 1 < len(args) {
+					size, size_ok := utils.parse_size(args[i + 1])
+					if !size_ok {
+						errors.log_error("Invalid --max-download: %s (expected a size such as 500K, 20M or 1G)", args[i + 1])
+						return 1
+					}
+					config.max_download = size
+					skip_next = true
+				}
+			} else if arg == "--group-by-category" {
+				config.search_group = true
+			} else if arg == "--available-updates" {
+				config.available_updates = true
+			} else if arg == "--repo-version" {
+				config.repo_version = true
+			} else if arg == "--max-per-category" {
+				if i + 1 < len(args) {
+					n := utils.parse_int(args[i + 1])
+					if n < 1 {
+						errors.log_error("Invalid --max-per-category: %s (expected a positive number)", args[i + 1])
+						return 1
+					}
+					config.max_per_category = n
+					skip_next = true
+				}
+			} else if arg == "--repo-priority" {
+				if i + 1 < len(args) {
+					if !index.is_valid_repo_priority(args[i + 1]) {
+						errors.log_error("Invalid --repo-priority: %s (expected a non-negative number)", args[i + 1])
+						return 1
+					}
+					config.repo_priority = utils.parse_int(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--sort" {
+				if i + 1 < len(args) {
+					config.search_sort = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--print-repo" {
+				config.print_repo = true
+			} else if arg == "--select-version" {
+				config.select_version = true
+			} else if arg == "--idempotent" {
+				config.idempotent = true
+			} else if arg == "--verify-installed" {
+				config.verify_installed = true
+			} else if arg == "--download-first" {
+				config.download_first = true
+			} else if arg == "--allow-insecure-repo" {
+				config.allow_insecure = true
+			} else if arg == "--new-since" {
+				if i + 1 < len(args) {
+					if !utils.is_valid_date(args[i + 1]) {
+						errors.log_error("Invalid --new-since: %s (expected YYYY-MM-DD)", args[i + 1])
+						return 1
+					}
+					config.new_since = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--license" {
+				if i + 1 < len(args) {
+					config.license = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--since" {
+				if i + 1 < len(args) {
+					config.since = strings.clone(args[i + 1])
+					skip_next = true
+				}
+			} else if arg == "--simulate" {
+				config.simulate = true
+			} else if arg == "--color" || strings.has_prefix(arg, "--color=") {
+				// Both "--color never" and "--color=never", as ls and git take it
+				value := arg[len("--color="):] if len(arg) > len("--color") else ""
+				if len(value) == 0 {
+					if i + 1 >= len(args) {
+						errors.log_error("--color needs a value: auto, always or never")
+						return 1
+					}
+					value = args[i + 1]
+					skip_next = true
+				}
+				mode, mode_ok := errors.parse_color_mode(value)
+				if !mode_ok {
+					errors.log_error("Invalid --color: %s (expected auto, always or never)", value)
+					return 1
+				}
+				color_mode = mode
+			} else if arg == "--template-base" {
+				if i + 1 < len(args) {
+					if len(config.template_base) > 0 {
+						delete(config.template_base)
+					}
+					config.template_base = strings.clone(args[i + 1])
+					skip_next = true
+				}
 			} else if strings.has_prefix(arg, "-") && len(arg) > 1 && arg[1] != '-' {
 				// Short flags combined (e.g., -Sy, -Ryn)
 				for c in arg[1:] {
@@ -113,6 +346,8 @@ run :: proc() -> int {
 						config.description_search = true
 					case 'v':
 						config.verbose = true
+					case 'q':
+						config.quiet = true
 					case 'S':
 						config.sync = true
 					case 'u':
@@ -129,6 +364,8 @@ run :: proc() -> int {
 						config.show_files = true
 					case 'x':
 						config.show_deps = true
+					case 'V':
+						show_version = true
 					case 'h':
 						print_help()
 						return 0
@@ -147,26 +384,91 @@ run :: proc() -> int {
 		}
 	}
 
+	errors.set_colors(colors_wanted(color_mode))
+
+	// Path flags get "~" and $VAR expanded like the shell would, so quoted
+	// values ("~/chroot") work too
+	for path in ([]^string{&config.rootdir, &config.output_path, &config.apply_plan, &config.template_file, &config.save_to}) {
+		if strings.contains_any(path^, "~$") {
+			expanded := utils.expand_path(path^)
+			delete(path^)
+			path^ = expanded
+		}
+	}
+
+	// Validated here so a bad VURU_TEMPLATE_BASE is reported like a bad flag
+	if len(config.template_base) > 0 && !index.is_valid_url(config.template_base) {
+		errors.log_error("Invalid template base URL: %s", config.template_base)
+		return 1
+	}
+
+	// Foreign-arch installs: the xbps commands get XBPS_TARGET_ARCH, and
+	// get_arch() reports the target so index repo URLs match it
+	if len(config.arch) > 0 {
+		if !utils.command_env_set("XBPS_TARGET_ARCH", config.arch) {
+			errors.log_error("Failed to set XBPS_TARGET_ARCH")
+			return 1
+		}
+		cfg.set_target_arch(config.arch)
+	}
+	for assignment in config.env_vars {
+		key, value, _ := utils.parse_env_assignment(assignment)
+		if !utils.command_env_set(key, value) {
+			errors.log_error("Failed to set %s (at most %d --env variables)", key, utils.MAX_COMMAND_ENV)
+			return 1
+		}
+	}
+
+	errors.quiet = config.quiet
+
+	// Metered connections: cap index and template downloads, and say what
+	// this run fetched
+	if config.max_download >= 0 {
+		utils.download_set_budget(config.max_download)
+	}
+	defer if (config.verbose || utils.download_budget_set()) && utils.download_total_bytes() > 0 {
+		errors.log_info("Downloaded %s", utils.format_size(utils.download_total_bytes(), context.temp_allocator))
+	}
+
+	// -u goes through install (see route_update_system), which also picks up
+	// -y and non-interactive mode below, so "vuru -y -u" runs unattended end
+	// to end
+	command_name = route_update_system(command_name, &command_args, config.update_system)
+
+	// Flags of one command are parsed with the global ones; given to any
+	// other command they are refused instead of silently ignored
+	if flag, owner := misplaced_command_flag(command_name, &config); len(flag) > 0 {
+		errors.log_error("%s is an option of 'vuru %s' only", flag, owner)
+		return 1
+	}
+
+	// Nobody can answer prompts in CI or from a pipe: behave as if -y was given
+	switch command_name {
+	case "install", "i", "remove", "r", "uninstall", "update", "upgrade", "u", "apply":
+		if !config.yes && !config.interactive && !config.dry_run {
+			if reason, non_interactive := utils.non_interactive_reason(); non_interactive {
+				errors.log_info("Non-interactive mode detected (%s), assuming --yes", reason)
+				config.yes = true
+			}
+		}
+	}
+
+	// Repair path for a corrupt cache: whatever runs next fetches from scratch
+	if config.rebuild_cache {
+		index.index_cache_remove_all()
+		errors.log_info("Removed cached index files")
+		if len(command_name) == 0 {
+			return run_with_arena(commands.sync_run, command_args[:], &config)
+		}
+	}
+
+	// -V is handled after parsing so that -v can still extend the output
+	if show_version {
+		return run_with_arena(commands.version_run, command_args[:], &config)
+	}
+
 	// Dispatch with arena allocator for automatic cleanup
 	switch command_name {
-	case "query", "q", "info", "show":
-		return run_with_arena(commands.query_run, command_args[:], &config)
-	case "search", "s":
-		return run_with_arena(commands.search_run, command_args[:], &config)
-	case "install", "i":
-		return run_with_arena(commands.install_run, command_args[:], &config)
-	case "remove", "r", "uninstall":
-		return run_with_arena(commands.remove_run, command_args[:], &config)
-	case "update", "upgrade", "u":
-		return run_with_arena(commands.update_run, command_args[:], &config)
-	case "build":
-		return run_with_arena(commands.build_run, command_args[:], &config)
-	case "clone":
-		return run_with_arena(commands.clone_run, command_args[:], &config)
-	case "sync":
-		return run_with_arena(commands.sync_run, command_args[:], &config)
-	case "fetch":
-		return run_with_arena(commands.fetch_run, command_args[:], &config)
 	case "src":
 		// Pass raw args after 'src' command (bypass vuru's flag parsing)
 		if src_cmd_index >= 0 && src_cmd_index + 1 < len(args) {
@@ -190,14 +492,10 @@ run :: proc() -> int {
 			errors.print_flag_error("-o/--orphans", "remove", "vuru remove -o")
 			return 1
 		}
-		if config.sync && !config.update_system {
+		if config.sync {
 			errors.print_flag_error("-S/--sync", "install", "vuru install -S")
 			return 1
 		}
-		if config.update_system {
-			errors.print_flag_error("-u/--update", "install", "vuru install -Su")
-			return 1
-		}
 		if config.list_pkgs {
 			errors.print_flag_error("-l/--list", "query", "vuru query -l")
 			return 1
@@ -217,6 +515,9 @@ run :: proc() -> int {
 		print_help()
 		return 1
 	case:
+		if run, ok := command_lookup(command_name); ok {
+			return run_with_arena(run, command_args[:], &config)
+		}
 		errors.print_error(errors.make_error(.Unknown_Command, command_name))
 		return 1
 	}
@@ -224,6 +525,104 @@ run :: proc() -> int {
 	return 0
 }
 
+// One command of the dispatch table: its names (aliases included) and its
+// proc. "help" is handled by main itself, so it has no proc.
+Command_Entry :: struct {
+	names: []string,
+	run:   proc(_: []string, _: ^commands.Config) -> int,
+}
+
+// Every command vuru knows. Dispatch and is_command_name both go by this
+// table, so a command added here is never mistaken for a package name.
+COMMAND_TABLE := []Command_Entry {
+	{{"query", "q", "info", "show"}, commands.query_run},
+	{{"search", "s"}, commands.search_run},
+	{{"install", "i"}, commands.install_run},
+	{{"remove", "r", "uninstall"}, commands.remove_run},
+	{{"update", "upgrade", "u"}, commands.update_run},
+	{{"build"}, commands.build_run},
+	{{"clone"}, commands.clone_run},
+	{{"sync"}, commands.sync_run},
+	{{"fetch"}, commands.fetch_run},
+	{{"clean"}, commands.clean_run},
+	{{"src"}, commands.src_run},
+	{{"version"}, commands.version_run},
+	{{"completion"}, commands.completion_run},
+	{{"gen-completion-cache"}, commands.gen_completion_cache_run},
+	{{"revdeps"}, commands.revdeps_run},
+	{{"why"}, commands.why_run},
+	{{"list-packages"}, commands.list_packages_run},
+	{{"index-stats"}, commands.index_stats_run},
+	{{"check"}, commands.check_run},
+	{{"pin"}, commands.pin_run},
+	{{"unpin"}, commands.unpin_run},
+	{{"apply"}, commands.apply_run},
+	{{"repo"}, commands.repo_run},
+	{{"history"}, commands.history_run},
+	{{"tui"}, commands.tui_run},
+	{{"watch"}, commands.watch_run},
+	{{"help"}, nil},
+}
+
+// The proc of a command or alias; ok is false for unknown words
+command_lookup :: proc(name: string) -> (run: proc(_: []string, _: ^commands.Config) -> int, ok: bool) {
+	for entry in COMMAND_TABLE {
+		for n in entry.names {
+			if n == name {
+				return entry.run, true
+			}
+		}
+	}
+	return nil, false
+}
+
+// Whether a word is one of the commands dispatched in main
+is_command_name :: proc(name: string) -> bool {
+	_, ok := command_lookup(name)
+	return ok
+}
+
+// The first flag given that belongs to a command other than command_name,
+// and that command; "" when there is none
+misplaced_command_flag :: proc(command_name: string, config: ^commands.Config) -> (flag: string, owner: string) {
+	Command_Flag :: struct {
+		flag:  string,
+		set:   bool,
+		owner: string,
+	}
+	flags := []Command_Flag {
+		{"--all", config.clean_all, "clean"},
+		{"--no-templates", config.no_templates, "clean"},
+		{"--no-index", config.no_index, "clean"},
+		{"--no-binpkgs", config.no_binpkgs, "clean"},
+		{"--test", config.repo_test, "repo"},
+	}
+	for f in flags {
+		if f.set && command_name != f.owner {
+			return f.flag, f.owner
+		}
+	}
+	return "", ""
+}
+
+// "vuru -u foo bar" updates just those packages: a first word that isn't a
+// command is then a package, moved in front of the arguments of install.
+// Returns the command to run.
+route_update_system :: proc(command_name: string, command_args: ^[dynamic]string, update_system: bool) -> string {
+	if !update_system {
+		return command_name
+	}
+	// Bare -u/-Su is a full system upgrade, as with xbps-install
+	if len(command_name) == 0 {
+		return "install"
+	}
+	if !is_command_name(command_name) {
+		inject_at(command_args, 0, command_name)
+		return "install"
+	}
+	return command_name
+}
+
 // Run a command with arena allocator - all allocations freed when command returns
 run_with_arena :: proc(
 	command: proc(_: []string, _: ^commands.Config) -> int,
@@ -242,6 +641,21 @@ run_with_arena :: proc(
 	return command(args, config)
 }
 
+// Resolve --color: auto colors a terminal unless NO_COLOR is set (no-color.org)
+colors_wanted :: proc(mode: errors.Color_Mode) -> bool {
+	switch mode {
+	case .Always:
+		return true
+	case .Never:
+		return false
+	case .Auto:
+	}
+	if len(os.get_env("NO_COLOR", context.temp_allocator)) > 0 {
+		return false
+	}
+	return utils.isatty(1) == 1
+}
+
 print_help :: proc() {
 	fmt.println("Usage: vuru <command> [options] [arguments]")
 	fmt.println()
@@ -250,13 +664,29 @@ print_help :: proc() {
 	fmt.println("Commands:")
 	fmt.println("  query    <pkg>         Show package info (default), or use modes below")
 	fmt.println("  install  <pkg...>      Install packages (VUP + official)")
+	fmt.println("  tui      [query]       Browse, mark and install packages (needs a TUI=1 build)")
+	fmt.println("  watch    [interval]    Notify about VUP updates every 6h/interval (needs a WATCH=1 build)")
 	fmt.println("  remove   <pkg...>      Remove packages")
-	fmt.println("  update                 Update all packages")
+	fmt.println("  revdeps  <pkg...>      Show installed packages depending on a package")
+	fmt.println("  why      <pkg...>      Explain why a package is installed")
+	fmt.println("  list-packages          Print all index package names (--json: one object per line)")
+	fmt.println("  index-stats            Check the index: counts per category, repo URLs, bad entries")
+	fmt.println("  check    [pkg...]      Report installed VUP packages with changed upstream templates")
+	fmt.println("  pin      [pkg...]      Hold packages back from update (no args: list)")
+	fmt.println("  unpin    <pkg...>      Allow pinned packages to be updated again")
+	fmt.println("  apply    <manifest>    Install the packages a TOML manifest lists (-n: only show the diff)")
+	fmt.println("  history                Show what vuru installed/upgraded/removed (--since 7d, --json)")
+	fmt.println("  update                 Update all packages (-n: list pending VUP updates)")
 	fmt.println("  build    <pkg...>      Build packages from source")
-	fmt.println("  sync                   Sync repository index")
+	fmt.println("  sync                   Sync repository index (-n: check for an update)")
+	fmt.println("  repo     <cmd>         Manage overlay repos (list, add, remove)")
 	fmt.println("  fetch    <url...>      Download files from URLs")
+	fmt.println("  clean                  Clean vuru caches (see 'vuru clean')")
 	fmt.println("  clone                  Clone/update VUP repository")
 	fmt.println("  src      <cmd> [args]  Run xbps-src with VUP deps")
+	fmt.println("  version                Show version (-v adds index details)")
+	fmt.println("  completion <shell>     Print bash/zsh/fish completion (--output <file>, --list-shells)")
+	fmt.println("  gen-completion-cache   Cache package names for offline completion")
 	fmt.println("  help                   Show this help")
 	fmt.println()
 	fmt.println("Query modes:")
@@ -268,20 +698,70 @@ print_help :: proc() {
 	fmt.println()
 	fmt.println("Install/Remove flags:")
 	fmt.println("  -S, --sync       Sync repos before operation")
-	fmt.println("  -u, --update     Update mode (system upgrade)")
+	fmt.println("  -u, --update     System upgrade, official + VUP (vuru -Syu, -y: unattended);")
+	fmt.println("                   with packages (vuru -u foo) only updates those")
 	fmt.println("  -R, --recursive  Recursive remove/deps")
+	fmt.println("  --clean-deps     remove: also remove deps no longer needed (same as -R)")
 	fmt.println("  -o, --orphans    Remove orphan packages")
 	fmt.println("  -O, --clean-cache  Clean package cache")
+	fmt.println("  --preview        Show templates and xbps dry run, then ask (even with -y)")
+	fmt.println("  --no-review      install: fetch no templates, let xbps resolve VUP dependencies")
+	fmt.println("  --confirm-each   install: continue/skip/abort before fetching, showing and installing")
+	fmt.println("  --stdin          install: read package names from stdin (or use '-')")
+	fmt.println("  --keep-diff-files  Keep old/new templates of reviews in TMPDIR")
 	fmt.println()
 	fmt.println("General options:")
 	fmt.println("  -y, --yes        Skip confirmations")
+	fmt.println("  --default-no     Treat an empty answer as \"no\" (remove always does)")
+	fmt.println("  --interactive    Prompt even in CI or without a terminal (default: assume -y)")
 	fmt.println("  -n, --dry-run    Show what would be done")
 	fmt.println("  -b, --build      Force build from source")
 	fmt.println("  -d, --desc       Include descriptions in search")
 	fmt.println("  -v, --verbose    Verbose output")
+	fmt.println("  -q, --quiet      Only print warnings and errors")
+	fmt.println("  --quiet-if-unchanged  sync: print nothing unless the index changed (for cron)")
 	fmt.println("  -r, --rootdir    Alternate root directory")
+	fmt.println("  --arch <arch>    Target architecture (XBPS_TARGET_ARCH), e.g. for a foreign rootfs")
+	fmt.println("  --env KEY=VALUE  Environment variable for the xbps-* commands only (repeatable)")
+	fmt.println("  --template-ref <ref>  Review templates from a VUP branch, tag or commit")
+	fmt.println("  --template-base <url> Raw template host of a fork/mirror (VURU_TEMPLATE_BASE)")
 	fmt.println("  --vup-only       VUP packages only")
-	fmt.println("  -V, --version    Show version")
+	fmt.println("  --installed      search: installed packages only")
+	fmt.println("  --any            search: match any of the words instead of all")
+	fmt.println("  --repo-only <name>  search: only packages of one repo (vup = main index)")
+	fmt.println("  --repo-priority <n> repo add: higher wins when repos share a package (main: 0)")
+	fmt.println("  --porcelain      Stable tab-separated output (search, update -n, revdeps, why, check)")
+	fmt.println("  --stats          update: show pending upgrades per category")
+	fmt.println("  --simulate       update/remove: write the upgrade/removal plan as JSON (to --out <file>)")
+	fmt.println("  --apply <file>   update: install exactly the upgrades of a saved plan")
+	fmt.println("  --since <dur>    history: only entries newer than e.g. 30m, 12h, 7d, 2w")
+	fmt.println("  --sort <key>     search: order by relevance (default), version or name")
+	fmt.println("  --group-by-category  search: VUP results under a header per category")
+	fmt.println("  --available-updates  search: installed VUP matches with installed/index version")
+	fmt.println("  --repo-version   search/info: compare index versions with the repos (slow, queries each repo)")
+	fmt.println("  --max-per-category <n>  search: show at most n results per category (groups)")
+	fmt.println("  --license <text>  search: VUP packages whose license contains text (e.g. GPL)")
+	fmt.println("  --new-since <date>  search/list-packages: packages added or changed since YYYY-MM-DD")
+	fmt.println("  --max-concurrent <n>  Parallel template downloads for update/check (default 4)")
+	fmt.println("  --max-download <size> Stop index/template downloads past this total (e.g. 20M)")
+	fmt.println("  --color <when>   Colored output: auto (default, only on a terminal), always, never")
+	fmt.println("  --json           history/index-stats: JSON output; list-packages: NDJSON")
+	fmt.println("  --notify         Desktop notification when install/update finishes")
+	fmt.println("  --as-dependency  install: mark packages as dependencies (removable as orphans)")
+	fmt.println("  --trust-repo     install: import the signing key of VUP repos without asking")
+	fmt.println("  --tree           install: show the dependency tree before confirming")
+	fmt.println("  --print-repo     install: print version and repo URL of VUP packages, install nothing")
+	fmt.println("  --select-version install: choose among the versions the package's repo offers")
+	fmt.println("  --idempotent     install: skip packages already at the index version, exit 0 if all are")
+	fmt.println("  --verify-installed  install: warn if a VUP package isn't installed at the index version")
+	fmt.println("  --from-file <f>  install: review a local template instead of the upstream one")
+	fmt.println("  --save-to <dir>  install: archive installed templates as <pkg>-<version>-<date>.template")
+	fmt.println("  --from-repo      install/update: fetch VUP packages only from their index repo")
+	fmt.println("  --download-first update: download all upgrades, official and VUP, before installing any")
+	fmt.println("  --allow-insecure-repo  install/update: accept plain http:// repos without asking")
+	fmt.println("  --clean-orphans  remove: also remove orphaned packages afterwards")
+	fmt.println("  --force-cache-rebuild  Delete cached indexes and fetch them again")
+	fmt.println("  -V, --version    Show version (with -v: index URL, size, last sync)")
 	fmt.println("  -h, --help       Show help")
 	fmt.println()
 	fmt.println("Aliases: q=query, s=search, i=install, r=remove, u=update")
@@ -0,0 +1,81 @@
+package main
+
+import "core:testing"
+
+import commands "commands"
+
+@(test)
+test_route_update_system_packages :: proc(t: ^testing.T) {
+	args := make([dynamic]string, context.temp_allocator)
+	append(&args, "bar")
+
+	// vuru -u foo bar
+	testing.expect_value(t, route_update_system("foo", &args, true), "install")
+	testing.expect_value(t, len(args), 2)
+	testing.expect_value(t, args[0], "foo")
+	testing.expect_value(t, args[1], "bar")
+}
+
+@(test)
+test_route_update_system_bare :: proc(t: ^testing.T) {
+	args := make([dynamic]string, context.temp_allocator)
+
+	// vuru -u
+	testing.expect_value(t, route_update_system("", &args, true), "install")
+	testing.expect_value(t, len(args), 0)
+}
+
+@(test)
+test_route_update_system_keeps_commands :: proc(t: ^testing.T) {
+	// Every command and alias stays a command after -u, none becomes a package
+	for entry in COMMAND_TABLE {
+		for name in entry.names {
+			args := make([dynamic]string, context.temp_allocator)
+			append(&args, "foo")
+			testing.expect_value(t, route_update_system(name, &args, true), name)
+			testing.expect_value(t, len(args), 1)
+		}
+	}
+}
+
+@(test)
+test_route_update_system_without_u :: proc(t: ^testing.T) {
+	args := make([dynamic]string, context.temp_allocator)
+	testing.expect_value(t, route_update_system("foo", &args, false), "foo")
+	testing.expect_value(t, route_update_system("", &args, false), "")
+	testing.expect_value(t, len(args), 0)
+}
+
+@(test)
+test_command_lookup :: proc(t: ^testing.T) {
+	testing.expect(t, is_command_name("install"))
+	testing.expect(t, is_command_name("i"))
+	testing.expect(t, is_command_name("help"))
+	testing.expect(t, !is_command_name("vlang"))
+	testing.expect(t, !is_command_name(""))
+
+	run, ok := command_lookup("help")
+	testing.expect(t, ok && run == nil)
+}
+
+@(test)
+test_misplaced_command_flag :: proc(t: ^testing.T) {
+	config: commands.Config
+	flag, owner := misplaced_command_flag("install", &config)
+	testing.expect_value(t, flag, "")
+
+	config.clean_all = true
+	flag, owner = misplaced_command_flag("clean", &config)
+	testing.expect_value(t, flag, "")
+	flag, owner = misplaced_command_flag("install", &config)
+	testing.expect_value(t, flag, "--all")
+	testing.expect_value(t, owner, "clean")
+
+	config = {}
+	config.repo_test = true
+	flag, _ = misplaced_command_flag("repo", &config)
+	testing.expect_value(t, flag, "")
+	flag, owner = misplaced_command_flag("sync", &config)
+	testing.expect_value(t, flag, "--test")
+	testing.expect_value(t, owner, "repo")
+}
@@ -0,0 +1,78 @@
+package utils
+
+import "core:fmt"
+import "core:strings"
+
+// Extra environment for spawned xbps commands (e.g. XBPS_TARGET_ARCH for
+// --arch). Only xbps-* commands get it, as "env K=V xbps-..." or through sudo
+// (which resets the environment) as "sudo env K=V xbps-...". curl, git and
+// xbps-src run with vuru's own environment.
+MAX_COMMAND_ENV :: 8
+
+@(private)
+command_env: [dynamic; MAX_COMMAND_ENV][2]string
+
+// Set a variable for all xbps commands spawned from now on
+command_env_set :: proc(key: string, value: string) -> bool {
+	for &kv in command_env {
+		if kv[0] == key {
+			kv[1] = value
+			return true
+		}
+	}
+
+	if len(command_env) == MAX_COMMAND_ENV {
+		return false
+	}
+	append(&command_env, [2]string{key, value})
+	return true
+}
+
+// Rewrite "[sudo] xbps-<cmd> ..." to "[sudo] env K=V xbps-<cmd> ..." when
+// extra env is set; other commands are left alone
+@(private)
+command_argv_with_env :: proc(args: []string, allocator := context.allocator) -> []string {
+	if len(command_env) == 0 || len(args) == 0 {
+		return args
+	}
+
+	cmd_start := 1 if args[0] == "sudo" else 0
+	if cmd_start >= len(args) || !is_xbps_command(args[cmd_start]) {
+		return args
+	}
+
+	result := make([dynamic]string, 0, len(args) + len(command_env) + 1, allocator)
+	append(&result, ..args[:cmd_start])
+	append(&result, "env")
+	for kv in command_env {
+		append(&result, fmt.aprintf("%s=%s", kv[0], kv[1], allocator = allocator))
+	}
+	append(&result, ..args[cmd_start:])
+	return result[:]
+}
+
+// Whether a program is one of the xbps-* package tools (by name or path).
+// xbps-src is a build script, not one of them.
+@(private)
+is_xbps_command :: proc(program: string) -> bool {
+	name := program[strings.last_index_byte(program, '/') + 1:]
+	return strings.has_prefix(name, "xbps-") && name != "xbps-src"
+}
+
+// Split a KEY=VALUE assignment (--env). Keys are shell variable names; the
+// value may be empty.
+parse_env_assignment :: proc(s: string) -> (key: string, value: string, ok: bool) {
+	eq := strings.index_byte(s, '=')
+	if eq <= 0 {
+		return "", "", false
+	}
+
+	key = s[:eq]
+	for c, i in key {
+		valid := (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c == '_' || (i > 0 && c >= '0' && c <= '9')
+		if !valid {
+			return "", "", false
+		}
+	}
+	return key, s[eq + 1:], true
+}
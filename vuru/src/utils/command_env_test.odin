@@ -0,0 +1,24 @@
+package utils
+
+import "core:testing"
+
+@(test)
+test_command_argv_with_env :: proc(t: ^testing.T) {
+	testing.expect(t, command_env_set("XBPS_TARGET_ARCH", "aarch64"))
+	defer clear(&command_env)
+
+	args := command_argv_with_env([]string{"sudo", "xbps-install", "-S"}, context.temp_allocator)
+	testing.expect_value(t, len(args), 5)
+	testing.expect_value(t, args[1], "env")
+	testing.expect_value(t, args[2], "XBPS_TARGET_ARCH=aarch64")
+	testing.expect_value(t, args[3], "xbps-install")
+
+	args = command_argv_with_env([]string{"/usr/bin/xbps-query", "-l"}, context.temp_allocator)
+	testing.expect_value(t, len(args), 4)
+	testing.expect_value(t, args[0], "env")
+
+	// Everything else keeps vuru's environment
+	for cmd in ([][]string{{"curl", "-s"}, {"sudo", "git", "pull"}, {"sudo"}, {"./xbps-src", "pkg"}}) {
+		testing.expect_value(t, len(command_argv_with_env(cmd, context.temp_allocator)), len(cmd))
+	}
+}
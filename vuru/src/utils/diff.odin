@@ -4,7 +4,6 @@ import config "../core/config"
 import errors "../core/errors"
 import "core:fmt"
 import "core:math/rand"
-import "core:os"
 import "core:strings"
 import "core:sys/linux"
 
@@ -33,58 +32,228 @@ diff_write_temp_file :: proc(content: string, allocator := context.allocator) ->
 		allocator = allocator,
 	)
 
-	if !write_file(path, content) {
+	if !write_file_exclusive(path, content) {
 		delete(path)
 		return "", false
 	}
 
+	temp_file_register(path)
 	return path, true
 }
 
-// Generate a colored unified diff between old and new content
+// Write old/new versions to <name>.old/.new in a new private directory
+// (<tmpdir>/vuru_<name>_<random>, mode 0700) and leave them there for
+// inspection (--keep-diff-files). Nobody else can have put a file or symlink
+// in that directory, so the writes can't be redirected on a shared /tmp.
+// The .old file is skipped for new packages.
+diff_keep_files :: proc(
+	name: string,
+	old_content: string,
+	new_content: string,
+) -> (
+	old_path: string,
+	new_path: string,
+	ok: bool,
+) {
+	if !is_valid_package_name(name) {
+		return "", "", false
+	}
+
+	dir := fmt.tprintf("%s/vuru_%s_%s", config.get_tmpdir(), name, rand_string(8, context.temp_allocator))
+	if linux.mkdir(strings.clone_to_cstring(dir, context.temp_allocator), {.IRUSR, .IWUSR, .IXUSR}) != nil {
+		return "", "", false
+	}
+
+	new_path = fmt.tprintf("%s/%s.new", dir, name)
+	if !write_file_exclusive(new_path, new_content) {
+		return "", "", false
+	}
+
+	if len(old_content) > 0 {
+		old_path = fmt.tprintf("%s/%s.old", dir, name)
+		if !write_file_exclusive(old_path, old_content) {
+			return "", "", false
+		}
+		errors.log_info("Kept diff files: %s %s", old_path, new_path)
+	} else {
+		errors.log_info("Kept template file: %s", new_path)
+	}
+
+	return old_path, new_path, true
+}
+
+// Generate a colored unified diff between old and new content.
+// With keep_name set the compared files are kept (see diff_keep_files).
 diff_generate :: proc(
 	old_content: string,
 	new_content: string,
 	allocator := context.allocator,
+	keep_name := "",
 ) -> (
 	string,
 	bool,
 ) {
+	if len(keep_name) > 0 {
+		old_path, new_path, kept := diff_keep_files(keep_name, old_content, new_content)
+		if kept {
+			if len(old_path) == 0 {
+				return strings.clone(new_content, allocator), true
+			}
+			return diff_files(old_path, new_path, old_content, new_content, allocator), true
+		}
+		errors.log_warning("Could not keep diff files for %s", keep_name)
+	}
+
 	new_path, new_ok := diff_write_temp_file(new_content, context.temp_allocator)
 	if !new_ok {
 		return "", false
 	}
-	defer os.remove(new_path)
+	defer temp_file_remove(new_path)
 
 	if len(old_content) > 0 {
 		old_path, old_ok := diff_write_temp_file(old_content, context.temp_allocator)
 		if !old_ok {
 			return "", false
 		}
-		defer os.remove(old_path)
+		defer temp_file_remove(old_path)
 
-		// diff returns 1 if different, which is fine. run_command_output just captures output.
-		// Note: diff -u --color=always old new
-		output, _ := run_command_output({"diff", "-u", "--color=always", old_path, new_path})
-		return output, true
+		return diff_files(old_path, new_path, old_content, new_content, allocator), true
 	} else {
 		// No old content, return new content
 		return strings.clone(new_content, allocator), true
 	}
 }
 
+// Templates above this size (old + new, in bytes) are diffed straight into
+// the review file instead of through memory
+DIFF_STREAM_THRESHOLD :: 256 * 1024
+
+// Diff large contents with diff(1) writing directly to a temp review file.
+// Without diff(1) the review file holds just the new content.
+@(private)
+diff_stream_to_file :: proc(old_content: string, new_content: string) -> (review_path: string, ok: bool) {
+	new_path, new_ok := diff_write_temp_file(new_content, context.temp_allocator)
+	if !new_ok {
+		return "", false
+	}
+
+	if !command_exists("diff") {
+		errors.log_warning("diff not found, showing the new template only")
+		return new_path, true
+	}
+	defer temp_file_remove(new_path)
+
+	old_path, old_ok := diff_write_temp_file(old_content, context.temp_allocator)
+	if !old_ok {
+		return "", false
+	}
+	defer temp_file_remove(old_path)
+
+	review_path, ok = diff_write_temp_file("", context.temp_allocator)
+	if !ok {
+		return "", false
+	}
+
+	// diff exits 1 when the files differ; only 2 and up are errors
+	if run_command_to_file({"diff", "-u", diff_color_arg(), old_path, new_path}, review_path) > 1 {
+		temp_file_remove(review_path)
+		return "", false
+	}
+	return review_path, true
+}
+
+// diff(1) colors follow vuru's own --color setting
+@(private)
+diff_color_arg :: proc() -> string {
+	return "--color=always" if errors.colors_enabled() else "--color=never"
+}
+
+// Diff two files with diff(1), or the built-in line diff on systems without it
+@(private)
+diff_files :: proc(
+	old_path: string,
+	new_path: string,
+	old_content: string,
+	new_content: string,
+	allocator := context.allocator,
+) -> string {
+	if !command_exists("diff") {
+		return diff_lines_builtin(old_content, new_content, allocator)
+	}
+
+	// diff returns 1 if different, which is fine. run_command_output just captures output.
+	output, _ := run_command_output({"diff", "-u", diff_color_arg(), old_path, new_path}, allocator)
+	return output
+}
+
+// Above this many line pairs the built-in diff just shows both versions
+@(private)
+BUILTIN_DIFF_MAX_CELLS :: 4 * 1024 * 1024
+
+// Minimal line diff for systems without diff(1): every line prefixed with
+// ' ', '-' or '+' (longest common subsequence, no hunks)
+diff_lines_builtin :: proc(old_content: string, new_content: string, allocator := context.allocator) -> string {
+	a := strings.split_lines(old_content, context.temp_allocator)
+	b := strings.split_lines(new_content, context.temp_allocator)
+	n, m := len(a), len(b)
+
+	builder := strings.builder_make(allocator)
+
+	if (n + 1) * (m + 1) > BUILTIN_DIFF_MAX_CELLS {
+		fmt.sbprintf(&builder, "%s===== OLD =====%s\n%s\n", errors.COLOR_RED, errors.COLOR_RESET, old_content)
+		fmt.sbprintf(&builder, "%s===== NEW =====%s\n%s\n", errors.COLOR_GREEN, errors.COLOR_RESET, new_content)
+		return strings.to_string(builder)
+	}
+
+	// lcs[i*(m+1)+j] = LCS length of a[i:] and b[j:]
+	lcs := make([]int, (n + 1) * (m + 1), context.temp_allocator)
+	for i := n - 1; i >= 0; i -= 1 {
+		for j := m - 1; j >= 0; j -= 1 {
+			if a[i] == b[j] {
+				lcs[i * (m + 1) + j] = lcs[(i + 1) * (m + 1) + j + 1] + 1
+			} else {
+				lcs[i * (m + 1) + j] = max(lcs[(i + 1) * (m + 1) + j], lcs[i * (m + 1) + j + 1])
+			}
+		}
+	}
+
+	i, j := 0, 0
+	for i < n || j < m {
+		switch {
+		case i < n && j < m && a[i] == b[j]:
+			fmt.sbprintf(&builder, " %s\n", a[i])
+			i += 1
+			j += 1
+		case j < m && (i == n || lcs[i * (m + 1) + j + 1] >= lcs[(i + 1) * (m + 1) + j]):
+			fmt.sbprintf(&builder, "%s+%s%s\n", errors.COLOR_GREEN, b[j], errors.COLOR_RESET)
+			j += 1
+		case:
+			fmt.sbprintf(&builder, "%s-%s%s\n", errors.COLOR_RED, a[i], errors.COLOR_RESET)
+			i += 1
+		}
+	}
+
+	return strings.to_string(builder)
+}
+
 // Show content in less pager
 diff_show_pager :: proc(path: string) {
 	run_command({"less", "-R", path})
 }
 
 // Review changes between current and previous template
-review_changes :: proc(pkg_name: string, current: string, previous: string) -> bool {
+review_changes :: proc(
+	pkg_name: string,
+	current: string,
+	previous: string,
+	default_yes := true,
+	keep_files := false,
+) -> bool {
 	if len(current) == 0 {
 		return false
 	}
 
-	if !is_valid_identifier(pkg_name) {
+	if !is_valid_package_name(pkg_name) {
 		errors.log_error("Invalid package name")
 		return false
 	}
@@ -92,13 +261,33 @@ review_changes :: proc(pkg_name: string, current: string, previous: string) -> b
 	if len(previous) > 0 && current == previous {
 		errors.log_info("Template for %s unchanged since last install.", pkg_name)
 	} else {
-		if len(previous) > 0 {
+		if len(previous) > 0 && len(previous) + len(current) > DIFF_STREAM_THRESHOLD && !keep_files {
+			// Unusually large template: don't hold the diff in memory as well
+			errors.log_warning(
+				"Template for %s is unusually large (%s), review it carefully",
+				pkg_name,
+				format_size(i64(len(current)), context.temp_allocator),
+			)
+			review_path, path_ok := diff_stream_to_file(previous, current)
+			if path_ok {
+				defer temp_file_remove(review_path)
+
+				fmt.println()
+				fmt.printf("Template for %s has changed:\n", pkg_name)
+				diff_show_pager(review_path)
+			}
+		} else if len(previous) > 0 {
 			// Generate colored diff and show in pager
-			diff_output, diff_ok := diff_generate(previous, current, context.temp_allocator)
+			diff_output, diff_ok := diff_generate(
+				previous,
+				current,
+				context.temp_allocator,
+				pkg_name if keep_files else "",
+			)
 			if diff_ok && len(diff_output) > 0 {
 				review_path, path_ok := diff_write_temp_file(diff_output, context.temp_allocator)
 				if path_ok {
-					defer os.remove(review_path)
+					defer temp_file_remove(review_path)
 
 					fmt.println()
 					fmt.printf("Template for %s has changed:\n", pkg_name)
@@ -109,25 +298,16 @@ review_changes :: proc(pkg_name: string, current: string, previous: string) -> b
 			// New package - show full template in pager
 			fmt.println()
 			fmt.printf("New package %s. Review template:\n", pkg_name)
+			if keep_files {
+				diff_keep_files(pkg_name, "", current)
+			}
 			review_path, path_ok := diff_write_temp_file(current, context.temp_allocator)
 			if path_ok {
-				defer os.remove(review_path)
+				defer temp_file_remove(review_path)
 				diff_show_pager(review_path)
 			}
 		}
 	}
 
-	fmt.print("Proceed with installation? [Y/n] ")
-
-	buf: [100]u8
-	n, _ := os.read(os.stdin, buf[:])
-
-	if n <= 0 {
-		return false
-	}
-
-	input := strings.trim_space(string(buf[:n]))
-	input_lower := strings.to_lower(input, context.temp_allocator)
-
-	return len(input) == 0 || input_lower == "y" || input_lower == "yes"
+	return prompt_yes_no("Proceed with installation?", default_yes)
 }
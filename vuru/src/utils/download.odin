@@ -0,0 +1,78 @@
+package utils
+
+import "core:fmt"
+import "core:os"
+
+import errors "../core/errors"
+
+// Download accounting for metered connections: the bytes index and template
+// downloads fetched in this run, and the --max-download budget for them.
+// The budget is enforced per download through curl's --max-filesize, which
+// refuses a response whose Content-Length doesn't fit before transferring it.
+
+@(private)
+download_total: i64
+
+@(private)
+download_budget: i64 = -1 // -1 = unlimited
+
+// Limit what later downloads may fetch in total
+download_set_budget :: proc(bytes: i64) {
+	download_budget = bytes
+}
+
+// Whether --max-download is in effect
+download_budget_set :: proc() -> bool {
+	return download_budget >= 0
+}
+
+// Bytes downloaded so far
+download_total_bytes :: proc() -> i64 {
+	return download_total
+}
+
+// curl options for the next download of url: none without a budget, else
+// --max-filesize with what is left. For a parallel batch of transfers each
+// one gets an equal share, so the batch as a whole stays within the budget.
+// False (after logging) when the budget is used up and url must not be
+// fetched.
+download_curl_args :: proc(url: string, allocator := context.allocator, transfers := 1) -> ([]string, bool) {
+	if download_budget < 0 {
+		return nil, true
+	}
+
+	left := download_budget - download_total
+	if left <= 0 {
+		errors.log_error(
+			"Download budget of %s (--max-download) is used up, not fetching %s",
+			format_size(download_budget, context.temp_allocator),
+			url,
+		)
+		return nil, false
+	}
+
+	args := make([]string, 2, allocator)
+	args[0] = "--max-filesize"
+	args[1] = fmt.aprintf("%d", max(left / i64(max(transfers, 1)), 1), allocator = allocator)
+	return args, true
+}
+
+// Count a finished download by the size of the file it wrote
+download_record_file :: proc(path: string) {
+	if fi, err := os.stat(path, context.temp_allocator); err == nil {
+		download_total += fi.size
+	}
+}
+
+// After a failed download: point at the budget, which curl enforces by
+// failing the transfer
+download_budget_hint :: proc() {
+	if download_budget < 0 {
+		return
+	}
+	errors.log_info(
+		"Downloads are limited to %s (--max-download, %s left); the file may be larger",
+		format_size(download_budget, context.temp_allocator),
+		format_size(max(download_budget - download_total, 0), context.temp_allocator),
+	)
+}
@@ -0,0 +1,197 @@
+package utils
+
+import "core:os"
+import "core:strings"
+import "core:sys/linux"
+
+// Writes of related files (an index with its validators, a state file) that
+// land together. New contents are staged next to their target under a unique
+// "<path>.vuru-new~<random>" name, and commit renames each one over its
+// target: rename(2) replaces a file atomically, so a reader (or a crash) sees
+// the old or the new content of every file, never a missing or partial one.
+//
+// Before anything changes every existing target is hard-linked to a
+// "<path>.vuru-old~<random>" backup, so a failure part-way through the commit
+// puts all of them back: commit changes every file or none. Only a crash can
+// leave a mix; files land in the order they were staged, after the removals,
+// so stage the file that the others describe first: a crash part-way then
+// leaves it new with old companions, never the other way round. Everything
+// lives in the temp allocator.
+File_Transaction :: struct {
+	writes:  [dynamic]File_Tx_Write,
+	removes: [dynamic]string,
+	failed:  bool, // A stage failed, commit will refuse
+}
+
+@(private)
+File_Tx_Write :: struct {
+	path:   string,
+	staged: string,
+}
+
+// A target changed by commit, and the link to its old content ("" if it had none)
+@(private)
+File_Tx_Done :: struct {
+	path:   string,
+	backup: string,
+}
+
+// '~' never appears in package names, so leftovers in the template cache are
+// reported as stray files rather than taken for templates
+@(private)
+FILE_TX_STAGED_SUFFIX :: ".vuru-new~"
+
+@(private)
+FILE_TX_BACKUP_SUFFIX :: ".vuru-old~"
+
+// Stage new content for path
+file_tx_write :: proc(tx: ^File_Transaction, path: string, content: string) -> bool {
+	// A unique name created exclusively, so concurrent vuru runs don't write
+	// into each other's staged files and a planted symlink isn't followed
+	for _ in 0 ..< 8 {
+		staged := strings.concatenate(
+			{path, FILE_TX_STAGED_SUFFIX, rand_string(8, context.temp_allocator)},
+			context.temp_allocator,
+		)
+		if os.exists(staged) {
+			continue
+		}
+		if !write_file_exclusive(staged, content, {.IRUSR, .IWUSR, .IRGRP, .IROTH}) {
+			break
+		}
+		file_tx_stage(tx, staged, path)
+		return true
+	}
+	tx.failed = true
+	return false
+}
+
+// Stage a file that is already written (e.g. a download) to replace path.
+// It has to be on the same filesystem as path.
+file_tx_stage :: proc(tx: ^File_Transaction, staged: string, path: string) {
+	if tx.writes == nil {
+		tx.writes = make([dynamic]File_Tx_Write, context.temp_allocator)
+	}
+	append(&tx.writes, File_Tx_Write{path = path, staged = staged})
+}
+
+// Remove path as part of the transaction (missing files are fine)
+file_tx_remove :: proc(tx: ^File_Transaction, path: string) {
+	if tx.removes == nil {
+		tx.removes = make([dynamic]string, context.temp_allocator)
+	}
+	append(&tx.removes, path)
+}
+
+// Put every staged file in place and do the removals. On any failure the
+// targets changed so far get their old content back and nothing changes.
+file_tx_commit :: proc(tx: ^File_Transaction) -> bool {
+	if tx.failed {
+		file_tx_rollback(tx)
+		return false
+	}
+
+	// Back up every existing target first, while nothing has changed yet
+	backups := make(map[string]string, context.temp_allocator)
+	ok := true
+	for path in tx.removes {
+		ok = ok && file_tx_backup(path, &backups)
+	}
+	for w in tx.writes {
+		ok = ok && file_tx_backup(w.path, &backups)
+	}
+
+	done := make([dynamic]File_Tx_Done, context.temp_allocator)
+	if ok {
+		for path in tx.removes {
+			backup, had := backups[path]
+			if !had {
+				continue
+			}
+			if os.remove(path) != os.ERROR_NONE {
+				ok = false
+				break
+			}
+			append(&done, File_Tx_Done{path, backup})
+		}
+	}
+	if ok {
+		for w in tx.writes {
+			if os.rename(w.staged, w.path) != os.ERROR_NONE {
+				ok = false
+				break
+			}
+			backup, _ := backups[w.path]
+			append(&done, File_Tx_Done{w.path, backup})
+		}
+	}
+
+	if !ok {
+		// Undo in reverse: a path both removed and written ends up as before
+		for i := len(done) - 1; i >= 0; i -= 1 {
+			d := done[i]
+			if len(d.backup) > 0 {
+				os.rename(d.backup, d.path)
+			} else {
+				os.remove(d.path)
+			}
+		}
+		for w in tx.writes {
+			os.remove(w.staged)
+		}
+	}
+	// Backups restored above are gone already
+	for _, backup in backups {
+		os.remove(backup)
+	}
+
+	tx^ = {}
+	return ok
+}
+
+// Hard-link path to a unique backup name if it exists. Missing is fine.
+// A directory can't be linked, and can't be replaced by a file either.
+@(private)
+file_tx_backup :: proc(path: string, backups: ^map[string]string) -> bool {
+	if path in backups {
+		return true
+	}
+
+	cpath := strings.clone_to_cstring(path, context.temp_allocator)
+	for _ in 0 ..< 8 {
+		backup := strings.concatenate(
+			{path, FILE_TX_BACKUP_SUFFIX, rand_string(8, context.temp_allocator)},
+			context.temp_allocator,
+		)
+		err := linux.link(cpath, strings.clone_to_cstring(backup, context.temp_allocator))
+		#partial switch err {
+		case .NONE:
+			backups[path] = backup
+			return true
+		case .ENOENT:
+			return true
+		case .EEXIST:
+			continue
+		}
+		break
+	}
+	return false
+}
+
+// Drop the staged files without touching the targets
+file_tx_rollback :: proc(tx: ^File_Transaction) {
+	for w in tx.writes {
+		os.remove(w.staged)
+	}
+	tx^ = {}
+}
+
+// Replace one file atomically: readers see the old or the new content, never
+// a partial write
+write_file_atomic :: proc(path: string, content: string) -> bool {
+	tx: File_Transaction
+	if !file_tx_write(&tx, path, content) {
+		return false
+	}
+	return file_tx_commit(&tx)
+}
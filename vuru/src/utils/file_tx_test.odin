@@ -0,0 +1,194 @@
+package utils
+
+import "core:fmt"
+import "core:os"
+import "core:strings"
+import "core:sys/linux"
+import "core:testing"
+
+import config "../core/config"
+
+// A fresh directory under TMPDIR for one test
+@(private = "file")
+test_dir_make :: proc(t: ^testing.T) -> string {
+	dir := fmt.tprintf("%s/vuru_test_%s", config.get_tmpdir(), rand_string(8, context.temp_allocator))
+	testing.expect(t, mkdir_p(dir), "create test directory")
+	return dir
+}
+
+// Remove a test directory with everything in it (one level deep)
+@(private = "file")
+test_dir_remove :: proc(dir: string) {
+	for name in test_dir_names(dir) {
+		path := path_join(dir, name, allocator = context.temp_allocator)
+		if os.is_dir(path) {
+			os.remove_directory(path)
+		} else {
+			os.remove(path)
+		}
+	}
+	os.remove_directory(dir)
+}
+
+@(private = "file")
+test_dir_names :: proc(dir: string) -> []string {
+	d, err := os.open(dir)
+	if err != os.ERROR_NONE {
+		return nil
+	}
+	defer os.close(d)
+
+	file_infos, _ := os.read_dir(d, -1, context.temp_allocator)
+	names := make([dynamic]string, context.temp_allocator)
+	for fi in file_infos {
+		append(&names, fi.name)
+	}
+	return names[:]
+}
+
+// Whether any staged file or backup was left behind in dir
+@(private = "file")
+test_has_staged :: proc(dir: string) -> bool {
+	for name in test_dir_names(dir) {
+		if strings.contains(name, FILE_TX_STAGED_SUFFIX) || strings.contains(name, FILE_TX_BACKUP_SUFFIX) {
+			return true
+		}
+	}
+	return false
+}
+
+@(private = "file")
+test_read :: proc(path: string) -> string {
+	content, _ := read_file(path, context.temp_allocator)
+	return content
+}
+
+@(test)
+test_file_tx_commit_replaces_files :: proc(t: ^testing.T) {
+	dir := test_dir_make(t)
+	defer test_dir_remove(dir)
+
+	a := path_join(dir, "a", allocator = context.temp_allocator)
+	b := path_join(dir, "b", allocator = context.temp_allocator)
+	gone := path_join(dir, "gone", allocator = context.temp_allocator)
+	write_file(a, "old")
+	write_file(gone, "x")
+
+	tx: File_Transaction
+	testing.expect(t, file_tx_write(&tx, a, "new"))
+	testing.expect(t, file_tx_write(&tx, b, "created"))
+	file_tx_remove(&tx, gone)
+	testing.expect(t, file_tx_commit(&tx))
+
+	testing.expect_value(t, test_read(a), "new")
+	testing.expect_value(t, test_read(b), "created")
+	testing.expect(t, !os.exists(gone))
+	testing.expect(t, !test_has_staged(dir))
+}
+
+@(test)
+test_file_tx_failed_stage_changes_nothing :: proc(t: ^testing.T) {
+	dir := test_dir_make(t)
+	defer test_dir_remove(dir)
+
+	a := path_join(dir, "a", allocator = context.temp_allocator)
+	gone := path_join(dir, "gone", allocator = context.temp_allocator)
+	write_file(a, "old")
+	write_file(gone, "x")
+
+	tx: File_Transaction
+	testing.expect(t, file_tx_write(&tx, a, "new"))
+	testing.expect(t, !file_tx_write(&tx, path_join(dir, "missing", "b", allocator = context.temp_allocator), "x"))
+	file_tx_remove(&tx, gone)
+	testing.expect(t, !file_tx_commit(&tx))
+
+	testing.expect_value(t, test_read(a), "old")
+	testing.expect(t, os.exists(gone))
+	testing.expect(t, !test_has_staged(dir))
+}
+
+@(test)
+test_file_tx_rename_failure_rolls_back :: proc(t: ^testing.T) {
+	dir := test_dir_make(t)
+	defer test_dir_remove(dir)
+
+	a := path_join(dir, "a", allocator = context.temp_allocator)
+	fresh := path_join(dir, "fresh", allocator = context.temp_allocator)
+	c := path_join(dir, "c", allocator = context.temp_allocator)
+	gone := path_join(dir, "gone", allocator = context.temp_allocator)
+	write_file(a, "old")
+	write_file(c, "old")
+	write_file(gone, "x")
+
+	tx: File_Transaction
+	testing.expect(t, file_tx_write(&tx, a, "new"))
+	testing.expect(t, file_tx_write(&tx, fresh, "new"))
+	testing.expect(t, file_tx_write(&tx, c, "new"))
+	file_tx_remove(&tx, gone)
+
+	// The last staged file vanishes, so its rename fails after the others
+	os.remove(tx.writes[2].staged)
+	testing.expect(t, !file_tx_commit(&tx))
+
+	// Everything done before the failure is undone
+	testing.expect_value(t, test_read(a), "old")
+	testing.expect(t, !os.exists(fresh))
+	testing.expect_value(t, test_read(c), "old")
+	testing.expect_value(t, test_read(gone), "x")
+	testing.expect(t, !test_has_staged(dir))
+}
+
+@(test)
+test_file_tx_directory_target_changes_nothing :: proc(t: ^testing.T) {
+	dir := test_dir_make(t)
+	defer test_dir_remove(dir)
+
+	// A directory can't be replaced by a file: refused before anything changes
+	a := path_join(dir, "a", allocator = context.temp_allocator)
+	b := path_join(dir, "b", allocator = context.temp_allocator)
+	write_file(a, "old")
+	testing.expect(t, mkdir_p(b))
+
+	tx: File_Transaction
+	testing.expect(t, file_tx_write(&tx, a, "new"))
+	testing.expect(t, file_tx_write(&tx, b, "x"))
+	testing.expect(t, !file_tx_commit(&tx))
+
+	testing.expect_value(t, test_read(a), "old")
+	testing.expect(t, os.is_dir(b))
+	testing.expect(t, !test_has_staged(dir))
+}
+
+@(test)
+test_write_file_exclusive_refuses_existing :: proc(t: ^testing.T) {
+	dir := test_dir_make(t)
+	defer test_dir_remove(dir)
+
+	target := path_join(dir, "target", allocator = context.temp_allocator)
+	link := path_join(dir, "link", allocator = context.temp_allocator)
+	write_file(target, "mine")
+	linux.symlink(
+		strings.clone_to_cstring(target, context.temp_allocator),
+		strings.clone_to_cstring(link, context.temp_allocator),
+	)
+
+	testing.expect(t, !write_file_exclusive(target, "theirs"))
+	testing.expect(t, !write_file_exclusive(link, "theirs"))
+	testing.expect_value(t, test_read(target), "mine")
+
+	fresh := path_join(dir, "fresh", allocator = context.temp_allocator)
+	testing.expect(t, write_file_exclusive(fresh, "content"))
+	testing.expect_value(t, test_read(fresh), "content")
+}
+
+@(test)
+test_write_file_atomic :: proc(t: ^testing.T) {
+	dir := test_dir_make(t)
+	defer test_dir_remove(dir)
+
+	path := path_join(dir, "state", allocator = context.temp_allocator)
+	testing.expect(t, write_file_atomic(path, "one"))
+	testing.expect(t, write_file_atomic(path, "two"))
+	testing.expect_value(t, test_read(path), "two")
+	testing.expect(t, !test_has_staged(dir))
+}
@@ -0,0 +1,42 @@
+package utils
+
+import "core:strings"
+
+// Media type of a curl header dump (-D), lowercased and without parameters
+// ("text/html; charset=utf-8" -> "text/html"). With -L every redirect hop
+// adds a header block, so only the last block counts. Empty when missing.
+response_content_type :: proc(headers: string) -> string {
+	content_type := ""
+	it := headers
+	for line in strings.split_lines_iterator(&it) {
+		l := strings.trim_space(line)
+
+		if strings.has_prefix(l, "HTTP/") {
+			content_type = ""
+			continue
+		}
+
+		colon := strings.index_byte(l, ':')
+		if colon <= 0 || !strings.equal_fold(l[:colon], "content-type") {
+			continue
+		}
+
+		value := l[colon + 1:]
+		if semi := strings.index_byte(value, ';'); semi >= 0 {
+			value = value[:semi]
+		}
+		content_type = strings.to_lower(strings.trim_space(value), context.temp_allocator)
+	}
+	return content_type
+}
+
+// HTML where data was expected: a captive portal, proxy login or error page
+// answering with 200
+is_html_content_type :: proc(content_type: string) -> bool {
+	return content_type == "text/html" || content_type == "application/xhtml+xml"
+}
+
+// Whether a URL uses unencrypted http:// (scheme compared case-insensitively)
+is_plain_http_url :: proc(url: string) -> bool {
+	return len(url) >= 7 && strings.equal_fold(url[:7], "http://")
+}
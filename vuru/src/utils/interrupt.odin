@@ -0,0 +1,85 @@
+package utils
+
+import "core:c/libc"
+import "core:os"
+import "core:sys/linux"
+
+// SIGINT and SIGTERM normally end vuru right away, after removing the tracked
+// temp files (see tempfile.odin) and restoring the terminal (terminal.odin). Ctrl-C however goes to the whole foreground
+// process group, so it reaches xbps-install as well as vuru. While a runner
+// waits for a child, vuru only notes the signal and lets the child wind down:
+// an interrupt is then reported to the caller ("interrupted" instead of dying
+// or blaming xbps), a SIGTERM ends vuru once the child is gone.
+
+// 128 + SIGINT, the status a shell reports for a command stopped with Ctrl-C
+STATUS_INTERRUPTED :: 130
+
+@(private)
+interrupt_seen: bool
+
+// SIGTERM arrived while a child was running
+@(private)
+terminate_pending: bool
+
+@(private)
+child_running: bool
+
+// Only async-signal-safe calls here
+@(private)
+signal_handler :: proc "c" (sig: i32) {
+	if child_running {
+		if sig == libc.SIGINT {
+			interrupt_seen = true
+		} else {
+			terminate_pending = true
+		}
+		return
+	}
+	terminal_restore()
+	temp_files_unlink_all()
+	linux.exit_group(128 + sig)
+}
+
+// Install the SIGINT/SIGTERM handler, once at startup
+signals_install :: proc() {
+	libc.signal(libc.SIGINT, signal_handler)
+	libc.signal(libc.SIGTERM, signal_handler)
+}
+
+// Whether Ctrl-C was pressed while the last command vuru ran was running
+command_interrupted :: proc() -> bool {
+	return interrupt_seen
+}
+
+// Note signals instead of acting on them until child_end. Call before fork:
+// exec resets the handler, so the child still gets the default behaviour.
+// Every runner calls it, so an interrupt only sticks to the command it hit.
+@(private)
+child_begin :: proc() {
+	interrupt_seen = false
+	child_running = true
+}
+
+// The child is gone: act on a SIGTERM that came in meanwhile
+@(private)
+child_end :: proc() {
+	child_running = false
+	if terminate_pending {
+		terminal_restore()
+		temp_files_cleanup()
+		os.exit(128 + int(libc.SIGTERM))
+	}
+}
+
+// Wait for a child, waiting again when a signal interrupts the wait
+@(private)
+child_wait :: proc(pid: linux.Pid) -> u32 {
+	status: u32
+	for {
+		_, err := linux.waitpid(pid, &status, {}, nil)
+		if err != .EINTR {
+			break
+		}
+	}
+	return status
+}
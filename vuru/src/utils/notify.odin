@@ -0,0 +1,17 @@
+package utils
+
+import "core:os"
+
+// Show a desktop notification through notify-send. Does nothing without a
+// graphical session or when notify-send is not installed.
+desktop_notify :: proc(summary: string, body: string) {
+	display := os.get_env("DISPLAY", context.temp_allocator)
+	wayland := os.get_env("WAYLAND_DISPLAY", context.temp_allocator)
+	if len(display) == 0 && len(wayland) == 0 {
+		return
+	}
+	if !command_exists("notify-send") {
+		return
+	}
+	run_command_silent({"notify-send", "--app-name=vuru", summary, body})
+}
@@ -0,0 +1,120 @@
+package utils
+
+import "core:fmt"
+import "core:os"
+import "core:strconv"
+import "core:strings"
+
+// Read whitespace-separated words from stdin until EOF, skipping "#" comments
+read_stdin_words :: proc(allocator := context.allocator) -> []string {
+	builder := strings.builder_make(context.temp_allocator)
+	buf: [4096]u8
+	for {
+		n, err := os.read(os.stdin, buf[:])
+		if n <= 0 || err != nil {
+			break
+		}
+		strings.write_bytes(&builder, buf[:n])
+	}
+
+	words := make([dynamic]string, allocator)
+	content := strings.to_string(builder)
+	for line in strings.split_lines_iterator(&content) {
+		l := line
+		if hash := strings.index_byte(l, '#'); hash >= 0 {
+			l = l[:hash]
+		}
+		for word in strings.fields(l, context.temp_allocator) {
+			append(&words, strings.clone(word, allocator))
+		}
+	}
+	return words[:]
+}
+
+// Why prompts can't be answered: CI=true/1 in the environment, or stdin is not
+// a terminal. Returns false when running interactively.
+non_interactive_reason :: proc() -> (reason: string, non_interactive: bool) {
+	ci := os.get_env("CI", context.temp_allocator)
+	if ci == "true" || ci == "1" {
+		return "CI environment", true
+	}
+	if isatty(0) != 1 {
+		return "stdin is not a terminal", true
+	}
+	return "", false
+}
+
+// Ask a yes/no question on stdin. Empty input picks the default, which is
+// shown capitalized ([Y/n] or [y/N]). EOF or a read error counts as "no".
+prompt_yes_no :: proc(question: string, default_yes: bool) -> bool {
+	fmt.printf("%s %s ", question, "[Y/n]" if default_yes else "[y/N]")
+
+	buf: [100]u8
+	n, _ := os.read(os.stdin, buf[:])
+
+	if n <= 0 {
+		return false
+	}
+
+	input := strings.trim_space(string(buf[:n]))
+	if len(input) == 0 {
+		return default_yes
+	}
+
+	input_lower := strings.to_lower(input, context.temp_allocator)
+	return input_lower == "y" || input_lower == "yes"
+}
+
+// Ask for a number from 1 to max on stdin, asking again on anything else.
+// Empty input returns 0 (the default); EOF or a read error returns false.
+prompt_number :: proc(question: string, max: int) -> (int, bool) {
+	for {
+		fmt.printf("%s ", question)
+
+		buf: [100]u8
+		n, _ := os.read(os.stdin, buf[:])
+		if n <= 0 {
+			return 0, false
+		}
+
+		input := strings.trim_space(string(buf[:n]))
+		if len(input) == 0 {
+			return 0, true
+		}
+		if choice, ok := strconv.parse_int(input, 10); ok && choice >= 1 && choice <= max {
+			return choice, true
+		}
+		fmt.printf("Enter a number from 1 to %d\n", max)
+	}
+}
+
+// Answer to a step prompt (install --confirm-each)
+Step_Answer :: enum {
+	Continue,
+	Skip,
+	Abort,
+}
+
+// Ask whether to run a step: [C]ontinue (the default), [s]kip or [a]bort.
+// Asks again on anything else; EOF or a read error aborts.
+prompt_step :: proc(question: string) -> Step_Answer {
+	for {
+		fmt.printf("%s [C/s/a] ", question)
+
+		buf: [100]u8
+		n, _ := os.read(os.stdin, buf[:])
+		if n <= 0 {
+			return .Abort
+		}
+
+		switch strings.to_lower(strings.trim_space(string(buf[:n])), context.temp_allocator) {
+		case "", "c", "continue":
+			return .Continue
+		case "s", "skip":
+			return .Skip
+		case "a", "abort":
+			return .Abort
+		}
+		fmt.println("Answer c (continue), s (skip) or a (abort)")
+	}
+}
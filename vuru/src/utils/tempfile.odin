@@ -0,0 +1,70 @@
+package utils
+
+import "core:os"
+import "core:sys/linux"
+
+// Temp files are tracked here so an interrupted run (Ctrl-C during a review
+// pager or prompt) doesn't leave them behind in $TMPDIR. The signal handler
+// that removes them lives in interrupt.odin.
+// A parallel template fetch tracks two files (download and headers) for each
+// of up to MAX_UPGRADES (64) templates, plus room for the rest
+MAX_TEMP_FILES :: 160
+TEMP_PATH_MAX :: 512
+
+@(private)
+Temp_Registry :: struct {
+	paths: [MAX_TEMP_FILES][TEMP_PATH_MAX]u8, // NUL-terminated for unlink
+	used:  [MAX_TEMP_FILES]bool,
+}
+
+@(private)
+temp_registry: Temp_Registry
+
+// Remove every tracked file. Only async-signal-safe calls, the signal handler uses it.
+@(private)
+temp_files_unlink_all :: proc "contextless" () {
+	for i in 0 ..< MAX_TEMP_FILES {
+		if temp_registry.used[i] {
+			_ = linux.unlink(cstring(&temp_registry.paths[i][0]))
+		}
+	}
+}
+
+// Remove every tracked temp file, before exiting on a signal
+@(private)
+temp_files_cleanup :: proc() {
+	temp_files_unlink_all()
+	temp_registry.used = {}
+}
+
+// Track a temp file so it is removed if vuru is interrupted
+temp_file_register :: proc(path: string) {
+	if len(path) == 0 || len(path) >= TEMP_PATH_MAX {
+		return
+	}
+
+	for i in 0 ..< MAX_TEMP_FILES {
+		if !temp_registry.used[i] {
+			copy(temp_registry.paths[i][:], path)
+			temp_registry.paths[i][len(path)] = 0
+			temp_registry.used[i] = true
+			return
+		}
+	}
+}
+
+// Stop tracking a temp file without removing it
+temp_file_forget :: proc(path: string) {
+	for i in 0 ..< MAX_TEMP_FILES {
+		if temp_registry.used[i] && string(cstring(&temp_registry.paths[i][0])) == path {
+			temp_registry.used[i] = false
+			return
+		}
+	}
+}
+
+// Remove a tracked temp file (use with defer in place of os.remove)
+temp_file_remove :: proc(path: string) {
+	temp_file_forget(path)
+	os.remove(path)
+}
@@ -0,0 +1,45 @@
+package utils
+
+import "core:sys/linux"
+
+// Terminal modes (raw mode, alternate screen) that a signal must not leave
+// behind: the signal handler in interrupt.odin puts the saved settings back
+// before vuru exits.
+
+@(private)
+Terminal_Guard :: struct {
+	termios: [64]u8, // struct termios of stdin, opaque (60 bytes on Linux)
+	leave:   string, // Escape sequence undoing the screen changes
+	active:  bool,
+}
+
+@(private)
+terminal_guard: Terminal_Guard
+
+// Save the settings of the terminal on stdin, to be restored if a signal
+// ends vuru before terminal_guard_end. leave is written to stdout then; it
+// must outlive the guard.
+terminal_guard_begin :: proc(leave: string) -> bool {
+	if tcgetattr(0, &terminal_guard.termios[0]) != 0 {
+		return false
+	}
+	terminal_guard.leave = leave
+	terminal_guard.active = true
+	return true
+}
+
+terminal_guard_end :: proc() {
+	terminal_guard.active = false
+}
+
+// Put the terminal back. Only async-signal-safe calls, the signal handler uses it.
+@(private)
+terminal_restore :: proc "contextless" () {
+	if !terminal_guard.active {
+		return
+	}
+	TCSANOW :: 0
+	_ = tcsetattr(0, TCSANOW, &terminal_guard.termios[0])
+	_, _ = linux.write(1, transmute([]u8)terminal_guard.leave)
+	terminal_guard.active = false
+}
@@ -1,15 +1,21 @@
 package utils
 
+import "core:fmt"
 import "core:mem"
 import "core:os"
 import "core:strings"
 import "core:sys/linux"
+import "core:time"
 
 // Define execvp since it's missing from core:c/libc sometimes or not exported commonly
 foreign import libc "system:c"
 
 foreign libc {
 	execvp :: proc(file: cstring, argv: [^]cstring) -> i32 ---
+	isatty :: proc(fd: i32) -> i32 ---
+	access :: proc(path: cstring, mode: i32) -> i32 ---
+	tcgetattr :: proc(fd: i32, termios: rawptr) -> i32 ---
+	tcsetattr :: proc(fd: i32, optional_actions: i32, termios: rawptr) -> i32 ---
 }
 
 
@@ -27,6 +33,43 @@ write_file :: proc(path: string, content: string) -> bool {
 	return os.write_entire_file(path, transmute([]u8)content) == nil
 }
 
+// Append content to a file, creating it if needed, with a single write:
+// with O_APPEND concurrent appenders never overwrite each other's lines
+append_file :: proc(path: string, content: string) -> bool {
+	cpath := strings.clone_to_cstring(path, context.temp_allocator)
+	fd, err := linux.open(cpath, {.WRONLY, .CREAT, .APPEND}, {.IRUSR, .IWUSR, .IRGRP, .IROTH})
+	if err != nil {
+		return false
+	}
+	defer linux.close(fd)
+
+	n, write_err := linux.write(fd, transmute([]u8)content)
+	return write_err == nil && n == len(content)
+}
+
+// Write content to a file that must not exist yet. Unlike write_file it
+// never follows a symlink or reuses a file someone else put at path, so it is
+// safe in shared directories such as /tmp.
+write_file_exclusive :: proc(path: string, content: string, mode: linux.Mode = {.IRUSR, .IWUSR}) -> bool {
+	cpath := strings.clone_to_cstring(path, context.temp_allocator)
+	fd, err := linux.open(cpath, {.WRONLY, .CREAT, .EXCL, .NOFOLLOW}, mode)
+	if err != nil {
+		return false
+	}
+	defer linux.close(fd)
+
+	data := transmute([]u8)content
+	for len(data) > 0 {
+		n, write_err := linux.write(fd, data)
+		if write_err != nil || n <= 0 {
+			linux.unlink(cpath)
+			return false
+		}
+		data = data[n:]
+	}
+	return true
+}
+
 
 // Make a C-compatible argv array from slice of strings
 make_argv :: proc(args: []string, allocator := context.allocator) -> [^]cstring {
@@ -41,6 +84,7 @@ make_argv :: proc(args: []string, allocator := context.allocator) -> [^]cstring
 // Run a command and return its output
 run_command_output :: proc(args: []string, allocator := context.allocator) -> (string, bool) {
 	if len(args) == 0 {return "", false}
+	cmd_args := command_argv_with_env(args, context.temp_allocator)
 
 	fds: [2]linux.Fd
 	// pipe2 with empty flags ({}) is equivalent to pipe
@@ -49,6 +93,9 @@ run_command_output :: proc(args: []string, allocator := context.allocator) -> (s
 	}
 	defer linux.close(fds[0])
 
+	child_begin()
+	defer child_end()
+
 	pid, err := linux.fork()
 	if err != nil {
 		linux.close(fds[1])
@@ -61,8 +108,8 @@ run_command_output :: proc(args: []string, allocator := context.allocator) -> (s
 		linux.dup2(fds[1], linux.STDOUT_FILENO)
 		linux.close(fds[1])
 
-		argv := make_argv(args, context.temp_allocator)
-		path := strings.clone_to_cstring(args[0], context.temp_allocator)
+		argv := make_argv(cmd_args, context.temp_allocator)
+		path := strings.clone_to_cstring(cmd_args[0], context.temp_allocator)
 
 		// If execvp returns, it failed
 		execvp(path, argv)
@@ -77,12 +124,12 @@ run_command_output :: proc(args: []string, allocator := context.allocator) -> (s
 
 	for {
 		n, read_err := linux.read(fds[0], buf[:])
+		if read_err == .EINTR {continue}
 		if n <= 0 || read_err != nil {break}
 		strings.write_bytes(&builder, buf[:n])
 	}
 
-	status: u32
-	linux.waitpid(pid, &status, {}, nil)
+	status := child_wait(pid)
 
 	// Check exit status
 	success := (status & 0x7f) == 0 && ((status & 0xff00) >> 8) == 0
@@ -93,6 +140,7 @@ run_command_output :: proc(args: []string, allocator := context.allocator) -> (s
 // Run a command silently (capture output, return exit code)
 run_command_silent :: proc(args: []string) -> int {
 	if len(args) == 0 {return 127}
+	cmd_args := command_argv_with_env(args, context.temp_allocator)
 
 	fds: [2]linux.Fd
 	if linux.pipe2(&fds, {}) != nil {
@@ -100,6 +148,9 @@ run_command_silent :: proc(args: []string) -> int {
 	}
 	defer linux.close(fds[0])
 
+	child_begin()
+	defer child_end()
+
 	pid, err := linux.fork()
 	if err != nil {
 		linux.close(fds[1])
@@ -113,8 +164,8 @@ run_command_silent :: proc(args: []string) -> int {
 		linux.dup2(fds[1], linux.STDERR_FILENO)
 		linux.close(fds[1])
 
-		argv := make_argv(args, context.temp_allocator)
-		path := strings.clone_to_cstring(args[0], context.temp_allocator)
+		argv := make_argv(cmd_args, context.temp_allocator)
+		path := strings.clone_to_cstring(cmd_args[0], context.temp_allocator)
 
 		execvp(path, argv)
 		os.exit(127)
@@ -124,12 +175,52 @@ run_command_silent :: proc(args: []string) -> int {
 	linux.close(fds[1])
 	buf: [1024]u8
 	for {
-		n, _ := linux.read(fds[0], buf[:])
+		n, read_err := linux.read(fds[0], buf[:])
+		if read_err == .EINTR {continue}
 		if n <= 0 {break}
 	}
 
-	status: u32
-	linux.waitpid(pid, &status, {}, nil)
+	status := child_wait(pid)
+
+	if (status & 0x7f) == 0 {
+		return int((status & 0xff00) >> 8)
+	}
+
+	return -1
+}
+
+// Run a command with stdout written to a file (truncated), return exit code.
+// Output never passes through memory, for commands with large output.
+run_command_to_file :: proc(args: []string, path: string) -> int {
+	if len(args) == 0 {return 127}
+	cmd_args := command_argv_with_env(args, context.temp_allocator)
+	cpath := strings.clone_to_cstring(path, context.temp_allocator)
+
+	child_begin()
+	defer child_end()
+
+	pid, err := linux.fork()
+	if err != nil {
+		return -1
+	}
+
+	if pid == 0 {
+		// Child - stdout goes to the file
+		fd, open_err := linux.open(cpath, {.WRONLY, .CREAT, .TRUNC}, {.IRUSR, .IWUSR})
+		if open_err != nil {
+			os.exit(127)
+		}
+		linux.dup2(fd, linux.STDOUT_FILENO)
+		linux.close(fd)
+
+		argv := make_argv(cmd_args, context.temp_allocator)
+		cmd := strings.clone_to_cstring(cmd_args[0], context.temp_allocator)
+
+		execvp(cmd, argv)
+		os.exit(127)
+	}
+
+	status := child_wait(pid)
 
 	if (status & 0x7f) == 0 {
 		return int((status & 0xff00) >> 8)
@@ -138,9 +229,62 @@ run_command_silent :: proc(args: []string) -> int {
 	return -1
 }
 
-// Run a command and return exit code
+// Run a command with input fed to its stdin, return exit code
+run_command_with_input :: proc(args: []string, input: string) -> int {
+	if len(args) == 0 {return 127}
+	cmd_args := command_argv_with_env(args, context.temp_allocator)
+
+	fds: [2]linux.Fd
+	if linux.pipe2(&fds, {}) != nil {
+		return -1
+	}
+
+	child_begin()
+	defer child_end()
+
+	pid, err := linux.fork()
+	if err != nil {
+		linux.close(fds[0])
+		linux.close(fds[1])
+		return -1
+	}
+
+	if pid == 0 {
+		// Child - stdin comes from the pipe
+		linux.close(fds[1])
+		linux.dup2(fds[0], linux.STDIN_FILENO)
+		linux.close(fds[0])
+
+		argv := make_argv(cmd_args, context.temp_allocator)
+		path := strings.clone_to_cstring(cmd_args[0], context.temp_allocator)
+
+		execvp(path, argv)
+		os.exit(127)
+	}
+
+	// Parent - write the input, then EOF
+	linux.close(fds[0])
+	linux.write(fds[1], transmute([]u8)input)
+	linux.close(fds[1])
+
+	status := child_wait(pid)
+
+	if (status & 0x7f) == 0 {
+		return int((status & 0xff00) >> 8)
+	}
+
+	return -1
+}
+
+// Run a command and return exit code. A command stopped with Ctrl-C returns
+// STATUS_INTERRUPTED; with every runner command_interrupted() reports whether
+// Ctrl-C was pressed while the command ran (see interrupt.odin).
 run_command :: proc(args: []string) -> int {
 	if len(args) == 0 {return 127}
+	cmd_args := command_argv_with_env(args, context.temp_allocator)
+
+	child_begin()
+	defer child_end()
 
 	pid, err := linux.fork()
 	if err != nil {
@@ -149,23 +293,31 @@ run_command :: proc(args: []string) -> int {
 
 	if pid == 0 {
 		// Child
-		argv := make_argv(args, context.temp_allocator)
-		path := strings.clone_to_cstring(args[0], context.temp_allocator)
+		argv := make_argv(cmd_args, context.temp_allocator)
+		path := strings.clone_to_cstring(cmd_args[0], context.temp_allocator)
 
 		execvp(path, argv)
 		os.exit(127)
 	}
 
 	// Parent
-	status: u32
-	linux.waitpid(pid, &status, {}, nil)
+	status := child_wait(pid)
 
 	// Decode status
 	if (status & 0x7f) == 0 {
-		return int((status & 0xff00) >> 8)
+		code := int((status & 0xff00) >> 8)
+		// xbps catches SIGINT itself and exits with a plain failure
+		if code != 0 && interrupt_seen {
+			return STATUS_INTERRUPTED
+		}
+		return code
 	}
 
-	return -1 // Terminated by signal
+	if int(status & 0x7f) == int(linux.Signal.SIGINT) {
+		interrupt_seen = true
+		return STATUS_INTERRUPTED
+	}
+	return -1 // Terminated by another signal
 }
 
 
@@ -196,6 +348,74 @@ is_valid_identifier :: proc(s: string) -> bool {
 	return true
 }
 
+// Validate a package name before it reaches template URLs or sudo xbps-*.
+// Same rules as is_valid_identifier plus '+' (gtk+3, libstdc++), and no
+// leading '-' so a name can't be taken as an option.
+is_valid_package_name :: proc(s: string) -> bool {
+	if len(s) == 0 || s[0] == '.' || s[0] == '-' {
+		return false
+	}
+
+	for c in s {
+		valid :=
+			(c >= 'a' && c <= 'z') ||
+			(c >= 'A' && c <= 'Z') ||
+			(c >= '0' && c <= '9') ||
+			c == '-' ||
+			c == '_' ||
+			c == '.' ||
+			c == '+'
+		if !valid {
+			return false
+		}
+	}
+
+	return !strings.contains(s, "..")
+}
+
+// Validate a git branch, tag or commit name used in template URLs
+is_valid_git_ref :: proc(s: string) -> bool {
+	if len(s) == 0 || s[0] == '-' || s[0] == '/' || s[len(s) - 1] == '/' {
+		return false
+	}
+
+	for c in s {
+		valid :=
+			(c >= 'a' && c <= 'z') ||
+			(c >= 'A' && c <= 'Z') ||
+			(c >= '0' && c <= '9') ||
+			c == '-' ||
+			c == '_' ||
+			c == '.' ||
+			c == '/'
+		if !valid {
+			return false
+		}
+	}
+
+	// Prevent escaping the repository path
+	if strings.contains(s, "..") || strings.contains(s, "//") {
+		return false
+	}
+
+	return true
+}
+
+// Validate a calendar date written as YYYY-MM-DD
+is_valid_date :: proc(s: string) -> bool {
+	if len(s) != 10 || s[4] != '-' || s[7] != '-' {
+		return false
+	}
+	for c, i in s {
+		if i != 4 && i != 7 && (c < '0' || c > '9') {
+			return false
+		}
+	}
+
+	month, day := parse_int(s[5:7]), parse_int(s[8:10])
+	return month >= 1 && month <= 12 && day >= 1 && day <= 31
+}
+
 // Create directory and all parents
 mkdir_p :: proc(path: string) -> bool {
 	if os.exists(path) {
@@ -204,11 +424,105 @@ mkdir_p :: proc(path: string) -> bool {
 	return os.make_directory_all(path) == nil
 }
 
+// Check that a file exists and is executable by us
+is_executable :: proc(path: string) -> bool {
+	X_OK :: 1
+	return access(strings.clone_to_cstring(path, context.temp_allocator), X_OK) == 0
+}
+
+// Check that a file exists and is readable by us
+is_readable :: proc(path: string) -> bool {
+	R_OK :: 4
+	return access(strings.clone_to_cstring(path, context.temp_allocator), R_OK) == 0
+}
+
+// Check whether a command can be found in $PATH
+command_exists :: proc(name: string) -> bool {
+	path_env := os.get_env("PATH", context.temp_allocator)
+	for dir in strings.split(path_env, ":", context.temp_allocator) {
+		if len(dir) > 0 && is_executable(path_join(dir, name, allocator = context.temp_allocator)) {
+			return true
+		}
+	}
+	return false
+}
+
+// Create directory (and parents) and check a file can actually be written there.
+// Catches read-only mounts where the directory exists but writes fail.
+dir_is_writable :: proc(path: string) -> bool {
+	if !mkdir_p(path) {
+		return false
+	}
+
+	probe := fmt.tprintf("%s/.vuru-probe-%d", path, linux.getpid())
+	if !write_file(probe, "") {
+		return false
+	}
+	os.remove(probe)
+	return true
+}
+
 // Join paths
 path_join :: proc(parts: ..string, allocator := context.allocator) -> string {
 	return strings.join(parts[:], "/", allocator)
 }
 
+// Expand a leading "~" and $VAR / ${VAR} references in a path, for paths
+// the shell didn't expand (quoted, or set in scripts). Unset variables expand
+// to nothing, as in the shell.
+expand_path :: proc(path: string, allocator := context.allocator) -> string {
+	b := strings.builder_make(allocator)
+
+	rest := path
+	if rest == "~" || strings.has_prefix(rest, "~/") {
+		strings.write_string(&b, os.get_env("HOME", context.temp_allocator))
+		rest = rest[1:]
+	}
+
+	i := 0
+	for i < len(rest) {
+		c := rest[i]
+		if c != '$' || i + 1 >= len(rest) {
+			strings.write_byte(&b, c)
+			i += 1
+			continue
+		}
+
+		// ${VAR}
+		if rest[i + 1] == '{' {
+			end := strings.index_byte(rest[i + 2:], '}')
+			if end < 0 {
+				strings.write_byte(&b, c)
+				i += 1
+				continue
+			}
+			strings.write_string(&b, os.get_env(rest[i + 2:i + 2 + end], context.temp_allocator))
+			i += end + 3
+			continue
+		}
+
+		// $VAR
+		j := i + 1
+		for j < len(rest) && is_var_char(rest[j]) {
+			j += 1
+		}
+		if j == i + 1 {
+			strings.write_byte(&b, c)
+			i += 1
+			continue
+		}
+		strings.write_string(&b, os.get_env(rest[i + 1:j], context.temp_allocator))
+		i = j
+	}
+
+	return strings.to_string(b)
+}
+
+@(private)
+is_var_char :: proc(c: u8) -> bool {
+	return (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || (c >= '0' && c <= '9') || c == '_'
+}
+
 // Helper functions extracted from template.odin to avoid cyclic deps and duplication
 
 strip_quotes :: proc(s: string) -> string {
@@ -249,6 +563,20 @@ parse_int :: proc(s: string) -> int {
 	return result
 }
 
+// Parse a count given as a flag value: digits only, unlike parse_int which
+// stops at the first non-digit ("4x" is not 4). At most 9 digits, no overflow.
+parse_count :: proc(s: string) -> (int, bool) {
+	if len(s) == 0 || len(s) > 9 {
+		return 0, false
+	}
+	for c in s {
+		if c < '0' || c > '9' {
+			return 0, false
+		}
+	}
+	return parse_int(s), true
+}
+
 int_to_string :: proc(n: int, allocator := context.allocator) -> string {
 	if n == 0 {
 		return strings.clone("0", allocator)
@@ -266,3 +594,80 @@ int_to_string :: proc(n: int, allocator := context.allocator) -> string {
 
 	return strings.clone(string(buf[i:]), allocator)
 }
+
+// Format a timestamp as "YYYY-MM-DD HH:MM:SS UTC"
+format_timestamp :: proc(t: time.Time, allocator := context.allocator) -> string {
+	year, month, day := time.date(t)
+	hour, minute, second := time.clock(t)
+
+	return fmt.aprintf(
+		"%04d-%02d-%02d %02d:%02d:%02d UTC",
+		year,
+		int(month),
+		day,
+		hour,
+		minute,
+		second,
+		allocator = allocator,
+	)
+}
+
+// Format a byte count for humans (e.g. "1.5 MiB")
+format_size :: proc(bytes: i64, allocator := context.allocator) -> string {
+	units := [?]string{"B", "KiB", "MiB", "GiB", "TiB"}
+
+	size := f64(bytes)
+	unit := 0
+	for size >= 1024 && unit < len(units) - 1 {
+		size /= 1024
+		unit += 1
+	}
+
+	if unit == 0 {
+		return fmt.aprintf("%d B", bytes, allocator = allocator)
+	}
+	return fmt.aprintf("%.1f %s", size, units[unit], allocator = allocator)
+}
+
+// Parse a byte count such as "500K", "20M", "1G" or "4096" (binary units,
+// case-insensitive, optional "B"/"iB": "20MiB"). False for anything else.
+parse_size :: proc(s: string) -> (i64, bool) {
+	digits := 0
+	for digits < len(s) && s[digits] >= '0' && s[digits] <= '9' {
+		digits += 1
+	}
+	if digits == 0 {
+		return 0, false
+	}
+
+	unit := strings.to_lower(s[digits:], context.temp_allocator)
+	unit = strings.trim_suffix(strings.trim_suffix(unit, "b"), "i")
+	shift: uint
+	switch unit {
+	case "":
+		shift = 0
+	case "k":
+		shift = 10
+	case "m":
+		shift = 20
+	case "g":
+		shift = 30
+	case:
+		return 0, false
+	}
+
+	// Refuse what doesn't fit rather than wrap: a negative budget would
+	// mean "unlimited"
+	n: i64 = 0
+	for c in s[:digits] {
+		d := i64(c - '0')
+		if n > (max(i64) - d) / 10 {
+			return 0, false
+		}
+		n = n * 10 + d
+	}
+	if n > max(i64) >> shift {
+		return 0, false
+	}
+	return n << shift, true
+}